@@ -0,0 +1,60 @@
+//! Builds a Circomspect binary with a custom analysis pass which flags `log`
+//! statements left in templates and functions.
+//!
+//! Run using `cargo run --example custom_pass -- path/to/circuit`.
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use circomspect::program_analysis::analysis_context::AnalysisContext;
+use circomspect::program_analysis::pass_registry::AnalysisPass;
+use circomspect::program_structure::cfg::Cfg;
+use circomspect::program_structure::ir::Statement;
+use circomspect::program_structure::report::{Report, ReportCollection};
+use circomspect::program_structure::report_code::ReportCode;
+
+const LOG_STATEMENT: ReportCode = ReportCode::Custom { id: "ORG001", name: "log-statement" };
+
+struct LogStatement;
+
+impl AnalysisPass for LogStatement {
+    fn id(&self) -> &str {
+        "log-statement"
+    }
+
+    fn name(&self) -> &str {
+        "Log statement"
+    }
+
+    fn description(&self) -> &str {
+        "Finds `log` statements, which should be removed before deployment."
+    }
+
+    fn report_codes(&self) -> &[ReportCode] {
+        &[LOG_STATEMENT]
+    }
+
+    fn run(&self, _: &dyn AnalysisContext, cfg: &Cfg) -> ReportCollection {
+        let mut reports = ReportCollection::new();
+        for basic_block in cfg.iter() {
+            for stmt in basic_block.iter() {
+                if let Statement::LogCall { meta, .. } = stmt {
+                    let mut report =
+                        Report::warning("Found `log` statement.".to_string(), LOG_STATEMENT);
+                    if let Some(file_id) = meta.file_id() {
+                        report.add_primary(
+                            meta.file_location(),
+                            file_id,
+                            "`log` called here.".to_string(),
+                        );
+                    }
+                    reports.push(report);
+                }
+            }
+        }
+        reports
+    }
+}
+
+fn main() -> ExitCode {
+    circomspect::run_cli(&[Arc::new(LogStatement)])
+}
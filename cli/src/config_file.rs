@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use log::debug;
+use serde::Deserialize;
+
+use program_analysis::analysis_runner::AnalysisRunner;
+use program_analysis::config::{self, AnalysisConfig};
+use program_analysis::pass_registry::{AnalysisPass, PassSelection};
+use program_analysis::query::QueryPass;
+use program_structure::constants::Curve;
+use program_structure::report::MessageCategory;
+
+use crate::FailOn;
+
+/// The name of the project configuration file.
+pub const CONFIG_FILE_NAME: &str = "circomspect.toml";
+
+/// A project configuration file. All values are optional. Values given on the
+/// command line override the corresponding values in the configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ConfigFile {
+    /// Library file paths, relative to the configuration file.
+    pub libraries: Vec<PathBuf>,
+    /// The curve (BN254, BLS12_381, or GOLDILOCKS).
+    pub curve: Option<String>,
+    /// The output level (INFO, WARNING, or ERROR).
+    pub level: Option<String>,
+    /// The minimum severity of results causing a non-zero exit code (INFO,
+    /// WARNING, ERROR, or NEVER).
+    pub fail_on: Option<String>,
+    /// The maximum recursion depth.
+    pub depth: Option<usize>,
+    /// Output analysis results to a Sarif file, relative to the configuration
+    /// file.
+    pub sarif_file: Option<PathBuf>,
+    /// Enable verbose output.
+    pub verbose: Option<bool>,
+    /// Cache analysis results in the given directory, relative to the
+    /// configuration file.
+    pub cache_dir: Option<PathBuf>,
+    /// Ignore results with the given report IDs.
+    pub allow: Vec<String>,
+    /// Pass selection and pass-specific settings.
+    pub passes: PassesSection,
+    /// Overrides for the severity of results, keyed by report ID or name.
+    pub severity: HashMap<String, String>,
+    /// Custom queries, which are run as additional analysis passes.
+    #[serde(rename = "query")]
+    pub queries: Vec<QuerySection>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PassesSection {
+    /// If non-empty, only the analysis passes with these IDs are run.
+    pub enable: Vec<String>,
+    /// The analysis passes with these IDs are not run.
+    pub disable: Vec<String>,
+    pub unused_output_signal: UnusedOutputSignalSection,
+    pub under_constrained_signal: UnderConstrainedSignalSection,
+    pub overly_complex_function_or_template: ComplexitySection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct UnusedOutputSignalSection {
+    /// Templates that may be instantiated without using their output signals.
+    pub allow_list: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct UnderConstrainedSignalSection {
+    /// The minimum number of constraints each intermediate signal should
+    /// occur in.
+    pub min_constraint_count: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ComplexitySection {
+    pub max_cyclomatic_complexity: Option<usize>,
+    pub max_parameters: Option<usize>,
+}
+
+/// A custom query reporting each match of the given pattern.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct QuerySection {
+    /// The report ID (e.g. `ORG001`).
+    pub id: String,
+    /// The pass ID and report name (e.g. `unchecked-less-than-input`).
+    pub name: String,
+    /// The query pattern.
+    pub pattern: String,
+    /// The message reported for each match.
+    pub message: String,
+    /// The severity of the report (INFO, WARNING, or ERROR) [default: WARNING].
+    pub severity: Option<String>,
+}
+
+impl ConfigFile {
+    /// Searches for a configuration file in the directories containing the
+    /// given input files, and then in each parent directory. Returns the first
+    /// configuration file found.
+    pub fn discover(input_files: &[PathBuf]) -> Option<PathBuf> {
+        for input_file in input_files {
+            let Ok(input_file) = input_file.canonicalize() else {
+                continue;
+            };
+            let start =
+                if input_file.is_dir() { Some(input_file.as_path()) } else { input_file.parent() };
+            for dir in start.into_iter().flat_map(Path::ancestors) {
+                let config_file = dir.join(CONFIG_FILE_NAME);
+                if config_file.is_file() {
+                    debug!("found configuration file `{}`", config_file.display());
+                    return Some(config_file);
+                }
+            }
+        }
+        None
+    }
+
+    /// Reads and parses the given configuration file. Relative paths in the
+    /// configuration file are resolved relative to the directory containing
+    /// the file.
+    pub fn load(config_file: &Path) -> Result<ConfigFile> {
+        let contents = fs::read_to_string(config_file)
+            .with_context(|| format!("failed to read `{}`", config_file.display()))?;
+        let mut config = ConfigFile::parse(&contents)
+            .with_context(|| format!("failed to parse `{}`", config_file.display()))?;
+        if let Some(base_dir) = config_file.parent() {
+            config.resolve_paths(base_dir);
+        }
+        Ok(config)
+    }
+
+    pub fn parse(contents: &str) -> Result<ConfigFile> {
+        let config: ConfigFile = toml::from_str(contents)?;
+        // Validate values eagerly to report errors before the analysis starts.
+        config.curve()?;
+        config.level()?;
+        config.fail_on()?;
+        config.severity_overrides()?;
+        config.query_passes()?;
+        Ok(config)
+    }
+
+    fn resolve_paths(&mut self, base_dir: &Path) {
+        for library in self.libraries.iter_mut() {
+            *library = base_dir.join(&library);
+        }
+        if let Some(sarif_file) = self.sarif_file.as_mut() {
+            *sarif_file = base_dir.join(&sarif_file);
+        }
+        if let Some(cache_dir) = self.cache_dir.as_mut() {
+            *cache_dir = base_dir.join(&cache_dir);
+        }
+    }
+
+    pub fn curve(&self) -> Result<Option<Curve>> {
+        self.curve.as_deref().map(str::parse).transpose()
+    }
+
+    pub fn level(&self) -> Result<Option<MessageCategory>> {
+        self.level.as_deref().map(str::parse).transpose()
+    }
+
+    pub fn fail_on(&self) -> Result<Option<FailOn>> {
+        self.fail_on
+            .as_deref()
+            .map(|fail_on| {
+                FailOn::from_str(fail_on, true)
+                    .map_err(|_| anyhow!("unknown fail-on severity '{fail_on}'"))
+            })
+            .transpose()
+    }
+
+    fn severity_overrides(&self) -> Result<HashMap<String, MessageCategory>> {
+        self.severity
+            .iter()
+            .map(|(code, level)| {
+                level
+                    .parse()
+                    .map(|level| (code.clone(), level))
+                    .map_err(|_| anyhow!("unknown severity '{level}' for '{code}'"))
+            })
+            .collect()
+    }
+
+    /// Returns the analysis passes selected by the configuration file.
+    pub fn pass_selection(&self) -> PassSelection {
+        PassSelection::new(&self.passes.enable, &self.passes.disable)
+    }
+
+    /// Returns the analysis configuration given by the configuration file.
+    /// (Pass IDs and report codes are validated separately using
+    /// `AnalysisConfig::validate`, since this requires the custom passes.)
+    pub fn analysis_config(&self) -> Result<AnalysisConfig> {
+        let mut config =
+            AnalysisConfig { pass_selection: self.pass_selection(), ..Default::default() };
+        let passes = &self.passes;
+        if let Some(allow_list) = &passes.unused_output_signal.allow_list {
+            config.unused_output_signal_allow_list = allow_list.clone();
+        }
+        if let Some(count) = passes.under_constrained_signal.min_constraint_count {
+            config.min_constraint_count = count;
+        }
+        let complexity = &passes.overly_complex_function_or_template;
+        config.max_cyclomatic_complexity = complexity.max_cyclomatic_complexity;
+        config.max_parameters = complexity.max_parameters;
+        config.severity_overrides = self.severity_overrides()?;
+        Ok(config)
+    }
+
+    /// Returns an analysis pass for each custom query.
+    pub fn query_passes(&self) -> Result<Vec<Arc<dyn AnalysisPass>>> {
+        self.queries
+            .iter()
+            .map(|query| {
+                let pattern = query
+                    .pattern
+                    .parse()
+                    .with_context(|| format!("invalid pattern for query '{}'", query.name))?;
+                let severity = match &query.severity {
+                    Some(severity) => severity.parse().map_err(|_| {
+                        anyhow!("unknown severity '{severity}' for '{}'", query.name)
+                    })?,
+                    None => MessageCategory::Warning,
+                };
+                let pass =
+                    QueryPass::new(&query.name, &query.id, pattern, &query.message, severity);
+                Ok(Arc::new(pass) as Arc<dyn AnalysisPass>)
+            })
+            .collect()
+    }
+}
+
+/// Creates an analysis runner for a subcommand, using the configuration file
+/// for the given input files (if any). Returns the runner together with the
+/// analysis depth.
+pub fn new_runner(
+    input_files: &[PathBuf],
+    libraries: &[PathBuf],
+    curve: Option<Curve>,
+) -> Result<(AnalysisRunner, usize)> {
+    let config_file = match ConfigFile::discover(input_files) {
+        Some(config_file) => ConfigFile::load(&config_file)?,
+        None => ConfigFile::default(),
+    };
+    let mut all_libraries = config_file.libraries.clone();
+    all_libraries.extend_from_slice(libraries);
+    let curve = match curve {
+        Some(curve) => curve,
+        None => config_file.curve()?.unwrap_or(config::DEFAULT_CURVE.parse()?),
+    };
+    let depth = match config_file.depth {
+        Some(depth) => depth,
+        None => config::DEFAULT_DEPTH.parse()?,
+    };
+    let runner = AnalysisRunner::new(curve)
+        .with_config(config_file.analysis_config()?)
+        .with_libraries(&all_libraries);
+    Ok((runner, depth))
+}
+
+#[cfg(test)]
+mod tests {
+    use program_analysis::pass_registry::analysis_passes;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_config_file() {
+        let config = ConfigFile::parse(
+            r#"
+            libraries = ["node_modules/circomlib/circuits"]
+            curve = "BLS12_381"
+            level = "info"
+            fail-on = "error"
+            allow = ["CS0005"]
+
+            [passes]
+            disable = ["field-element-arithmetic"]
+
+            [passes.unused-output-signal]
+            allow-list = ["Num2Bits", "MyGadget"]
+
+            [passes.under-constrained-signal]
+            min-constraint-count = 3
+
+            [severity]
+            CS0010 = "error"
+
+            [[query]]
+            id = "ORG001"
+            name = "less-than-input"
+            pattern = "$c = LessThan($n); $c.in[0] <== $x:input"
+            message = "LessThan input assigned from an input signal."
+            severity = "info"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.libraries, vec![PathBuf::from("node_modules/circomlib/circuits")]);
+        assert_eq!(config.curve().unwrap(), Some(Curve::Bls12_381));
+        assert_eq!(config.level().unwrap(), Some(MessageCategory::Info));
+        assert_eq!(config.fail_on().unwrap(), Some(FailOn::Error));
+        assert_eq!(config.depth, None);
+        assert_eq!(config.allow, vec!["CS0005"]);
+        assert_eq!(config.passes.disable, vec!["field-element-arithmetic"]);
+
+        let analysis_config = config.analysis_config().unwrap();
+        let passes = config.query_passes().unwrap();
+        assert!(analysis_config.validate(analysis_passes(&passes)).is_ok());
+        assert!(!analysis_config.pass_selection.is_selected(
+            analysis_passes(&[]).find(|pass| pass.id() == "field-element-arithmetic").unwrap()
+        ));
+        assert_eq!(analysis_config.unused_output_signal_allow_list, vec!["Num2Bits", "MyGadget"]);
+        assert_eq!(analysis_config.min_constraint_count, 3);
+        assert_eq!(analysis_config.max_parameters, None);
+        assert_eq!(analysis_config.severity_overrides.get("CS0010"), Some(&MessageCategory::Error));
+
+        let query_passes = config.query_passes().unwrap();
+        assert_eq!(query_passes.len(), 1);
+        assert_eq!(query_passes[0].id(), "less-than-input");
+        assert_eq!(query_passes[0].report_codes()[0].id(), "ORG001");
+        assert_eq!(query_passes[0].default_severity(), MessageCategory::Info);
+    }
+
+    #[test]
+    fn test_unknown_passes_and_results() {
+        let validate = |contents: &str| {
+            let config = ConfigFile::parse(contents).unwrap();
+            let passes = config.query_passes().unwrap();
+            config.analysis_config().unwrap().validate(analysis_passes(&passes))
+        };
+        // Passes are selected by pass ID, not by report ID.
+        assert!(validate("[passes]\nenable = [\"signal-assignment\"]").is_ok());
+        assert!(validate("[passes]\nenable = [\"CS0005\"]").is_err());
+        assert!(validate("[passes]\ndisable = [\"unknown-pass\"]").is_err());
+        // Severity overrides are keyed by report ID or name.
+        assert!(validate("[severity]\nsignal-assignment-statement = \"info\"").is_ok());
+        assert!(validate("[severity]\nCS9999 = \"info\"").is_err());
+        // Custom queries define both a pass ID and a report ID.
+        let query = "[[query]]\nid = \"ORG001\"\nname = \"org\"\nmessage = \"\"\npattern = \"$x\"";
+        assert!(validate(&format!(
+            "[passes]\ndisable = [\"org\"]\n[severity]\nORG001 = \"info\"\n{query}"
+        ))
+        .is_ok());
+    }
+
+    #[test]
+    fn test_invalid_config_file() {
+        assert!(ConfigFile::parse("curve = \"P256\"").is_err());
+        assert!(ConfigFile::parse("level = \"critical\"").is_err());
+        assert!(ConfigFile::parse("fail-on = \"always\"").is_err());
+        assert!(ConfigFile::parse("unknown-key = 1").is_err());
+        assert!(ConfigFile::parse("[severity]\nCS0005 = \"fatal\"").is_err());
+        let query = "[[query]]\nid = \"A\"\nname = \"a\"\nmessage = \"\"\npattern = ";
+        assert!(ConfigFile::parse(&format!("{query}\"$x <==\"")).is_err());
+    }
+
+    #[test]
+    fn test_resolve_paths() {
+        let mut config =
+            ConfigFile::parse("libraries = [\"lib\"]\nsarif-file = \"out.sarif\"").unwrap();
+        config.resolve_paths(Path::new("/project"));
+        assert_eq!(config.libraries, vec![PathBuf::from("/project/lib")]);
+        assert_eq!(config.sarif_file, Some(PathBuf::from("/project/out.sarif")));
+    }
+}
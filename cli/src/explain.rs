@@ -0,0 +1,106 @@
+//! Implements the `explain` subcommand, which prints a long-form explanation
+//! of a report code, together with an example and a fixed example.
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+
+use program_analysis::pass_registry::AnalysisPass;
+use program_structure::report_code::ReportCode;
+
+use crate::config_file::ConfigFile;
+use crate::{CommandError, CommandResult};
+
+/// The maximum line width used when printing descriptions.
+const LINE_WIDTH: usize = 80;
+
+/// Prints the explanation of the report code with the given ID or name.
+/// Report codes defined by custom passes (including custom queries from the
+/// configuration file in the current directory) are explained using the
+/// description of the corresponding pass.
+pub fn run(code: &str, custom_passes: &[Arc<dyn AnalysisPass>]) -> CommandResult {
+    let matches = |report_code: &ReportCode| {
+        report_code.id().eq_ignore_ascii_case(code) || report_code.name() == code
+    };
+    // Some report IDs are shared by multiple codes.
+    let builtin_codes =
+        ReportCode::builtin_codes().iter().filter(|report_code| matches(report_code));
+    let mut explanations = builtin_codes
+        .filter_map(|report_code| Some((report_code, report_code.explanation()?.to_text())))
+        .map(|(report_code, text)| format_explanation(report_code, &text))
+        .collect::<Vec<_>>();
+
+    if explanations.is_empty() {
+        let mut custom_passes = custom_passes.to_vec();
+        if let Some(config_file) = ConfigFile::discover(&[PathBuf::from(".")]) {
+            let query_passes = ConfigFile::load(&config_file)
+                .and_then(|config_file| config_file.query_passes())
+                .map_err(CommandError::InvalidOptions)?;
+            custom_passes.extend(query_passes);
+        }
+        for pass in &custom_passes {
+            for report_code in pass.report_codes().iter().filter(|report_code| matches(report_code))
+            {
+                explanations.push(format_explanation(report_code, pass.description()));
+            }
+        }
+    }
+    if explanations.is_empty() {
+        return Err(CommandError::InvalidOptions(anyhow!("unknown report code `{code}`")));
+    }
+    println!("{}", explanations.join("\n\n"));
+    Ok(())
+}
+
+fn format_explanation(code: &ReportCode, text: &str) -> String {
+    let mut lines = vec![format!("{} ({})", code.id(), code.name()), String::new()];
+    lines.extend(text.lines().map(|line| wrap(line, LINE_WIDTH)));
+    if let Some(url) = code.url() {
+        lines.push(String::new());
+        lines.push(format!("For more details, see {url}."));
+    }
+    lines.join("\n")
+}
+
+/// Wraps the given line of text at the given width. Indented lines (like
+/// code examples) are not wrapped.
+fn wrap(line: &str, width: usize) -> String {
+    if line.starts_with(' ') {
+        return line.to_string();
+    }
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in line.split_whitespace() {
+        if !current.is_empty() && current.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    lines.push(current);
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("a bb ccc dddd", 6), "a bb\nccc\ndddd");
+        assert_eq!(wrap("    a bb ccc dddd", 6), "    a bb ccc dddd");
+        assert_eq!(wrap("", 6), "");
+    }
+
+    #[test]
+    fn test_format_explanation() {
+        let code = ReportCode::UnconstrainedDivision;
+        let text = format_explanation(&code, &code.explanation().unwrap().to_text());
+        assert!(text.starts_with("CS0015 (unconstrained-division)\n\n"));
+        let is_wrapped = |line: &str| line.len() <= LINE_WIDTH || line.starts_with(' ');
+        assert!(text.lines().filter(|line| !line.contains("https://")).all(is_wrapped));
+        assert!(text.ends_with("analysis_passes.md#unconstrained-division."));
+    }
+}
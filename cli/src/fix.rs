@@ -0,0 +1,191 @@
+//! Implements `--fix` and `--fix-dry-run`, which apply the suggested fixes
+//! from the analysis results to the analyzed source files.
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use program_structure::file_definition::{FileID, FileLibrary};
+use program_structure::report::{Report, Suggestion};
+
+/// The fixes selected for a single file.
+pub struct FileFixes<'a> {
+    pub path: String,
+    source: &'a str,
+    /// Non-overlapping suggestions, sorted by location.
+    suggestions: Vec<&'a Suggestion>,
+}
+
+/// The fixes selected from a set of reports.
+pub struct Fixes<'a> {
+    pub files: Vec<FileFixes<'a>>,
+    /// The number of suggestions skipped because they overlap other
+    /// suggestions.
+    pub skipped: usize,
+}
+
+impl<'a> Fixes<'a> {
+    /// Selects the suggestions to apply from the given reports. Suggestions
+    /// for files which are not user provided input files are ignored, and
+    /// suggestions overlapping a previous suggestion are skipped.
+    pub fn new(reports: &'a [Report], file_library: &'a FileLibrary) -> Fixes<'a> {
+        let mut selected: Vec<&Suggestion> = Vec::new();
+        let mut skipped = 0;
+        let suggestions = reports
+            .iter()
+            .flat_map(|report| report.suggestions())
+            .filter(|suggestion| file_library.is_user_input(suggestion.file_id()));
+        for suggestion in suggestions {
+            if selected.contains(&suggestion) {
+                // The same fix may be suggested by multiple reports.
+                continue;
+            }
+            if selected.iter().any(|other| other.overlaps(suggestion)) {
+                skipped += 1;
+                continue;
+            }
+            selected.push(suggestion);
+        }
+
+        let mut files: BTreeMap<FileID, Vec<&Suggestion>> = BTreeMap::new();
+        for suggestion in selected {
+            files.entry(suggestion.file_id()).or_default().push(suggestion);
+        }
+        let storage = file_library.to_storage();
+        let files = files
+            .into_iter()
+            .filter_map(|(file_id, mut suggestions)| {
+                let file = storage.get(file_id).ok()?;
+                suggestions.sort_by_key(|suggestion| suggestion.location().start);
+                Some(FileFixes { path: file.name().clone(), source: file.source(), suggestions })
+            })
+            .collect();
+        Fixes { files, skipped }
+    }
+}
+
+impl FileFixes<'_> {
+    /// Returns the number of fixes in the file.
+    pub fn len(&self) -> usize {
+        self.suggestions.len()
+    }
+
+    /// Returns the source of the file with all fixes applied.
+    pub fn fixed_source(&self) -> String {
+        apply(self.source, 0, &self.suggestions)
+    }
+
+    /// Writes the fixed source back to the file.
+    pub fn write(&self) -> Result<()> {
+        fs::write(&self.path, self.fixed_source())
+            .with_context(|| format!("failed to write fixes to `{}`", self.path))
+    }
+
+    /// Returns the fixes as a unified diff (without context lines).
+    pub fn to_diff(&self) -> String {
+        let line_starts = line_starts(self.source);
+        let line_index = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+
+        // Group suggestions affecting the same lines into hunks.
+        let mut hunks: Vec<(usize, usize, Vec<&Suggestion>)> = Vec::new();
+        for &suggestion in &self.suggestions {
+            let location = suggestion.location();
+            let first_line = line_index(location.start);
+            let last_line = line_index(location.end.max(location.start + 1) - 1);
+            match hunks.last_mut() {
+                Some((_, last, suggestions)) if first_line <= *last => {
+                    *last = (*last).max(last_line);
+                    suggestions.push(suggestion);
+                }
+                _ => hunks.push((first_line, last_line, vec![suggestion])),
+            }
+        }
+
+        // Paths in the diff are relative to the current directory if possible.
+        let path = Path::new(&self.path);
+        let relative_path = env::current_dir()
+            .ok()
+            .and_then(|current_dir| Some(path.strip_prefix(current_dir).ok()?.display()));
+        let (old_path, new_path) = match relative_path {
+            Some(path) => (format!("a/{path}"), format!("b/{path}")),
+            None => (self.path.clone(), self.path.clone()),
+        };
+        let mut lines = vec![format!("--- {old_path}"), format!("+++ {new_path}")];
+        let mut line_offset = 0_isize;
+        for (first_line, last_line, suggestions) in hunks {
+            let start = line_starts[first_line];
+            let end = line_starts.get(last_line + 1).copied().unwrap_or(self.source.len());
+            let old_text = &self.source[start..end];
+            let new_text = apply(old_text, start, &suggestions);
+            let old_count = old_text.lines().count();
+            let new_count = new_text.lines().count();
+            let new_start = first_line as isize + 1 + line_offset;
+            line_offset += new_count as isize - old_count as isize;
+
+            lines.push(format!("@@ -{},{old_count} +{new_start},{new_count} @@", first_line + 1));
+            lines.extend(old_text.lines().map(|line| format!("-{line}")));
+            lines.extend(new_text.lines().map(|line| format!("+{line}")));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Applies the given (sorted, non-overlapping) suggestions to the given text,
+/// which starts at the given offset in the file.
+fn apply(text: &str, offset: usize, suggestions: &[&Suggestion]) -> String {
+    let mut result = String::new();
+    let mut current = 0;
+    for suggestion in suggestions {
+        let location = suggestion.location();
+        result.push_str(&text[current..location.start - offset]);
+        result.push_str(suggestion.replacement());
+        current = location.end - offset;
+    }
+    result.push_str(&text[current..]);
+    result
+}
+
+/// Returns the byte offsets of the start of each line in the given text.
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0).chain(text.match_indices('\n').map(|(offset, _)| offset + 1)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use program_structure::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_fixes() {
+        let source = "template T() {\n    a <-- b * c;\n    d <-- e * f;\n}\n";
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file("test.circom".to_string(), source.to_string(), true);
+        let report = |location: std::ops::Range<usize>| {
+            let mut report =
+                Report::warning("message".to_string(), ReportCode::UnnecessarySignalAssignment);
+            report.add_suggestion(location, file_id, "<==".to_string(), "fix".to_string());
+            report
+        };
+        let a = source.find("<--").unwrap();
+        let d = source.rfind("<--").unwrap();
+        let reports = vec![report(a..a + 3), report(d..d + 3), report(a..a + 3), report(a..a + 2)];
+
+        let fixes = Fixes::new(&reports, &file_library);
+        assert_eq!(fixes.files.len(), 1);
+        assert_eq!(fixes.files[0].len(), 2);
+        assert_eq!(fixes.skipped, 1);
+        assert_eq!(
+            fixes.files[0].fixed_source(),
+            "template T() {\n    a <== b * c;\n    d <== e * f;\n}\n"
+        );
+        assert_eq!(
+            fixes.files[0].to_diff(),
+            "--- test.circom\n+++ test.circom\n\
+            @@ -2,1 +2,1 @@\n-    a <-- b * c;\n+    a <== b * c;\n\
+            @@ -3,1 +3,1 @@\n-    d <-- e * f;\n+    d <== e * f;"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Implements the `inspect` subcommand, which prints the facts inferred by the
+//! analysis for the innermost IR node at a given source location.
+use anyhow::{anyhow, Context, Result};
+use codespan_reporting::files::Files;
+use std::path::{Path, PathBuf};
+
+use program_structure::constants::Curve;
+use program_structure::cfg::{DefinitionType, IrNode};
+use program_structure::file_definition::{FileID, FileLibrary};
+
+use crate::config_file;
+use crate::{is_input_error, CommandError, CommandResult};
+
+/// A source location on the form `PATH:LINE:COLUMN`. Lines and columns are
+/// one-based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::str::FromStr for SourceLocation {
+    type Err = anyhow::Error;
+
+    fn from_str(location: &str) -> Result<SourceLocation> {
+        let error = || anyhow!("invalid location `{location}` (expected `PATH:LINE:COLUMN`)");
+        let mut parts = location.rsplitn(3, ':');
+        let column = parts.next().and_then(|column| column.parse().ok()).ok_or_else(error)?;
+        let line = parts.next().and_then(|line| line.parse().ok()).ok_or_else(error)?;
+        let path = parts.next().filter(|path| !path.is_empty()).ok_or_else(error)?;
+        if line == 0 || column == 0 {
+            return Err(error());
+        }
+        Ok(SourceLocation { path: PathBuf::from(path), line, column })
+    }
+}
+
+/// Analyzes the file containing the given location, and prints the facts
+/// inferred for the innermost IR node at the location.
+pub fn run(
+    location: &SourceLocation,
+    libraries: &[PathBuf],
+    curve: Option<Curve>,
+) -> CommandResult {
+    let input_files = [location.path.clone()];
+    let (runner, depth) = config_file::new_runner(&input_files, libraries, curve)
+        .map_err(CommandError::InvalidOptions)?;
+    let (runner, reports) = runner.with_files(&input_files, depth);
+    let file_id = find_file(runner.file_library(), &location.path).map_err(CommandError::Input)?;
+    let offset = to_offset(runner.file_library(), file_id, location)
+        .map_err(CommandError::InvalidOptions)?;
+    let Some(cfg) = runner.cfg_at(file_id, offset) else {
+        // If the file could not be parsed, the location is not the problem.
+        if reports.iter().any(is_input_error) {
+            return Err(CommandError::Input(anyhow!("failed to parse `{}`", display(location))));
+        }
+        return Err(CommandError::InvalidOptions(anyhow!(
+            "no template or function found at `{}`",
+            display(location)
+        )));
+    };
+    let definition_type = match cfg.definition_type() {
+        DefinitionType::Function => "function",
+        DefinitionType::Template => "template",
+        DefinitionType::CustomTemplate => "custom template",
+    };
+    println!("definition: {definition_type} `{}`", cfg.name());
+    match cfg.find_node(file_id, offset) {
+        Some(node) => print_node(&node),
+        None => println!("no statement or expression found at `{}`", display(location)),
+    }
+    Ok(())
+}
+
+fn print_node(node: &IrNode) {
+    let kind = match node {
+        IrNode::Statement(_) => "statement",
+        IrNode::Expression(_) => "expression",
+    };
+    println!("{kind}: {node}");
+    if let Some(name) = node.variable_name() {
+        println!("variable: {name:?}");
+    }
+    if let Some(var_type) = node.variable_type() {
+        println!("type: {var_type}");
+    }
+    match node.value() {
+        Some(value) => println!("value: {value}"),
+        None => println!("value: unknown"),
+    }
+    match node.degree() {
+        Some(degree) => println!("degree: {degree:?}"),
+        None => println!("degree: unknown"),
+    }
+    match node.range() {
+        Some(range) => println!("range: {range}"),
+        None => println!("range: unknown"),
+    }
+}
+
+/// Returns the file ID of the given (user provided) input file.
+fn find_file(file_library: &FileLibrary, path: &Path) -> Result<FileID> {
+    let path =
+        path.canonicalize().with_context(|| format!("failed to open `{}`", path.display()))?;
+    let files = file_library.to_storage();
+    file_library
+        .user_inputs()
+        .iter()
+        .copied()
+        .find(|file_id| {
+            let name = files.name(*file_id).map(PathBuf::from);
+            name.and_then(|name| Ok(name.canonicalize()?))
+                .map(|name| name == path)
+                .unwrap_or_default()
+        })
+        .ok_or_else(|| anyhow!("failed to parse `{}`", path.display()))
+}
+
+/// Converts the given (one-based) line and column into a byte offset.
+fn to_offset(
+    file_library: &FileLibrary,
+    file_id: FileID,
+    location: &SourceLocation,
+) -> Result<usize> {
+    let files = file_library.to_storage();
+    let source = files.source(file_id)?;
+    let line_range = files
+        .line_range(file_id, location.line - 1)
+        .map_err(|_| anyhow!("invalid line number {}", location.line))?;
+    let line = &source[line_range.clone()];
+    let column = line
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(line.len()))
+        .nth(location.column - 1)
+        .ok_or_else(|| anyhow!("invalid column number {}", location.column))?;
+    Ok(line_range.start + column)
+}
+
+fn display(location: &SourceLocation) -> String {
+    format!("{}:{}:{}", location.path.display(), location.line, location.column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_location() {
+        let location: SourceLocation = "dir/file.circom:12:5".parse().unwrap();
+        assert_eq!(location.path, PathBuf::from("dir/file.circom"));
+        assert_eq!((location.line, location.column), (12, 5));
+
+        assert!("file.circom:12".parse::<SourceLocation>().is_err());
+        assert!("file.circom:0:1".parse::<SourceLocation>().is_err());
+        assert!(":1:1".parse::<SourceLocation>().is_err());
+    }
+}
@@ -0,0 +1,1049 @@
+//! The Circomspect command line interface.
+//!
+//! The CLI is exposed as a library to allow downstream crates to build their
+//! own Circomspect binary with additional (e.g. organization-specific)
+//! analysis passes. Custom passes implement
+//! [`AnalysisPass`](program_analysis::pass_registry::AnalysisPass) and are
+//! passed to [`run_cli`]. See `examples/custom_pass.rs` for an example.
+use std::collections::HashSet;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use log::error;
+
+mod config_file;
+mod explain;
+mod fix;
+mod inspect;
+mod lsp;
+mod query;
+mod watch;
+
+use config_file::ConfigFile;
+use fix::Fixes;
+use program_analysis::config;
+use program_analysis::analysis_runner::AnalysisRunner;
+use program_analysis::pass_registry::{analysis_passes, AnalysisPass};
+use program_analysis::result_cache::ResultCache;
+
+use program_structure::constants::Curve;
+use program_structure::file_definition::FileID;
+use program_structure::report::Report;
+use program_structure::report::MessageCategory;
+use program_structure::baseline::Baseline;
+use program_structure::diff::Diff;
+use program_structure::xml_conversion::TestSuite;
+// Re-exported to ensure that custom passes are built against the same versions
+// of the analysis crates as the CLI.
+pub use program_analysis;
+pub use program_structure;
+
+use program_structure::writers::{
+    BaselineFilter, BaselineWriter, CachedWriter, CheckstyleWriter, DiffFilter, GitHubWriter,
+    HtmlWriter, JUnitWriter, JsonLinesWriter, JsonWriter, LogWriter, ReportFilter, ReportWriter,
+    SarifWriter, ShortWriter, StderrLogWriter, StdoutWriter, SuppressionFilter,
+};
+
+/// Exit code used if any results at or above the `--fail-on` level are found.
+const EXIT_FINDINGS: u8 = 1;
+/// Exit code used if the command line options or the configuration file are
+/// invalid. (This is also the exit code used by `clap` for usage errors.)
+const EXIT_INVALID_OPTIONS: u8 = 2;
+/// Exit code used if a file could not be parsed, or if a template or function
+/// could not be analyzed. This takes precedence over `EXIT_FINDINGS`.
+const EXIT_INPUT_ERROR: u8 = 3;
+/// Exit code used for all other errors (e.g. failing to write output files).
+const EXIT_INTERNAL_ERROR: u8 = 4;
+
+/// An error returned by a subcommand. The variant determines the exit code.
+#[derive(Debug)]
+enum CommandError {
+    /// Invalid arguments or configuration file (exits with `EXIT_INVALID_OPTIONS`).
+    InvalidOptions(anyhow::Error),
+    /// The input could not be parsed or analyzed (exits with `EXIT_INPUT_ERROR`).
+    Input(anyhow::Error),
+    /// Any other error (exits with `EXIT_INTERNAL_ERROR`).
+    Internal(anyhow::Error),
+}
+
+impl CommandError {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            CommandError::InvalidOptions(_) => ExitCode::from(EXIT_INVALID_OPTIONS),
+            CommandError::Input(_) => ExitCode::from(EXIT_INPUT_ERROR),
+            CommandError::Internal(_) => ExitCode::from(EXIT_INTERNAL_ERROR),
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::InvalidOptions(err)
+            | CommandError::Input(err)
+            | CommandError::Internal(err) => write!(f, "{err:#}"),
+        }
+    }
+}
+
+/// The result of running a subcommand.
+type CommandResult = Result<(), CommandError>;
+
+/// The minimum severity of results causing Circomspect to exit with a
+/// non-zero exit code.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum FailOn {
+    /// Fail if any errors are found
+    Error,
+    /// Fail if any warnings or errors are found
+    Warning,
+    /// Fail if any results are found
+    #[default]
+    Info,
+    /// Never fail because of results (parse errors still cause a failure)
+    Never,
+}
+
+impl FailOn {
+    /// Returns true if the given report should cause a failure.
+    fn is_failure(&self, report: &Report) -> bool {
+        let threshold = match self {
+            FailOn::Error => MessageCategory::Error,
+            FailOn::Warning => MessageCategory::Warning,
+            FailOn::Info => MessageCategory::Info,
+            FailOn::Never => return false,
+        };
+        report.category() >= &threshold
+    }
+}
+
+/// The scope used to decide if a result is part of a diff.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum DiffScope {
+    /// Report results overlapping added or modified lines
+    #[default]
+    Lines,
+    /// Report all results from templates and functions with added or modified
+    /// lines
+    Definitions,
+}
+
+/// The format used when writing results to `stdout`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human readable diagnostics
+    #[default]
+    Text,
+    /// One line per result on the form `path:line:column: severity[ID]: message`
+    Short,
+    /// A single JSON array containing all results
+    Json,
+    /// One JSON object per line, written as results are found
+    Jsonl,
+    /// A JUnit XML document with one test case per template or function
+    Junit,
+    /// A Checkstyle XML document
+    Checkstyle,
+    /// GitHub Actions workflow commands, written as results are found
+    Github,
+    /// A self-contained HTML report
+    Html,
+}
+
+#[derive(Parser, Debug)]
+#[command(styles=cli_styles())]
+#[command(args_conflicts_with_subcommands = true)]
+/// A static analyzer and linter for Circom programs.
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Initial input file(s)
+    #[clap(name = "INPUT")]
+    input_files: Vec<PathBuf>,
+
+    /// Library file paths
+    #[clap(short = 'L', long = "library", name = "LIBRARIES")]
+    libraries: Vec<PathBuf>,
+
+    /// Output level (INFO, WARNING, or ERROR) [default: WARNING]
+    #[clap(short = 'l', long = "level", name = "LEVEL")]
+    output_level: Option<MessageCategory>,
+
+    /// Exit with a non-zero exit code if any displayed results have at least
+    /// the given severity [default: info]
+    #[clap(long = "fail-on", name = "SEVERITY", value_enum)]
+    fail_on: Option<FailOn>,
+
+    /// Output format used for results written to stdout
+    #[clap(
+        long = "output-format",
+        visible_alias = "format",
+        name = "FORMAT",
+        value_enum,
+        default_value = "text"
+    )]
+    output_format: OutputFormat,
+
+    /// Output analysis results to a Sarif file
+    #[clap(short, long, name = "OUTPUT")]
+    sarif_file: Option<PathBuf>,
+
+    /// Only report results which are not contained in the given baseline file
+    #[clap(long = "baseline", name = "BASELINE")]
+    baseline_file: Option<PathBuf>,
+
+    /// Write all current results to the given baseline file
+    #[clap(long = "write-baseline", name = "NEW_BASELINE")]
+    write_baseline_file: Option<PathBuf>,
+
+    /// Only report results overlapping lines added or modified by the given
+    /// unified diff (use `-` to read the diff from stdin)
+    #[clap(long = "diff", name = "DIFF")]
+    diff_file: Option<PathBuf>,
+
+    /// Report results from the changed lines, or from the changed templates
+    /// and functions, when `--diff` is given
+    #[clap(long = "diff-scope", name = "SCOPE", value_enum, default_value = "lines")]
+    diff_scope: DiffScope,
+
+    /// Apply suggested fixes to the analyzed files
+    #[clap(long = "fix", conflicts_with_all = ["fix_dry_run", "watch"])]
+    fix: bool,
+
+    /// Print suggested fixes as a unified diff without applying them (only
+    /// supported by the text output format)
+    #[clap(long = "fix-dry-run", conflicts_with = "watch")]
+    fix_dry_run: bool,
+
+    /// Ignore results from given analysis passes
+    #[clap(short = 'a', long = "allow", name = "ID")]
+    allow_list: Vec<String>,
+
+    /// Only run the given analysis passes (by pass ID). This replaces the
+    /// `enable` list from the configuration file
+    #[clap(long = "only", name = "ONLY", value_name = "PASS_ID,...", value_delimiter = ',')]
+    only_passes: Vec<String>,
+
+    /// Do not run the given analysis passes (by pass ID), in addition to the
+    /// passes disabled by the configuration file
+    #[clap(long = "skip", name = "SKIP", value_name = "PASS_ID,...", value_delimiter = ',')]
+    skip_passes: Vec<String>,
+
+    /// List all analysis passes and exit
+    #[clap(long = "list-passes")]
+    list_passes: bool,
+
+    /// Enable verbose output
+    #[clap(short = 'v', long = "verbose")]
+    verbose: bool,
+
+    /// Set curve (BN254, BLS12_381, or GOLDILOCKS) [default: BN254]
+    #[clap(short = 'c', long = "curve", name = "NAME")]
+    curve: Option<Curve>,
+
+    /// Maximum recursion depth [default: 1000]
+    #[clap(short = 'd', long = "depth", name = "DEPTH")]
+    depth: Option<usize>,
+
+    /// Read options from the given configuration file (by default,
+    /// `circomspect.toml` is searched for in the input file directories and
+    /// their parents)
+    #[clap(long = "config", name = "CONFIG", conflicts_with = "no_config")]
+    config_file: Option<PathBuf>,
+
+    /// Do not read options from a configuration file
+    #[clap(long = "no-config")]
+    no_config: bool,
+
+    /// Cache analysis results in the given directory, and skip templates and
+    /// functions with cached results
+    #[clap(long = "cache-dir", name = "CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// Re-run the analysis whenever an analyzed file changes
+    #[clap(short = 'w', long = "watch")]
+    watch: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Start a language server providing diagnostics over stdio
+    Lsp,
+    /// Print the facts inferred by the analysis for the innermost statement
+    /// or expression at the given location
+    Inspect {
+        /// Source location on the form PATH:LINE:COLUMN
+        #[clap(name = "LOCATION")]
+        location: inspect::SourceLocation,
+
+        /// Library file paths
+        #[clap(short = 'L', long = "library", name = "LIBRARIES")]
+        libraries: Vec<PathBuf>,
+
+        /// Set curve (BN254, BLS12_381, or GOLDILOCKS) [default: BN254]
+        #[clap(short = 'c', long = "curve", name = "NAME")]
+        curve: Option<Curve>,
+    },
+    /// Print a detailed explanation of a report code, with an example and a
+    /// fixed example
+    Explain {
+        /// The report ID (e.g. CS0005) or name (e.g. signal-assignment-statement)
+        #[clap(name = "CODE")]
+        code: String,
+    },
+    /// Print all matches of a structural pattern (e.g. `$c.in[0] <== $x:input`)
+    /// in the given input files
+    Query {
+        /// The pattern to search for
+        #[clap(name = "PATTERN")]
+        pattern: String,
+
+        /// Input file(s)
+        #[clap(name = "INPUT", required = true)]
+        input_files: Vec<PathBuf>,
+
+        /// Library file paths
+        #[clap(short = 'L', long = "library", name = "LIBRARIES")]
+        libraries: Vec<PathBuf>,
+
+        /// Set curve (BN254, BLS12_381, or GOLDILOCKS) [default: BN254]
+        #[clap(short = 'c', long = "curve", name = "NAME")]
+        curve: Option<Curve>,
+    },
+}
+
+/// Options obtained by merging the command line options with the options from
+/// the configuration file. Command line options take precedence.
+struct Options {
+    input_files: Vec<PathBuf>,
+    libraries: Vec<PathBuf>,
+    output_level: MessageCategory,
+    fail_on: FailOn,
+    output_format: OutputFormat,
+    sarif_file: Option<PathBuf>,
+    baseline_file: Option<PathBuf>,
+    write_baseline_file: Option<PathBuf>,
+    diff: Option<Diff>,
+    diff_scope: DiffScope,
+    fix: bool,
+    fix_dry_run: bool,
+    allow_list: Vec<String>,
+    verbose: bool,
+    curve: Curve,
+    depth: usize,
+    analysis_config: config::AnalysisConfig,
+    custom_passes: Vec<Arc<dyn AnalysisPass>>,
+    cache_dir: Option<PathBuf>,
+    watch: bool,
+}
+
+impl Options {
+    fn new(cli: Cli, custom_passes: &[Arc<dyn AnalysisPass>]) -> anyhow::Result<Options> {
+        // The diff is written to `stdout`, and would corrupt machine readable
+        // output.
+        if cli.fix_dry_run && cli.output_format != OutputFormat::Text {
+            anyhow::bail!("`--fix-dry-run` can only be used with the `text` output format");
+        }
+        let config_file = if cli.no_config {
+            None
+        } else {
+            cli.config_file.clone().or_else(|| ConfigFile::discover(&cli.input_files))
+        };
+        let config_file = match config_file {
+            Some(path) => ConfigFile::load(&path)?,
+            None => ConfigFile::default(),
+        };
+
+        let mut libraries = config_file.libraries.clone();
+        libraries.extend(cli.libraries);
+        let mut allow_list = config_file.allow.clone();
+        allow_list.extend(cli.allow_list);
+        let output_level = match cli.output_level {
+            Some(output_level) => output_level,
+            None => config_file.level()?.unwrap_or(config::DEFAULT_LEVEL.parse()?),
+        };
+        let fail_on = match cli.fail_on {
+            Some(fail_on) => fail_on,
+            None => config_file.fail_on()?.unwrap_or_default(),
+        };
+        let curve = match cli.curve {
+            Some(curve) => curve,
+            None => config_file.curve()?.unwrap_or(config::DEFAULT_CURVE.parse()?),
+        };
+        let depth = match cli.depth.or(config_file.depth) {
+            Some(depth) => depth,
+            None => config::DEFAULT_DEPTH.parse()?,
+        };
+        let mut custom_passes = custom_passes.to_vec();
+        custom_passes.extend(config_file.query_passes()?);
+        let mut analysis_config = config_file.analysis_config()?;
+        analysis_config.pass_selection =
+            analysis_config.pass_selection.with_overrides(&cli.only_passes, &cli.skip_passes);
+        analysis_config.validate(analysis_passes(&custom_passes))?;
+        Ok(Options {
+            input_files: cli.input_files,
+            libraries,
+            output_level,
+            fail_on,
+            output_format: cli.output_format,
+            sarif_file: cli.sarif_file.or_else(|| config_file.sarif_file.clone()),
+            baseline_file: cli.baseline_file,
+            write_baseline_file: cli.write_baseline_file,
+            diff: cli.diff_file.as_deref().map(Diff::read).transpose()?,
+            diff_scope: cli.diff_scope,
+            fix: cli.fix,
+            fix_dry_run: cli.fix_dry_run,
+            allow_list,
+            verbose: cli.verbose || config_file.verbose.unwrap_or_default(),
+            curve,
+            depth,
+            analysis_config,
+            custom_passes,
+            cache_dir: cli.cache_dir.or_else(|| config_file.cache_dir.clone()),
+            watch: cli.watch,
+        })
+    }
+}
+
+/// Styles the help output for the [`Cli`].
+fn cli_styles() -> clap::builder::Styles {
+    use clap::builder::styling::*;
+
+    Styles::styled()
+        .header(AnsiColor::Yellow.on_default())
+        .usage(AnsiColor::Green.on_default())
+        .literal(AnsiColor::Green.on_default())
+        .placeholder(AnsiColor::Green.on_default())
+}
+
+/// Returns true if a primary location of the report corresponds to a file
+/// specified on the command line by the user.
+fn filter_by_file(report: &Report, user_inputs: &HashSet<FileID>) -> bool {
+    report.primary_file_ids().iter().any(|file_id| user_inputs.contains(file_id))
+}
+
+/// Returns true if the report level is greater than or equal to the given
+/// level.
+fn filter_by_level(report: &Report, output_level: &MessageCategory) -> bool {
+    report.category() >= output_level
+}
+
+/// Returns true if the report ID is not in the given list.
+fn filter_by_id(report: &Report, allow_list: &[String]) -> bool {
+    !allow_list.contains(&report.id())
+}
+
+/// Returns true if the report is a parse error (or an error lifting a template
+/// or function).
+fn is_input_error(report: &Report) -> bool {
+    report.category() == &MessageCategory::Error && report.code().is_compiler_code()
+}
+
+/// Returns the exit code of an analysis run, given all reports and the reports
+/// that were written. Parse errors (and errors lifting templates and functions)
+/// are taken into account even if they are filtered out, since they mean that
+/// the input could not be fully analyzed.
+fn exit_code(all_reports: &[Report], written_reports: &[Report], fail_on: FailOn) -> ExitCode {
+    if all_reports.iter().any(is_input_error) {
+        ExitCode::from(EXIT_INPUT_ERROR)
+    } else if written_reports.iter().any(|report| fail_on.is_failure(report)) {
+        ExitCode::from(EXIT_FINDINGS)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Prints the ID, severity, scope, and emitted result IDs of each analysis
+/// pass, together with a short description.
+fn list_passes(custom_passes: &[Arc<dyn AnalysisPass>]) {
+    for pass in analysis_passes(custom_passes) {
+        let report_ids =
+            pass.report_codes().iter().map(|code| code.id()).collect::<Vec<_>>().join(", ");
+        println!("{} ({}, {}): {}", pass.id(), pass.default_severity(), pass.scope(), pass.name());
+        println!("    {}", pass.description());
+        println!("    Results: {report_ids}");
+    }
+}
+
+/// Runs the given subcommand.
+fn run_command(command: Command, custom_passes: &[Arc<dyn AnalysisPass>]) -> CommandResult {
+    match command {
+        Command::Lsp => lsp::run(custom_passes),
+        Command::Inspect { location, libraries, curve } => {
+            inspect::run(&location, &libraries, curve)
+        }
+        Command::Explain { code } => explain::run(&code, custom_passes),
+        Command::Query { pattern, input_files, libraries, curve } => {
+            query::run(&pattern, &input_files, &libraries, curve)
+        }
+    }
+}
+
+/// Parses the command line arguments and runs Circomspect. The given custom
+/// analysis passes are run in addition to the built-in passes.
+pub fn run_cli(custom_passes: &[Arc<dyn AnalysisPass>]) -> ExitCode {
+    // Initialize logger and options.
+    pretty_env_logger::init();
+    let cli = Cli::parse();
+    if let Some(command) = cli.command {
+        return match run_command(command, custom_passes) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                error!("{err}");
+                err.exit_code()
+            }
+        };
+    }
+    if cli.input_files.is_empty() && !cli.list_passes {
+        match Cli::command().print_help() {
+            Ok(()) => return ExitCode::SUCCESS,
+            Err(_) => return ExitCode::from(EXIT_INTERNAL_ERROR),
+        }
+    }
+    let list_passes_only = cli.list_passes;
+    let options = match Options::new(cli, custom_passes) {
+        Ok(options) => options,
+        Err(err) => {
+            error!("{err:#}");
+            return ExitCode::from(EXIT_INVALID_OPTIONS);
+        }
+    };
+    // Custom queries from the configuration file are listed as well.
+    if list_passes_only {
+        list_passes(&options.custom_passes);
+        return ExitCode::SUCCESS;
+    }
+    let baseline = match options.baseline_file.as_deref().map(Baseline::read).transpose() {
+        Ok(baseline) => baseline,
+        Err(err) => {
+            error!("{err:#}");
+            return ExitCode::from(EXIT_INVALID_OPTIONS);
+        }
+    };
+
+    // Results are written to `stdout` while the analysis is running, except
+    // for document formats (like JSON) which are written once the analysis is
+    // complete. Log messages are written to `stderr` for machine readable
+    // output formats.
+    match options.output_format {
+        OutputFormat::Text => {
+            let verbose = options.verbose;
+            run(options, baseline, || StdoutWriter::new(verbose))
+        }
+        OutputFormat::Json
+        | OutputFormat::Junit
+        | OutputFormat::Checkstyle
+        | OutputFormat::Html => run(options, baseline, StderrLogWriter::new),
+        OutputFormat::Short => {
+            let verbose = options.verbose;
+            run(options, baseline, || ShortWriter::new(io::stdout(), verbose))
+        }
+        OutputFormat::Jsonl => run(options, baseline, || JsonLinesWriter::new(io::stdout())),
+        OutputFormat::Github => run(options, baseline, || GitHubWriter::new(io::stdout())),
+    }
+}
+
+/// Creates a filter dropping results outside the given diff. If the diff
+/// scope is `definitions`, results from templates and functions overlapping
+/// the diff are kept as well.
+fn diff_filter(diff: &Diff, options: &Options, runner: &AnalysisRunner) -> DiffFilter {
+    let diff_filter = DiffFilter::new(diff);
+    match options.diff_scope {
+        DiffScope::Lines => diff_filter,
+        DiffScope::Definitions => {
+            let mut names = runner.template_names(false);
+            names.extend(runner.function_names(false));
+            let changed_definitions = names
+                .into_iter()
+                .filter(|name| match runner.definition_location(name) {
+                    Some((file_id, location)) => {
+                        diff_filter.is_changed(file_id, &location, runner.file_library())
+                    }
+                    None => false,
+                })
+                .collect::<Vec<_>>();
+            diff_filter.with_definitions(changed_definitions)
+        }
+    }
+}
+
+/// Runs the analysis once, or repeatedly if `--watch` is given. A new writer
+/// is created for each run.
+fn run<W, F>(options: Options, baseline: Option<Baseline>, new_writer: F) -> ExitCode
+where
+    W: LogWriter + ReportWriter,
+    F: Fn() -> W,
+{
+    if options.watch {
+        watch::run(&options, baseline.as_ref(), new_writer)
+    } else {
+        analyze(&options, baseline.as_ref(), new_writer(), None).1
+    }
+}
+
+/// Runs the analysis and writes all results using the given writer. If the
+/// runner from a previous run is given, results for unchanged templates and
+/// functions are reused. Returns the runner together with the exit code.
+fn analyze<W: LogWriter + ReportWriter>(
+    options: &Options,
+    baseline: Option<&Baseline>,
+    writer: W,
+    previous_run: Option<AnalysisRunner>,
+) -> (AnalysisRunner, ExitCode) {
+    // Set up analysis runner.
+    let mut runner = AnalysisRunner::new(options.curve.clone())
+        .with_config(options.analysis_config.clone())
+        .with_passes(&options.custom_passes)
+        .with_libraries(&options.libraries);
+    if let Some(cache_dir) = &options.cache_dir {
+        match ResultCache::open(cache_dir) {
+            Ok(result_cache) => runner = runner.with_result_cache(result_cache),
+            Err(err) => error!("{err:#}"),
+        }
+    }
+    if let Some(previous_run) = previous_run {
+        runner = runner.with_previous_run(previous_run);
+    }
+    let (mut runner, reports) = runner.with_files(&options.input_files, options.depth);
+    let output_level = options.output_level;
+
+    // Set up writer and write reports to `stdout`. The suppression filter is
+    // added first to ensure that it sees all reports. Otherwise, suppressions
+    // could be incorrectly reported as unused.
+    let suppression_filter = SuppressionFilter::new(runner.file_library());
+    let allow_list = options.allow_list.clone();
+    let mut stdout_writer = CachedWriter::new(writer)
+        .add_filter(suppression_filter.clone())
+        .add_filter(move |report: &Report| filter_by_level(report, &output_level))
+        .add_filter(move |report: &Report| filter_by_id(report, &allow_list));
+    // The baseline filter is added last to ensure that baseline entries are
+    // only matched against reports that would otherwise be written.
+    let baseline_filter = baseline.map(BaselineFilter::new);
+    if let Some(baseline_filter) = &baseline_filter {
+        stdout_writer = stdout_writer.add_filter(baseline_filter.clone());
+    }
+    // The diff filter is added after the baseline filter, since results
+    // outside the diff still correspond to baseline entries.
+    let diff_filter = options.diff.as_ref().map(|diff| diff_filter(diff, options, &runner));
+    if let Some(diff_filter) = &diff_filter {
+        stdout_writer = stdout_writer.add_filter(diff_filter.clone());
+    }
+    stdout_writer.write_reports(&reports, runner.file_library());
+
+    // Analyze functions and templates in user provided input files.
+    runner.analyze_functions(&mut stdout_writer, false);
+    runner.analyze_templates(&mut stdout_writer, false);
+
+    // Report inline suppressions that did not suppress any reports.
+    let unused_suppressions = suppression_filter.unused_suppressions(runner.file_library());
+    stdout_writer.write_reports(&unused_suppressions, runner.file_library());
+
+    // List baseline entries which no longer correspond to any reports.
+    if let Some(baseline_filter) = &baseline_filter {
+        let disappeared_entries = baseline_filter.disappeared_entries();
+        if !disappeared_entries.is_empty() {
+            stdout_writer.write_message(format!(
+                "{} baseline {} no longer found:",
+                disappeared_entries.len(),
+                if disappeared_entries.len() == 1 { "entry is" } else { "entries are" }
+            ));
+            for entry in disappeared_entries {
+                let fingerprint = &entry.fingerprint;
+                let definition = fingerprint.definition.as_deref().unwrap_or("<unknown>");
+                stdout_writer.write_message(format!(
+                    "  {} in `{definition}`: `{}`",
+                    fingerprint.code, fingerprint.snippet
+                ));
+            }
+        }
+    }
+
+    // Document formats are written once all results are known.
+    let reports = stdout_writer.written_reports();
+    match options.output_format {
+        OutputFormat::Json => {
+            JsonWriter::new(io::stdout()).write_reports(reports, runner.file_library());
+        }
+        OutputFormat::Junit => {
+            let mut templates = runner.template_names(true);
+            let mut functions = runner.function_names(true);
+            templates.sort();
+            functions.sort();
+            JUnitWriter::new(io::stdout())
+                .add_test_suite(TestSuite::new("templates", &templates))
+                .add_test_suite(TestSuite::new("functions", &functions))
+                .write_reports(reports, runner.file_library());
+        }
+        OutputFormat::Checkstyle => {
+            CheckstyleWriter::new(io::stdout()).write_reports(reports, runner.file_library());
+        }
+        OutputFormat::Html => {
+            HtmlWriter::new(io::stdout()).write_reports(reports, runner.file_library());
+        }
+        OutputFormat::Text | OutputFormat::Short | OutputFormat::Jsonl | OutputFormat::Github => {}
+    }
+
+    // If a Sarif file is passed to the program we write the reports to it.
+    if let Some(sarif_file) = &options.sarif_file {
+        let allow_list = options.allow_list.clone();
+        let user_inputs = runner.file_library().user_inputs().clone();
+        let mut sarif_writer = SarifWriter::new(sarif_file)
+            .add_filter(suppression_filter.clone())
+            .add_filter(move |report: &Report| filter_by_level(report, &output_level))
+            .add_filter(move |report: &Report| filter_by_file(report, &user_inputs))
+            .add_filter(move |report: &Report| filter_by_id(report, &allow_list));
+        if let Some(baseline) = baseline {
+            // Use a new filter here to reset the baseline entry counts.
+            sarif_writer = sarif_writer.add_filter(BaselineFilter::new(baseline));
+        }
+        if let Some(diff_filter) = &diff_filter {
+            sarif_writer = sarif_writer.add_filter(diff_filter.clone());
+        }
+        if sarif_writer.write_reports(stdout_writer.reports(), runner.file_library()) > 0 {
+            stdout_writer.write_message(format!("Result written to `{}`.", sarif_file.display()));
+        }
+    }
+
+    // If `--fix` or `--fix-dry-run` is passed to the program we apply (or
+    // print) the suggested fixes from all written reports.
+    if options.fix || options.fix_dry_run {
+        let reports = stdout_writer.written_reports().clone();
+        let fixes = Fixes::new(&reports, runner.file_library());
+        for file in &fixes.files {
+            if options.fix_dry_run {
+                println!("{}", file.to_diff());
+                continue;
+            }
+            if let Err(err) = file.write() {
+                error!("{err:#}");
+                return (runner, ExitCode::from(EXIT_INTERNAL_ERROR));
+            }
+            stdout_writer.write_message(format!(
+                "Applied {} {} to `{}`.",
+                file.len(),
+                if file.len() == 1 { "fix" } else { "fixes" },
+                file.path
+            ));
+        }
+        if fixes.skipped > 0 {
+            stdout_writer.write_message(format!(
+                "{} overlapping {} skipped. Run Circomspect again to apply {}.",
+                fixes.skipped,
+                if fixes.skipped == 1 { "fix was" } else { "fixes were" },
+                if fixes.skipped == 1 { "it" } else { "them" }
+            ));
+        }
+    }
+
+    // If a new baseline file is passed to the program we write all current
+    // reports to it. (Reports in the old baseline are included.) Results
+    // recorded in the new baseline do not cause a failure.
+    let mut failing_reports = stdout_writer.written_reports().clone();
+    if let Some(baseline_file) = &options.write_baseline_file {
+        let allow_list = options.allow_list.clone();
+        let user_inputs = runner.file_library().user_inputs().clone();
+        let mut baseline_writer = BaselineWriter::new(baseline_file)
+            .add_filter(suppression_filter)
+            .add_filter(move |report: &Report| filter_by_level(report, &output_level))
+            .add_filter(move |report: &Report| filter_by_file(report, &user_inputs))
+            .add_filter(move |report: &Report| filter_by_id(report, &allow_list));
+        match baseline_writer.write_baseline(stdout_writer.reports(), runner.file_library()) {
+            Ok(new_baseline) => {
+                let n = new_baseline.len();
+                stdout_writer.write_message(format!(
+                    "Baseline with {n} {} written to `{}`.",
+                    if n == 1 { "result" } else { "results" },
+                    baseline_file.display()
+                ));
+                let baseline_filter = BaselineFilter::new(&new_baseline);
+                failing_reports
+                    .retain(|report| baseline_filter.filter(report, runner.file_library()));
+            }
+            Err(err) => {
+                error!("{err:#}");
+                return (runner, ExitCode::from(EXIT_INTERNAL_ERROR));
+            }
+        }
+    }
+
+    match stdout_writer.reports_written() {
+        0 => stdout_writer.write_message("No issues found."),
+        1 => stdout_writer.write_message("1 issue found."),
+        n => stdout_writer.write_message(format!("{n} issues found.")),
+    }
+    let exit_code = exit_code(stdout_writer.reports(), &failing_reports, options.fail_on);
+    (runner, exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use program_structure::report_code::ReportCode;
+
+    use super::*;
+
+    /// Creates a temporary directory containing the given files.
+    fn temp_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("circomspect-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (file_name, contents) in files {
+            fs::write(dir.join(file_name), contents).unwrap();
+        }
+        dir
+    }
+
+    fn parse_options(args: &[&str]) -> Options {
+        let cli = Cli::try_parse_from(["circomspect", "--no-config"].iter().chain(args)).unwrap();
+        Options::new(cli, &[]).unwrap()
+    }
+
+    const SIGNAL_ASSIGNMENT: &str = r#"
+        pragma circom 2.0.0;
+
+        template T() {
+            signal input in;
+            signal output out;
+            out <-- in;
+        }
+
+        component main = T();
+    "#;
+
+    #[test]
+    fn test_write_baseline() {
+        let dir = temp_dir(
+            "write-baseline",
+            &[("main.circom", SIGNAL_ASSIGNMENT), ("error.circom", "template T( {")],
+        );
+        let main = dir.join("main.circom");
+        let main = main.to_str().unwrap();
+        let error = dir.join("error.circom");
+        let error = error.to_str().unwrap();
+        let baseline_file = dir.join("baseline.json");
+        let baseline_file = baseline_file.to_str().unwrap();
+        let run = |args: &[&str], baseline: Option<&Baseline>| {
+            analyze(&parse_options(args), baseline, StderrLogWriter::new(), None).1
+        };
+
+        // Results recorded in the new baseline do not cause a failure.
+        assert_eq!(run(&[main], None), ExitCode::from(EXIT_FINDINGS));
+        assert_eq!(run(&[main, "--write-baseline", baseline_file], None), ExitCode::SUCCESS);
+        let baseline = Baseline::read(Path::new(baseline_file)).unwrap();
+        assert!(!baseline.is_empty());
+        assert_eq!(run(&[main], Some(&baseline)), ExitCode::SUCCESS);
+
+        // Parse errors are still reported when a baseline is written.
+        assert_eq!(
+            run(&[error, "--write-baseline", baseline_file], None),
+            ExitCode::from(EXIT_INPUT_ERROR)
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pass_selection_options() {
+        let dir = std::env::temp_dir().join(format!("circomspect-options-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config_file = dir.join("circomspect.toml");
+        fs::write(
+            &config_file,
+            "[passes]\nenable = [\"signal-assignment\", \"bitwise-complement\"]\n\
+             disable = [\"field-element-arithmetic\"]\n",
+        )
+        .unwrap();
+        let selected = |args: &[&str]| -> anyhow::Result<Vec<String>> {
+            let cli = Cli::try_parse_from(
+                ["circomspect", "--config", config_file.to_str().unwrap(), "main.circom"]
+                    .iter()
+                    .chain(args),
+            )?;
+            let options = Options::new(cli, &[])?;
+            Ok(analysis_passes(&[])
+                .filter(|pass| options.analysis_config.pass_selection.is_selected(*pass))
+                .map(|pass| pass.id().to_string())
+                .collect())
+        };
+
+        // The configuration file selects the passes to run.
+        assert_eq!(selected(&[]).unwrap(), vec!["bitwise-complement", "signal-assignment"]);
+        // `--skip` is added to the passes disabled by the configuration file.
+        assert_eq!(selected(&["--skip", "bitwise-complement"]).unwrap(), vec!["signal-assignment"]);
+        // `--only` replaces the passes enabled by the configuration file.
+        assert_eq!(
+            selected(&["--only", "field-element-arithmetic,unused-output-signal"]).unwrap(),
+            vec!["unused-output-signal"]
+        );
+        // Pass IDs are validated.
+        assert!(selected(&["--only", "CS0005"]).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_custom_passes() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        use program_analysis::analysis_context::AnalysisContext;
+        use program_structure::cfg::Cfg;
+        use program_structure::file_definition::FileLibrary;
+        use program_structure::report::ReportCollection;
+
+        const CUSTOM: ReportCode = ReportCode::Custom { id: "ORG001", name: "org-template" };
+
+        struct CustomPass;
+
+        impl AnalysisPass for CustomPass {
+            fn id(&self) -> &str {
+                "org-template"
+            }
+
+            fn name(&self) -> &str {
+                "Organization template check"
+            }
+
+            fn description(&self) -> &str {
+                "Reports each template."
+            }
+
+            fn report_codes(&self) -> &[ReportCode] {
+                &[CUSTOM]
+            }
+
+            fn run(&self, _: &dyn AnalysisContext, cfg: &Cfg) -> ReportCollection {
+                vec![Report::warning(format!("Template `{}`.", cfg.name()), CUSTOM)]
+            }
+        }
+
+        /// Records the IDs of all written reports.
+        #[derive(Clone, Default)]
+        struct IdWriter(Rc<RefCell<Vec<String>>>);
+
+        impl LogWriter for IdWriter {
+            fn write_messages<D: std::fmt::Display>(&mut self, _: &[D]) {}
+        }
+
+        impl ReportWriter for IdWriter {
+            fn write_reports(&mut self, reports: &[Report], _: &FileLibrary) -> usize {
+                self.0.borrow_mut().extend(reports.iter().map(Report::id));
+                reports.len()
+            }
+
+            fn reports_written(&self) -> usize {
+                self.0.borrow().len()
+            }
+        }
+
+        let dir = temp_dir("custom-passes", &[("main.circom", SIGNAL_ASSIGNMENT)]);
+        let main = dir.join("main.circom");
+        let custom_passes: [Arc<dyn AnalysisPass>; 1] = [Arc::new(CustomPass)];
+        let run = |args: &[&str]| -> anyhow::Result<Vec<String>> {
+            let cli = Cli::try_parse_from(
+                ["circomspect", "--no-config", main.to_str().unwrap()].iter().chain(args),
+            )?;
+            let options = Options::new(cli, &custom_passes)?;
+            let writer = IdWriter::default();
+            analyze(&options, None, writer.clone(), None);
+            let mut ids = writer.0.take();
+            ids.sort();
+            ids.dedup();
+            Ok(ids)
+        };
+
+        // Custom passes run together with the built-in passes.
+        let ids = run(&[]).unwrap();
+        assert!(ids.len() > 1 && ids.contains(&"ORG001".to_string()));
+        // Custom passes are selected and skipped by ID, like built-in passes.
+        assert_eq!(run(&["--only", "org-template"]).unwrap(), vec!["ORG001"]);
+        let ids = run(&["--skip", "org-template"]).unwrap();
+        assert!(!ids.is_empty() && !ids.contains(&"ORG001".to_string()));
+        assert!(run(&["--only", "ORG001"]).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_command_exit_codes() {
+        let dir = temp_dir(
+            "commands",
+            &[("main.circom", SIGNAL_ASSIGNMENT), ("error.circom", "template T( {")],
+        );
+        let main = dir.join("main.circom");
+        let main = main.to_str().unwrap();
+        let error = dir.join("error.circom");
+        let error = error.to_str().unwrap();
+        let run = |args: &[&str]| {
+            let cli = Cli::try_parse_from(["circomspect"].iter().chain(args)).unwrap();
+            match run_command(cli.command.unwrap(), &[]) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(err) => err.exit_code(),
+            }
+        };
+
+        // `explain`
+        assert_eq!(run(&["explain", "CS0005"]), ExitCode::SUCCESS);
+        assert_eq!(run(&["explain", "CS9999"]), ExitCode::from(EXIT_INVALID_OPTIONS));
+
+        // `inspect`
+        assert_eq!(run(&["inspect", &format!("{main}:7:13")]), ExitCode::SUCCESS);
+        assert_eq!(
+            run(&["inspect", &format!("{main}:100:1")]),
+            ExitCode::from(EXIT_INVALID_OPTIONS)
+        );
+        assert_eq!(run(&["inspect", &format!("{error}:1:1")]), ExitCode::from(EXIT_INPUT_ERROR));
+
+        // `query`
+        assert_eq!(run(&["query", "$x <-- $y", main]), ExitCode::SUCCESS);
+        assert_eq!(run(&["query", "$x <--", main]), ExitCode::from(EXIT_INVALID_OPTIONS));
+        assert_eq!(run(&["query", "$x <-- $y", error]), ExitCode::from(EXIT_INPUT_ERROR));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fix_dry_run_options() {
+        let options = |args: &[&str]| {
+            let cli = Cli::try_parse_from(
+                ["circomspect", "--no-config", "--fix-dry-run", "main.circom"].iter().chain(args),
+            )
+            .unwrap();
+            Options::new(cli, &[])
+        };
+        assert!(options(&[]).is_ok());
+        // The diff cannot be combined with machine readable output formats.
+        assert!(options(&["--format", "json"]).is_err());
+        assert!(options(&["--format", "short"]).is_err());
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let info = Report::info("info".to_string(), ReportCode::FieldElementArithmetic);
+        let warning = Report::warning("warning".to_string(), ReportCode::SignalAssignmentStatement);
+        let parse_error = Report::error("error".to_string(), ReportCode::ParseFail);
+        let reports = [info.clone(), warning.clone()];
+
+        assert_eq!(exit_code(&[], &[], FailOn::Info), ExitCode::SUCCESS);
+        assert_eq!(
+            exit_code(&reports, std::slice::from_ref(&info), FailOn::Info),
+            ExitCode::from(EXIT_FINDINGS)
+        );
+        assert_eq!(exit_code(&reports, &[info], FailOn::Warning), ExitCode::SUCCESS);
+        assert_eq!(exit_code(&reports, &reports, FailOn::Warning), ExitCode::from(EXIT_FINDINGS));
+        assert_eq!(exit_code(&reports, &reports, FailOn::Error), ExitCode::SUCCESS);
+        assert_eq!(exit_code(&reports, &reports, FailOn::Never), ExitCode::SUCCESS);
+
+        // Parse errors take precedence, even if they are not written.
+        let reports = [warning.clone(), parse_error];
+        assert_eq!(
+            exit_code(&reports, &[warning], FailOn::Never),
+            ExitCode::from(EXIT_INPUT_ERROR)
+        );
+    }
+}
@@ -0,0 +1,627 @@
+//! A language server providing Circomspect diagnostics over stdio.
+//!
+//! The server keeps the contents of all open documents in memory. Whenever a
+//! document is opened, changed, or saved, the document is re-parsed and
+//! re-analyzed, and all (filtered) reports are published as diagnostics.
+//! Changes are coalesced, so that a document is only re-analyzed once the
+//! client has stopped sending changes for a short while.
+use anyhow::{anyhow, Result};
+use codespan_reporting::files::Files;
+use log::{debug, info, warn};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::{
+    CodeDescription, Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, InitializeParams, Location, NumberOrString, Position,
+    PublishDiagnosticsParams, Range, SaveOptions, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextDocumentSyncOptions, TextDocumentSyncSaveOptions, Url,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use program_analysis::analysis_runner::AnalysisRunner;
+use program_analysis::config;
+use program_analysis::pass_registry::{analysis_passes, AnalysisPass};
+use program_structure::file_definition::{FileID, FileLibrary};
+use program_structure::report::{MessageCategory, Report, ReportLabel};
+use program_structure::writers::{CachedWriter, ReportWriter, StderrLogWriter, SuppressionFilter};
+
+use crate::config_file::ConfigFile;
+use crate::{filter_by_id, filter_by_level, CommandError, CommandResult};
+
+const SOURCE: &str = "circomspect";
+
+/// The time to wait for further changes before analyzing a changed document.
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(200);
+
+/// Starts the language server and processes messages until the client
+/// requests a shutdown. The given custom analysis passes are run in addition
+/// to the built-in passes.
+pub fn run(custom_passes: &[Arc<dyn AnalysisPass>]) -> CommandResult {
+    info!("starting language server");
+    let (connection, io_threads) = Connection::stdio();
+    serve(connection, custom_passes)?;
+    io_threads.join().map_err(|err| CommandError::Internal(err.into()))?;
+    info!("language server stopped");
+    Ok(())
+}
+
+/// Initializes the language server on the given connection and processes
+/// messages until the client requests a shutdown. All errors are internal
+/// errors, since errors analyzing a document are logged rather than returned.
+fn serve(connection: Connection, custom_passes: &[Arc<dyn AnalysisPass>]) -> CommandResult {
+    initialize(&connection).map_err(CommandError::Internal)?;
+    Server::new(connection, custom_passes).run().map_err(CommandError::Internal)
+}
+
+/// Performs the initialization handshake with the client.
+fn initialize(connection: &Connection) -> Result<()> {
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+            open_close: Some(true),
+            change: Some(TextDocumentSyncKind::FULL),
+            save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                include_text: Some(true),
+            })),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+    let params = connection.initialize(serde_json::to_value(capabilities)?)?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+    debug!("initialized language server for client {:?}", params.client_info);
+    Ok(())
+}
+
+struct Server {
+    connection: Connection,
+    /// The contents of all open documents, keyed by canonicalized path.
+    documents: HashMap<PathBuf, String>,
+    /// Changed documents which have not been analyzed yet, together with the
+    /// latest version of each document.
+    pending_changes: Vec<(Url, Option<i32>)>,
+    /// The included files with diagnostics published by the last analysis of
+    /// each document. These are cleared when they no longer have any reports.
+    included_files: HashMap<Url, HashSet<Url>>,
+    custom_passes: Vec<Arc<dyn AnalysisPass>>,
+}
+
+impl Server {
+    fn new(connection: Connection, custom_passes: &[Arc<dyn AnalysisPass>]) -> Server {
+        Server {
+            connection,
+            documents: HashMap::new(),
+            pending_changes: Vec::new(),
+            included_files: HashMap::new(),
+            custom_passes: custom_passes.to_vec(),
+        }
+    }
+
+    /// Processes messages until the client requests a shutdown. Changed
+    /// documents are analyzed once no further messages have been received for
+    /// `DEBOUNCE_DELAY`, or before the next message which is not a change.
+    fn run(&mut self) -> Result<()> {
+        loop {
+            let message = if self.pending_changes.is_empty() {
+                match self.connection.receiver.recv() {
+                    Ok(message) => message,
+                    Err(_) => return Ok(()),
+                }
+            } else {
+                match self.connection.receiver.recv_timeout(DEBOUNCE_DELAY) {
+                    Ok(message) => message,
+                    Err(err) if err.is_timeout() => {
+                        self.analyze_pending_changes()?;
+                        continue;
+                    }
+                    Err(_) => return Ok(()),
+                }
+            };
+            match message {
+                Message::Notification(notification)
+                    if notification.method == DidChangeTextDocument::METHOD =>
+                {
+                    self.handle_change(notification)?;
+                }
+                Message::Request(request) => {
+                    self.analyze_pending_changes()?;
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    self.handle_request(request)?;
+                }
+                Message::Notification(notification) => {
+                    self.analyze_pending_changes()?;
+                    self.handle_notification(notification)?;
+                }
+                Message::Response(_) => {}
+            }
+        }
+    }
+
+    /// Updates the contents of a changed document. The document is analyzed
+    /// once the client stops sending changes.
+    fn handle_change(&mut self, notification: Notification) -> Result<()> {
+        let mut params: DidChangeTextDocumentParams = serde_json::from_value(notification.params)?;
+        // We use full document synchronization, so the last change contains
+        // the entire document.
+        let document = params.text_document;
+        if let (Some(path), Some(change)) = (to_path(&document.uri), params.content_changes.pop()) {
+            self.documents.insert(path, change.text);
+        }
+        self.pending_changes.retain(|(uri, _)| uri != &document.uri);
+        self.pending_changes.push((document.uri, Some(document.version)));
+        Ok(())
+    }
+
+    /// Analyzes all changed documents.
+    fn analyze_pending_changes(&mut self) -> Result<()> {
+        for (uri, version) in std::mem::take(&mut self.pending_changes) {
+            self.update_document(&uri, None, version)?;
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, request: Request) -> Result<()> {
+        debug!("unsupported request `{}`", request.method);
+        let response = Response::new_err(
+            request.id,
+            ErrorCode::MethodNotFound as i32,
+            format!("unsupported request `{}`", request.method),
+        );
+        self.connection.sender.send(Message::Response(response))?;
+        Ok(())
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let document = params.text_document;
+                self.update_document(&document.uri, Some(document.text), Some(document.version))
+            }
+            DidChangeTextDocument::METHOD => {
+                self.handle_change(notification)?;
+                self.analyze_pending_changes()
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: DidSaveTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                self.update_document(&params.text_document.uri, params.text, None)
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                if let Some(path) = to_path(&uri) {
+                    self.documents.remove(&path);
+                }
+                // Clear diagnostics for the closed document and its includes.
+                for included_uri in self.included_files.remove(&uri).unwrap_or_default() {
+                    self.publish_diagnostics(included_uri, Vec::new(), None)?;
+                }
+                self.publish_diagnostics(uri, Vec::new(), None)
+            }
+            method => {
+                debug!("ignoring notification `{method}`");
+                Ok(())
+            }
+        }
+    }
+
+    /// Updates the contents of the given document (if given), and publishes
+    /// diagnostics for the document and any included files. Diagnostics are
+    /// cleared for included files which no longer have any reports.
+    fn update_document(
+        &mut self,
+        uri: &Url,
+        text: Option<String>,
+        version: Option<i32>,
+    ) -> Result<()> {
+        let Some(path) = to_path(uri) else {
+            warn!("ignoring document `{uri}` (only local files are supported)");
+            return Ok(());
+        };
+        if let Some(text) = text {
+            self.documents.insert(path.clone(), text);
+        }
+        let mut diagnostics = match self.analyze(&path) {
+            Ok(diagnostics) => diagnostics,
+            Err(err) => {
+                warn!("failed to analyze `{}`: {err:#}", path.display());
+                return Ok(());
+            }
+        };
+        // Publish diagnostics for the document, and for any included files
+        // with reports. Diagnostics are keyed by the canonicalized path, which
+        // may differ from the URI used by the client.
+        let document_diagnostics = Url::from_file_path(&path)
+            .ok()
+            .and_then(|document_uri| diagnostics.remove(&document_uri))
+            .unwrap_or_default();
+        self.publish_diagnostics(uri.clone(), document_diagnostics, version)?;
+        let included_files = diagnostics.keys().cloned().collect::<HashSet<_>>();
+        let previous_files = self.included_files.insert(uri.clone(), included_files.clone());
+        for stale_uri in previous_files.unwrap_or_default().difference(&included_files) {
+            self.publish_diagnostics(stale_uri.clone(), Vec::new(), None)?;
+        }
+        for (uri, diagnostics) in diagnostics {
+            self.publish_diagnostics(uri, diagnostics, None)?;
+        }
+        Ok(())
+    }
+
+    /// Analyzes the given file, returning diagnostics grouped by document.
+    fn analyze(&self, path: &Path) -> Result<HashMap<Url, Vec<Diagnostic>>> {
+        let input_files = [path.to_path_buf()];
+        let config_file = match ConfigFile::discover(&input_files) {
+            Some(config_file) => ConfigFile::load(&config_file)?,
+            None => ConfigFile::default(),
+        };
+        let output_level = match config_file.level()? {
+            Some(output_level) => output_level,
+            None => config::DEFAULT_LEVEL.parse()?,
+        };
+        let curve = match config_file.curve()? {
+            Some(curve) => curve,
+            None => config::DEFAULT_CURVE.parse()?,
+        };
+        let depth = match config_file.depth {
+            Some(depth) => depth,
+            None => config::DEFAULT_DEPTH.parse()?,
+        };
+
+        let mut custom_passes = self.custom_passes.clone();
+        custom_passes.extend(config_file.query_passes()?);
+        let analysis_config = config_file.analysis_config()?;
+        analysis_config.validate(analysis_passes(&custom_passes))?;
+
+        let (mut runner, reports) = AnalysisRunner::new(curve)
+            .with_config(analysis_config)
+            .with_passes(&custom_passes)
+            .with_libraries(&config_file.libraries)
+            .with_file_contents(self.documents.clone())
+            .with_files(&input_files, depth);
+
+        let allow_list = config_file.allow.clone();
+        let mut writer = CachedWriter::new(StderrLogWriter::new())
+            .add_filter(SuppressionFilter::new(runner.file_library()))
+            .add_filter(move |report: &Report| filter_by_level(report, &output_level))
+            .add_filter(move |report: &Report| filter_by_id(report, &allow_list));
+        writer.write_reports(&reports, runner.file_library());
+        runner.analyze_functions(&mut writer, false);
+        runner.analyze_templates(&mut writer, false);
+
+        let file_library = runner.file_library();
+        let mut diagnostics: HashMap<Url, Vec<Diagnostic>> = HashMap::new();
+        for report in writer.written_reports() {
+            for (uri, diagnostic) in to_diagnostics(report, file_library) {
+                diagnostics.entry(uri).or_default().push(diagnostic);
+            }
+        }
+        Ok(diagnostics)
+    }
+
+    fn publish_diagnostics(
+        &self,
+        uri: Url,
+        diagnostics: Vec<Diagnostic>,
+        version: Option<i32>,
+    ) -> Result<()> {
+        debug!("publishing {} diagnostics for `{uri}`", diagnostics.len());
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, version);
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+        self.connection
+            .sender
+            .send(Message::Notification(notification))
+            .map_err(|_| anyhow!("failed to send diagnostics to the client"))
+    }
+}
+
+/// Returns the canonicalized path corresponding to the given URI.
+fn to_path(uri: &Url) -> Option<PathBuf> {
+    let path = uri.to_file_path().ok()?;
+    fs::canonicalize(&path).ok().or(Some(path))
+}
+
+/// Converts the report to LSP diagnostics (one for each primary location).
+/// Secondary locations are included as related information.
+fn to_diagnostics(report: &Report, file_library: &FileLibrary) -> Vec<(Url, Diagnostic)> {
+    let related_information = report
+        .secondary()
+        .iter()
+        .filter_map(|label| {
+            let (uri, range) = to_location(label, file_library)?;
+            Some(DiagnosticRelatedInformation {
+                location: Location { uri, range },
+                message: label.message.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let code_description = report
+        .code()
+        .url()
+        .and_then(|url| Url::parse(&url).ok())
+        .map(|href| CodeDescription { href });
+    let mut message = report.message().clone();
+    for note in report.notes() {
+        message.push('\n');
+        message.push_str(note);
+    }
+    report
+        .primary()
+        .iter()
+        .filter_map(|label| {
+            let (uri, range) = to_location(label, file_library)?;
+            let diagnostic = Diagnostic {
+                range,
+                severity: Some(to_severity(report.category())),
+                code: Some(NumberOrString::String(report.id())),
+                code_description: code_description.clone(),
+                source: Some(SOURCE.to_string()),
+                message: if label.message.is_empty() {
+                    message.clone()
+                } else {
+                    format!("{message}\n{}", label.message)
+                },
+                related_information: Some(related_information.clone()),
+                ..Default::default()
+            };
+            Some((uri, diagnostic))
+        })
+        .collect()
+}
+
+fn to_severity(category: &MessageCategory) -> DiagnosticSeverity {
+    use MessageCategory::*;
+    match category {
+        Error => DiagnosticSeverity::ERROR,
+        Warning => DiagnosticSeverity::WARNING,
+        Info => DiagnosticSeverity::INFORMATION,
+    }
+}
+
+/// Returns the document URI and range corresponding to the given label.
+fn to_location(label: &ReportLabel, file_library: &FileLibrary) -> Option<(Url, Range)> {
+    let files = file_library.to_storage();
+    let file = files.get(label.file_id).ok()?;
+    let uri = Url::from_file_path(file.name()).ok()?;
+    let start = to_position(label.file_id, label.range.start, file_library)?;
+    let end = to_position(label.file_id, label.range.end, file_library)?;
+    Some((uri, Range { start, end }))
+}
+
+/// Converts a byte offset into an LSP position. LSP positions are given as a
+/// zero-based line number and a character offset in UTF-16 code units.
+fn to_position(file_id: FileID, offset: usize, file_library: &FileLibrary) -> Option<Position> {
+    let files = file_library.to_storage();
+    let source = files.source(file_id).ok()?;
+    let line = files.line_index(file_id, offset).ok()?;
+    let line_start = files.line_range(file_id, line).ok()?.start;
+    let character = source.get(line_start..offset)?.encode_utf16().count();
+    Some(Position { line: line as u32, character: character as u32 })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::{self, JoinHandle};
+
+    use lsp_types::request::{Initialize, Request as _, Shutdown};
+    use lsp_types::{TextDocumentContentChangeEvent, TextDocumentItem, VersionedTextDocumentIdentifier};
+    use program_structure::report_code::ReportCode;
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(30);
+
+    fn start_server() -> (Connection, JoinHandle<CommandResult>) {
+        let (connection, client) = Connection::memory();
+        let server = thread::spawn(move || serve(connection, &[]));
+        let request =
+            Request::new(1.into(), Initialize::METHOD.to_string(), InitializeParams::default());
+        client.sender.send(request.into()).unwrap();
+        assert!(matches!(client.receiver.recv_timeout(TIMEOUT), Ok(Message::Response(_))));
+        notify(&client, "initialized", serde_json::json!({}));
+        (client, server)
+    }
+
+    /// Shuts down the server, returning any diagnostics published before the
+    /// shutdown response.
+    fn stop_server(
+        client: Connection,
+        server: JoinHandle<CommandResult>,
+    ) -> Vec<PublishDiagnosticsParams> {
+        let request = Request::new(2.into(), Shutdown::METHOD.to_string(), ());
+        client.sender.send(request.into()).unwrap();
+        let mut published = Vec::new();
+        loop {
+            match client.receiver.recv_timeout(TIMEOUT).unwrap() {
+                Message::Response(_) => break,
+                Message::Notification(notification) => {
+                    published.push(serde_json::from_value(notification.params).unwrap())
+                }
+                Message::Request(_) => {}
+            }
+        }
+        notify(&client, "exit", ());
+        server.join().unwrap().unwrap();
+        published
+    }
+
+    fn notify(client: &Connection, method: &str, params: impl serde::Serialize) {
+        let notification = Notification::new(method.to_string(), params);
+        client.sender.send(notification.into()).unwrap();
+    }
+
+    fn next_diagnostics(client: &Connection) -> PublishDiagnosticsParams {
+        match client.receiver.recv_timeout(TIMEOUT).unwrap() {
+            Message::Notification(notification) => {
+                assert_eq!(notification.method, PublishDiagnostics::METHOD);
+                serde_json::from_value(notification.params).unwrap()
+            }
+            message => panic!("unexpected message `{message:?}`"),
+        }
+    }
+
+    fn open(client: &Connection, uri: &Url, text: &str) {
+        let text_document =
+            TextDocumentItem::new(uri.clone(), "circom".to_string(), 1, text.to_string());
+        notify(client, DidOpenTextDocument::METHOD, DidOpenTextDocumentParams { text_document });
+    }
+
+    fn change(client: &Connection, uri: &Url, version: i32, text: &str) {
+        let params = DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier::new(uri.clone(), version),
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: text.to_string(),
+            }],
+        };
+        notify(client, DidChangeTextDocument::METHOD, params);
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("circomspect-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    const MAIN: &str = "
+        pragma circom 2.0.0;
+        include \"lib.circom\";
+
+        template Main() {
+            signal input in;
+            signal output out;
+            component lib = Lib();
+            lib.in <== in;
+            out <== lib.out;
+        }
+
+        component main = Main();
+    ";
+
+    const LIB: &str = "
+        pragma circom 2.0.0;
+
+        template Lib() {
+            signal input in;
+            signal output out;
+            out <-- in;
+        }
+    ";
+
+    /// Returns the main file with a fixed version of the library inlined.
+    fn inlined_main() -> String {
+        let lib = LIB.replace("pragma circom 2.0.0;", "").replace("<--", "<==");
+        format!("{}{lib}", MAIN.replace("include \"lib.circom\";", ""))
+    }
+
+    #[test]
+    fn test_stale_included_diagnostics() {
+        let dir = test_dir("lsp-stale-includes");
+        let main_path = dir.join("main.circom");
+        let lib_path = dir.join("lib.circom");
+        fs::write(&main_path, MAIN).unwrap();
+        fs::write(&lib_path, LIB).unwrap();
+        let main_uri = Url::from_file_path(&main_path).unwrap();
+        let lib_uri = Url::from_file_path(&lib_path).unwrap();
+
+        let (client, server) = start_server();
+        open(&client, &main_uri, MAIN);
+        let published = next_diagnostics(&client);
+        assert_eq!(published.uri, main_uri);
+        let published = next_diagnostics(&client);
+        assert_eq!(published.uri, lib_uri);
+        assert!(!published.diagnostics.is_empty());
+
+        // Removing the issue from the library should clear its diagnostics.
+        change(&client, &main_uri, 2, &inlined_main());
+        let published = next_diagnostics(&client);
+        assert_eq!(published.uri, main_uri);
+        assert_eq!(published.version, Some(2));
+        let published = next_diagnostics(&client);
+        assert_eq!(published.uri, lib_uri);
+        assert!(published.diagnostics.is_empty());
+
+        assert!(stop_server(client, server).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_coalesced_changes() {
+        let dir = test_dir("lsp-coalesced-changes");
+        let main_path = dir.join("main.circom");
+        let main = inlined_main();
+        fs::write(&main_path, &main).unwrap();
+        let main_uri = Url::from_file_path(&main_path).unwrap();
+
+        let (client, server) = start_server();
+        open(&client, &main_uri, &main);
+        assert_eq!(next_diagnostics(&client).version, Some(1));
+
+        // Consecutive changes should only be analyzed once, and the analysis
+        // must complete before the shutdown request is handled.
+        for version in 2..=4 {
+            change(&client, &main_uri, version, &format!("{main}// Version {version}.\n"));
+        }
+        let published = stop_server(client, server);
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].uri, main_uri);
+        assert_eq!(published[0].version, Some(4));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_serve_errors() {
+        // Failing to initialize the server is an internal error.
+        let (connection, client) = Connection::memory();
+        drop(client);
+        let err = serve(connection, &[]).unwrap_err();
+        assert!(matches!(err, CommandError::Internal(_)));
+    }
+
+    #[test]
+    fn test_to_diagnostics() {
+        let mut file_library = FileLibrary::new();
+        // Note that `∑` is 3 bytes in UTF-8, but a single UTF-16 code unit.
+        let source = "template T() {\n    // ∑\n  /*∑*/ out <-- in;\n}\n";
+        let file_id = file_library.add_file("/test.circom".to_string(), source.to_string(), true);
+        let start = source.find("out").unwrap();
+        let mut report = Report::warning(
+            "Using the signal assignment operator `<--` does not constrain the assigned signal."
+                .to_string(),
+            ReportCode::SignalAssignmentStatement,
+        );
+        report.add_primary(start..start + 11, file_id, "The signal is assigned here.".to_string());
+        report.add_secondary(0..12, file_id, Some("The template is defined here.".to_string()));
+
+        let diagnostics = to_diagnostics(&report, &file_library);
+        assert_eq!(diagnostics.len(), 1);
+        let (uri, diagnostic) = &diagnostics[0];
+        assert_eq!(uri.as_str(), "file:///test.circom");
+        assert_eq!(diagnostic.range.start, Position { line: 2, character: 8 });
+        assert_eq!(diagnostic.range.end, Position { line: 2, character: 19 });
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(diagnostic.code, Some(NumberOrString::String("CS0005".to_string())));
+        assert!(diagnostic
+            .code_description
+            .as_ref()
+            .unwrap()
+            .href
+            .as_str()
+            .ends_with("#signal-assignment"));
+        let related_information = diagnostic.related_information.as_ref().unwrap();
+        assert_eq!(related_information[0].message, "The template is defined here.");
+        assert_eq!(related_information[0].location.range.start, Position { line: 0, character: 0 });
+    }
+}
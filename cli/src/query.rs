@@ -0,0 +1,67 @@
+//! Implements the `query` subcommand, which prints all matches of a structural
+//! pattern in the given input files.
+use anyhow::{anyhow, Context};
+use std::path::PathBuf;
+
+use program_analysis::analysis_context::AnalysisContext;
+use program_analysis::query::Pattern;
+use program_structure::constants::Curve;
+use program_structure::report::{MessageCategory, Report};
+use program_structure::report_code::ReportCode;
+use program_structure::writers::{LogWriter, ReportWriter, StdoutWriter};
+
+use crate::config_file;
+use crate::{is_input_error, CommandError, CommandResult};
+
+const QUERY_MATCH: ReportCode = ReportCode::Custom { id: "QUERY", name: "query" };
+
+/// Parses the given pattern and input files, and prints all matches of the
+/// pattern in functions and templates defined in the input files. Fails with
+/// an input error if any input file could not be parsed.
+pub fn run(
+    pattern: &str,
+    input_files: &[PathBuf],
+    libraries: &[PathBuf],
+    curve: Option<Curve>,
+) -> CommandResult {
+    let pattern: Pattern = pattern
+        .parse()
+        .with_context(|| format!("invalid query pattern `{pattern}`"))
+        .map_err(CommandError::InvalidOptions)?;
+    let (runner, depth) = config_file::new_runner(input_files, libraries, curve)
+        .map_err(CommandError::InvalidOptions)?;
+    let (runner, reports) = runner.with_files(input_files, depth);
+
+    // Only parse errors are written, since the analysis is not run.
+    let mut writer = StdoutWriter::new(false)
+        .add_filter(|report: &Report| report.category() == &MessageCategory::Error);
+    writer.write_reports(&reports, runner.file_library());
+
+    let mut writer = StdoutWriter::new(false);
+    let mut function_names = runner.function_names(true);
+    let mut template_names = runner.template_names(true);
+    function_names.sort();
+    template_names.sort();
+    let cfgs = function_names
+        .iter()
+        .filter_map(|name| runner.function(name).ok())
+        .chain(template_names.iter().filter_map(|name| runner.template(name).ok()));
+    for cfg in cfgs {
+        let message = format!("Pattern matched in `{}`.", cfg.name());
+        let reports = pattern
+            .find_matches(cfg)
+            .iter()
+            .map(|m| m.to_report(&message, QUERY_MATCH, MessageCategory::Info))
+            .collect::<Vec<_>>();
+        writer.write_reports(&reports, runner.file_library());
+    }
+    let matches = writer.reports_written();
+    writer.write_message(format!(
+        "{matches} {} found.",
+        if matches == 1 { "match" } else { "matches" }
+    ));
+    if reports.iter().any(is_input_error) {
+        return Err(CommandError::Input(anyhow!("failed to parse all input files")));
+    }
+    Ok(())
+}
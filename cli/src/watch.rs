@@ -0,0 +1,133 @@
+//! Implements `--watch`, which re-runs the analysis whenever one of the
+//! analyzed files changes.
+//!
+//! Files are polled for changes. This includes the input files (and input
+//! directories), together with all files included by the input files. Only
+//! templates and functions affected by a change are re-analyzed.
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use codespan_reporting::files::Files;
+use program_structure::baseline::Baseline;
+use program_structure::file_definition::FileLibrary;
+use program_structure::writers::{LogWriter, ReportWriter, StderrLogWriter};
+
+use crate::{analyze, Options};
+
+/// The interval between checking files for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The last modification time of each watched path (or `None` if the path
+/// does not exist).
+type Snapshot = HashMap<PathBuf, Option<SystemTime>>;
+
+/// Runs the analysis, and then re-runs the analysis each time a watched file
+/// changes. This function only returns if the process is interrupted.
+pub fn run<W, F>(options: &Options, baseline: Option<&Baseline>, new_writer: F) -> ExitCode
+where
+    W: LogWriter + ReportWriter,
+    F: Fn() -> W,
+{
+    let mut log_writer = StderrLogWriter::new();
+    let (mut runner, _) = analyze(options, baseline, new_writer(), None);
+    loop {
+        let paths = watched_paths(&options.input_files, runner.file_library());
+        log_writer.write_message(format!(
+            "watching {} {} for changes",
+            paths.len(),
+            if paths.len() == 1 { "path" } else { "paths" }
+        ));
+        let changed_paths = wait_for_changes(snapshot(paths));
+        for path in changed_paths {
+            log_writer.write_message(format!("`{}` changed", path.display()));
+        }
+        (runner, _) = analyze(options, baseline, new_writer(), Some(runner));
+    }
+}
+
+/// Returns the paths to watch for changes. This includes all files read by
+/// the parser, together with the input files and input directories. (The
+/// modification time of a directory is updated when files are added or
+/// removed.)
+fn watched_paths(input_files: &[PathBuf], file_library: &FileLibrary) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for path in input_files {
+        add_input_path(path, &mut paths);
+    }
+    let files = file_library.to_storage();
+    let mut file_id = 0;
+    while let Ok(name) = files.name(file_id) {
+        paths.push(PathBuf::from(name));
+        file_id += 1;
+    }
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+fn add_input_path(path: &Path, paths: &mut Vec<PathBuf>) {
+    if path.is_dir() {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                add_input_path(&entry.path(), paths);
+            }
+        }
+        paths.push(path.to_path_buf());
+    } else if path.extension().map(|extension| extension == "circom").unwrap_or_default() {
+        paths.push(path.to_path_buf());
+    }
+}
+
+fn snapshot(paths: Vec<PathBuf>) -> Snapshot {
+    paths
+        .into_iter()
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+/// Blocks until at least one of the given paths has changed, and returns the
+/// changed paths.
+fn wait_for_changes(previous: Snapshot) -> Vec<PathBuf> {
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = snapshot(previous.keys().cloned().collect());
+        let mut changed_paths = current
+            .iter()
+            .filter(|(path, modified)| previous.get(*path) != Some(modified))
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        if !changed_paths.is_empty() {
+            changed_paths.sort();
+            return changed_paths;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wait_for_changes() {
+        let dir = std::env::temp_dir().join(format!("circomspect-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("test.circom");
+        fs::write(&file, "template T() {}").unwrap();
+
+        let paths = watched_paths(std::slice::from_ref(&dir), &FileLibrary::new());
+        assert_eq!(paths, vec![dir.clone(), file.clone()]);
+
+        let previous = snapshot(paths);
+        fs::remove_file(&file).unwrap();
+        assert!(wait_for_changes(previous).contains(&file));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+use std::fs;
+use std::process::Command;
+
+/// Templates and functions are analyzed in parallel, but results must be
+/// written in the same order regardless of the number of threads used.
+#[test]
+fn test_output_order_is_independent_of_thread_count() {
+    let dir = std::env::temp_dir().join(format!("circomspect-output-order-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("main.circom");
+    let mut src = "pragma circom 2.0.0;\n".to_string();
+    for i in (0..32).rev() {
+        src.push_str(&format!(
+            "
+            function f{i:02}(n) {{
+                var x = n;
+                return n + 1;
+            }}
+
+            template T{i:02}() {{
+                signal input in;
+                signal output out;
+                out <-- f{i:02}(1) * in;
+            }}
+            "
+        ));
+    }
+    fs::write(&file, src).unwrap();
+
+    let outputs = ["1", "2", "4", "8"]
+        .iter()
+        .map(|threads| {
+            let output = Command::new(env!("CARGO_BIN_EXE_circomspect"))
+                .arg(&file)
+                .args(["--no-config", "--level", "INFO", "--format", "jsonl"])
+                .env("RAYON_NUM_THREADS", threads)
+                .output()
+                .unwrap();
+            String::from_utf8(output.stdout).unwrap()
+        })
+        .collect::<Vec<_>>();
+    fs::remove_dir_all(&dir).unwrap();
+
+    // Each function and template generates at least one result.
+    assert!(outputs[0].lines().count() >= 64);
+    for output in &outputs[1..] {
+        assert_eq!(output, &outputs[0]);
+    }
+}
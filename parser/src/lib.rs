@@ -13,11 +13,13 @@ use log::debug;
 
 mod errors;
 mod include_logic;
+mod parse_cache;
 mod parser_logic;
 mod syntax_sugar_traits;
 mod syntax_sugar_remover;
 
 pub use parser_logic::parse_definition;
+pub use parse_cache::ParseCache;
 
 use include_logic::FileStack;
 use program_structure::ast::{Version, AST};
@@ -25,6 +27,7 @@ use program_structure::report::{Report, ReportCollection};
 use program_structure::file_definition::{FileID, FileLibrary};
 use program_structure::program_archive::ProgramArchive;
 use program_structure::template_library::TemplateLibrary;
+use program_structure::suppression::find_suppressions;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
@@ -40,6 +43,36 @@ pub fn parse_files(
     file_paths: &[PathBuf],
     libraries: &[PathBuf],
     compiler_version: &Version,
+) -> ParseResult {
+    parse_files_with_contents(file_paths, &HashMap::new(), libraries, compiler_version)
+}
+
+/// Parses the given files. If the (canonicalized) path of a file is contained
+/// in `file_contents`, the given contents are used instead of the contents of
+/// the file on disk. This is used to analyze unsaved files open in an editor.
+pub fn parse_files_with_contents(
+    file_paths: &[PathBuf],
+    file_contents: &HashMap<PathBuf, String>,
+    libraries: &[PathBuf],
+    compiler_version: &Version,
+) -> ParseResult {
+    parse_files_with_cache(
+        file_paths,
+        file_contents,
+        libraries,
+        compiler_version,
+        &mut ParseCache::new(),
+    )
+}
+
+/// Parses the given files, reusing the ASTs of unchanged files from the given
+/// parse cache. The cache is updated with the ASTs of all parsed files.
+pub fn parse_files_with_cache(
+    file_paths: &[PathBuf],
+    file_contents: &HashMap<PathBuf, String>,
+    libraries: &[PathBuf],
+    compiler_version: &Version,
+    parse_cache: &mut ParseCache,
 ) -> ParseResult {
     let mut reports = ReportCollection::new();
     let mut file_stack = FileStack::new(file_paths, libraries, &mut reports);
@@ -47,7 +80,15 @@ pub fn parse_files(
     let mut definitions = HashMap::new();
     let mut main_components = Vec::new();
     while let Some(file_path) = FileStack::take_next(&mut file_stack) {
-        match parse_file(&file_path, &mut file_stack, &mut file_library, compiler_version) {
+        let file_content = file_contents.get(&file_path).cloned();
+        match parse_file(
+            &file_path,
+            file_content,
+            &mut file_stack,
+            &mut file_library,
+            compiler_version,
+            parse_cache,
+        ) {
             Ok((file_id, program, mut warnings)) => {
                 if let Some(main_component) = program.main_component {
                     main_components.push((file_id, main_component, program.custom_gates));
@@ -135,19 +176,44 @@ pub fn parse_files(
 
 pub fn parse_file(
     file_path: &PathBuf,
+    file_content: Option<String>,
     file_stack: &mut FileStack,
     file_library: &mut FileLibrary,
     compiler_version: &Version,
+    parse_cache: &mut ParseCache,
 ) -> Result<(FileID, AST, ReportCollection), Box<Report>> {
     let mut reports = ReportCollection::new();
 
     debug!("reading file `{}`", file_path.display());
-    let (path_str, file_content) = open_file(file_path)?;
+    let (path_str, file_content) = match file_content {
+        Some(file_content) => (format!("{}", file_path.display()), file_content),
+        None => open_file(file_path)?,
+    };
     let is_user_input = file_stack.is_user_input(file_path);
     let file_id = file_library.add_file(path_str, file_content.clone(), is_user_input);
 
-    debug!("parsing file `{}`", file_path.display());
-    let program = parser_logic::parse_file(&file_content, file_id)?;
+    let (program, comments) = match parse_cache.get(file_path, file_id, &file_content) {
+        Some(cached) => {
+            debug!("reusing cached AST for `{}`", file_path.display());
+            cached
+        }
+        None => {
+            debug!("parsing file `{}`", file_path.display());
+            let (program, comments) = parser_logic::parse_file(&file_content, file_id)?;
+            parse_cache.insert(file_path, file_id, &file_content, &program, &comments);
+            (program, comments)
+        }
+    };
+
+    // Retain comments and inline suppressions for later use.
+    let definitions = program
+        .definitions
+        .iter()
+        .map(|definition| definition.meta().file_location())
+        .collect::<Vec<_>>();
+    file_library.add_comments(file_id, comments);
+    let suppressions = find_suppressions(file_id, file_library, &definitions);
+    file_library.add_suppressions(suppressions);
     match check_compiler_version(file_path, program.compiler_version, compiler_version) {
         Ok(warnings) => reports.extend(warnings),
         Err(error) => reports.push(*error),
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use program_structure::ast::AST;
+use program_structure::file_definition::{Comment, FileID};
+
+/// A cache of parsed files. This is used to avoid re-parsing unchanged files
+/// when the same set of files is parsed repeatedly (e.g. in watch mode).
+#[derive(Default)]
+pub struct ParseCache {
+    files: HashMap<PathBuf, CachedFile>,
+}
+
+struct CachedFile {
+    file_id: FileID,
+    file_content: String,
+    program: AST,
+    comments: Vec<Comment>,
+}
+
+impl ParseCache {
+    pub fn new() -> ParseCache {
+        ParseCache::default()
+    }
+
+    /// Returns the cached AST and comments for the given file, provided that
+    /// both the file contents and the file ID are unchanged. (Since file IDs
+    /// are stored in the AST, a cached AST cannot be reused if the file is
+    /// assigned a new file ID.)
+    pub(crate) fn get(
+        &self,
+        file_path: &Path,
+        file_id: FileID,
+        file_content: &str,
+    ) -> Option<(AST, Vec<Comment>)> {
+        self.files
+            .get(file_path)
+            .filter(|file| file.file_id == file_id && file.file_content == file_content)
+            .map(|file| (file.program.clone(), file.comments.clone()))
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        file_path: &Path,
+        file_id: FileID,
+        file_content: &str,
+        program: &AST,
+        comments: &[Comment],
+    ) {
+        let file = CachedFile {
+            file_id,
+            file_content: file_content.to_string(),
+            program: program.clone(),
+            comments: comments.to_vec(),
+        };
+        self.files.insert(file_path.to_path_buf(), file);
+    }
+}
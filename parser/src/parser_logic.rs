@@ -3,68 +3,55 @@ use super::lang;
 
 use program_structure::ast::AST;
 use program_structure::report::Report;
-use program_structure::file_definition::FileID;
+use program_structure::file_definition::{Comment, FileID};
 
-pub fn preprocess(expr: &str, file_id: FileID) -> Result<String, Box<Report>> {
+/// Replaces comments by whitespace (preserving byte offsets) and returns the
+/// preprocessed source together with the comments found.
+pub fn preprocess(expr: &str, file_id: FileID) -> Result<(String, Vec<Comment>), Box<Report>> {
     let mut pp = String::new();
+    let mut comments = Vec::new();
     let mut state = 0;
-    let mut loc = 0;
     let mut block_start = 0;
 
-    let mut it = expr.chars();
-    while let Some(c0) = it.next() {
-        loc += 1;
+    let mut it = expr.char_indices().peekable();
+    while let Some((loc, c0)) = it.next() {
         match (state, c0) {
-            (0, '/') => {
-                loc += 1;
-                match it.next() {
-                    Some('/') => {
-                        state = 1;
-                        pp.push(' ');
-                        pp.push(' ');
-                    }
-                    Some('*') => {
-                        block_start = loc;
-                        state = 2;
-                        pp.push(' ');
-                        pp.push(' ');
-                    }
-                    Some(c1) => {
-                        pp.push(c0);
-                        pp.push(c1);
-                    }
-                    None => {
-                        pp.push(c0);
-                        break;
-                    }
+            (0, '/') => match it.peek() {
+                Some((_, '/')) => {
+                    it.next();
+                    block_start = loc;
+                    state = 1;
+                    pp.push(' ');
+                    pp.push(' ');
                 }
-            }
+                Some((_, '*')) => {
+                    it.next();
+                    block_start = loc;
+                    state = 2;
+                    pp.push(' ');
+                    pp.push(' ');
+                }
+                _ => pp.push(c0),
+            },
             (0, _) => pp.push(c0),
             (1, '\n') => {
+                let text = expr[block_start + 2..loc].trim_end_matches('\r');
+                comments.push(Comment::new(text.to_string(), block_start..loc));
                 pp.push(c0);
                 state = 0;
             }
-            (2, '*') => {
-                loc += 1;
-                match it.next() {
-                    Some('/') => {
-                        pp.push(' ');
-                        pp.push(' ');
-                        state = 0;
-                    }
-                    Some(c) => {
-                        pp.push(' ');
-                        for _i in 0..c.len_utf8() {
-                            pp.push(' ');
-                        }
-                    }
-                    None => {
-                        let error =
-                            UnclosedCommentError { location: block_start..block_start, file_id };
-                        return Err(Box::new(error.into_report()));
-                    }
+            (2, '*') => match it.peek() {
+                Some((end, '/')) => {
+                    let end = end + 1;
+                    it.next();
+                    let text = &expr[block_start + 2..loc];
+                    comments.push(Comment::new(text.to_string(), block_start..end));
+                    pp.push(' ');
+                    pp.push(' ');
+                    state = 0;
                 }
-            }
+                _ => pp.push(' '),
+            },
             (_, c) => {
                 for _i in 0..c.len_utf8() {
                     pp.push(' ');
@@ -72,19 +59,33 @@ pub fn preprocess(expr: &str, file_id: FileID) -> Result<String, Box<Report>> {
             }
         }
     }
-    Ok(pp)
+    match state {
+        1 => {
+            let text = &expr[block_start + 2..];
+            comments.push(Comment::new(text.to_string(), block_start..expr.len()));
+        }
+        2 => {
+            let error = UnclosedCommentError { location: block_start..block_start, file_id };
+            return Err(Box::new(error.into_report()));
+        }
+        _ => {}
+    }
+    Ok((pp, comments))
 }
 
-pub fn parse_file(src: &str, file_id: FileID) -> Result<AST, Box<Report>> {
+/// Parses the given file and returns the AST together with all comments in the
+/// file.
+pub fn parse_file(src: &str, file_id: FileID) -> Result<(AST, Vec<Comment>), Box<Report>> {
     use lalrpop_util::ParseError::*;
+    let (src, comments) = preprocess(src, file_id)?;
     lang::ParseAstParser::new()
-        .parse(&preprocess(src, file_id)?)
+        .parse(&src)
         .map(|mut ast| {
             // Set file ID for better error reporting.
             for include in &mut ast.includes {
                 include.meta.set_file_id(file_id);
             }
-            ast
+            (ast, comments)
         })
         .map_err(|parse_error| match parse_error {
             InvalidToken { location } => ParsingError {
@@ -112,7 +113,7 @@ pub fn parse_file(src: &str, file_id: FileID) -> Result<AST, Box<Report>> {
 }
 
 pub fn parse_string(src: &str) -> Option<AST> {
-    let src = preprocess(src, 0).ok()?;
+    let (src, _) = preprocess(src, 0).ok()?;
     lang::ParseAstParser::new().parse(&src).ok()
 }
 
@@ -151,7 +152,28 @@ fn format_expected(tokens: &[String]) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_string;
+    use super::{parse_string, preprocess};
+
+    #[test]
+    fn test_preprocess() {
+        let src = "var x = 1; // Line comment.\n/* Block\n comment **/ var y = x / 2;\n// EOF";
+        let (pp, comments) = preprocess(src, 0).ok().unwrap();
+        assert_eq!(pp.len(), src.len());
+        assert_eq!(
+            pp.split_whitespace().collect::<Vec<_>>(),
+            ["var", "x", "=", "1;", "var", "y", "=", "x", "/", "2;"]
+        );
+
+        assert_eq!(comments.len(), 3);
+        assert_eq!(comments[0].text(), " Line comment.");
+        assert_eq!(&src[comments[0].location().clone()], "// Line comment.");
+        assert_eq!(comments[1].text(), " Block\n comment *");
+        assert_eq!(&src[comments[1].location().clone()], "/* Block\n comment **/");
+        assert_eq!(comments[2].text(), " EOF");
+        assert_eq!(&src[comments[2].location().clone()], "// EOF");
+
+        assert!(preprocess("/* Unclosed comment", 0).is_err());
+    }
 
     #[test]
     fn test_parse_string() {
@@ -5,6 +5,8 @@ use program_structure::{
     cfg::Cfg,
 };
 
+use crate::config::AnalysisConfig;
+
 /// Errors returned by the analysis context.
 #[derive(Debug, Error)]
 pub enum AnalysisError {
@@ -42,11 +44,16 @@ pub trait AnalysisContext {
     /// compared to `AnalysisContext::template`.
     fn is_template(&self, name: &str) -> bool;
 
-    /// Returns the CFG for the function with the given name.
-    fn function(&mut self, name: &str) -> Result<&Cfg, AnalysisError>;
+    /// Returns the CFG for the function with the given name. The CFG is
+    /// generated on first use and shared between all analysis passes.
+    fn function(&self, name: &str) -> Result<&Cfg, AnalysisError>;
+
+    /// Returns the CFG for the template with the given name. The CFG is
+    /// generated on first use and shared between all analysis passes.
+    fn template(&self, name: &str) -> Result<&Cfg, AnalysisError>;
 
-    /// Returns the CFG for the template with the given name.
-    fn template(&mut self, name: &str) -> Result<&Cfg, AnalysisError>;
+    /// Returns the analysis configuration.
+    fn config(&self) -> &AnalysisConfig;
 
     /// Returns the string corresponding to the given file ID and location.
     fn underlying_str(
@@ -1,16 +1,23 @@
-use log::{debug, trace};
-use std::{collections::HashSet, path::PathBuf};
-use std::collections::HashMap;
+use log::trace;
+use once_cell::sync::OnceCell;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
+use std::thread;
 
-use parser::ParseResult;
+use parser::{ParseCache, ParseResult};
 
 use program_structure::{
+    ast,
     writers::{LogWriter, ReportWriter},
     template_data::TemplateInfo,
     function_data::FunctionInfo,
     file_definition::{FileLibrary, FileLocation, FileID},
     cfg::{Cfg, IntoCfg},
-    constants::Curve,
+    constants::{Curve, UsefulConstants},
+    ir::value_meta::ValueReduction,
     report::{ReportCollection, Report},
 };
 
@@ -19,19 +26,69 @@ use program_structure::template_library::TemplateLibrary;
 
 use crate::{
     analysis_context::{AnalysisContext, AnalysisError},
-    get_analysis_passes, config,
+    config::{self, AnalysisConfig},
     gather_information::gather_templates_expression,
+    function_library::FunctionLibrary,
+    incremental::{self, DefinitionSummary},
+    instantiations::{
+        self, ComponentInstantiation, Instantiation, InstantiationChain, InstantiationContext,
+        TemplateInstances,
+    },
+    pass_registry::{analysis_passes, AnalysisPass},
+    result_cache::{self, ResultCache},
 };
 
-type CfgCache = HashMap<String, Cfg>;
+/// A CFG in SSA form, together with any reports generated when lifting the
+/// corresponding AST. (The CFG is `None` if lifting failed.)
+struct LiftedCfg {
+    cfg: Option<Cfg>,
+    reports: ReportCollection,
+}
+
+/// CFGs are generated on demand. Each entry is initialized at most once, which
+/// allows CFGs to be shared (immutably) between threads.
+type CfgCache = HashMap<String, OnceCell<LiftedCfg>>;
 type ReportCache = HashMap<String, ReportCollection>;
 
+/// The template instantiations reachable from the main component, together
+/// with a specialized CFG for each instantiation.
+#[derive(Default)]
+struct Instantiations {
+    /// The instantiations of each template.
+    templates: HashMap<String, TemplateInstances>,
+    /// Specialized CFGs for each instantiation.
+    cfgs: HashMap<Instantiation, LiftedCfg>,
+}
+
+#[derive(Clone, Copy)]
+enum DefinitionKind {
+    Template,
+    Function,
+}
+
+impl fmt::Display for DefinitionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefinitionKind::Template => write!(f, "template"),
+            DefinitionKind::Function => write!(f, "function"),
+        }
+    }
+}
+
 /// A type responsible for caching CFGs and running analysis passes over all
-/// functions and templates.
+/// functions and templates. Templates and functions are analyzed in parallel
+/// on the global Rayon thread pool.
 #[derive(Default)]
 pub struct AnalysisRunner {
     curve: Curve,
+    /// Analysis configuration passed to each analysis pass.
+    config: AnalysisConfig,
+    /// Custom analysis passes run in addition to the built-in passes.
+    custom_passes: Vec<Arc<dyn AnalysisPass>>,
     libraries: Vec<PathBuf>,
+    /// File contents overriding the contents of the corresponding files on
+    /// disk (e.g. for unsaved files open in an editor).
+    file_contents: HashMap<PathBuf, String>,
     /// The corresponding file library including file includes.
     file_library: FileLibrary,
     /// Template ASTs generated by the parser.
@@ -42,11 +99,42 @@ pub struct AnalysisRunner {
     template_cfgs: CfgCache,
     /// Cached function CFGs generated on demand.
     function_cfgs: CfgCache,
-    /// Reports created during CFG generation.
-    template_reports: ReportCache,
-    /// Reports created during CFG generation.
-    function_reports: ReportCache,
+    /// Functions used to evaluate function calls during value propagation.
+    functions: Arc<FunctionLibrary>,
+    /// The instantiation of the main component (if all arguments are constant).
+    context: Option<InstantiationContext>,
+    /// The templates and functions (transitively) called by the main
+    /// component. Only these templates may be instantiated by the main
+    /// component.
+    main_callees: HashSet<String>,
+    /// The template instantiations reachable from the main component. Since
+    /// enumerating instantiations requires lifting a CFG for each
+    /// instantiation, they are only enumerated on first use.
+    instantiations: OnceCell<Instantiations>,
     callee_ids: HashSet<String>,
+    /// Parsed files, reused by subsequent runs.
+    parse_cache: ParseCache,
+    /// Persistent cache of analysis results (if any).
+    result_cache: Option<ResultCache>,
+    /// A summary of each template and function.
+    definitions: HashMap<String, DefinitionSummary>,
+    /// Definition summaries from a previous run.
+    previous_definitions: HashMap<String, DefinitionSummary>,
+    /// The instantiation context from a previous run.
+    previous_context: Option<InstantiationContext>,
+    /// The templates and functions called by the main component in a previous
+    /// run.
+    previous_main_callees: HashSet<String>,
+    /// Template instantiations from a previous run. This is only set if the
+    /// instantiations may have changed, in which case the instantiations of a
+    /// template are compared before reusing results for the template.
+    previous_instantiations: Option<HashMap<String, TemplateInstances>>,
+    /// Results from a previous run.
+    previous_results: ReportCache,
+    /// Results from a previous run that are still valid for this run.
+    reusable_results: ReportCache,
+    /// Results from analyzing each template and function.
+    results: ReportCache,
 }
 
 impl AnalysisRunner {
@@ -54,34 +142,242 @@ impl AnalysisRunner {
         AnalysisRunner { curve, ..Default::default() }
     }
 
+    pub fn with_config(mut self, config: AnalysisConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Run the given custom analysis passes in addition to the built-in passes.
+    /// Custom passes are run after the built-in passes, in the given order.
+    pub fn with_passes(mut self, passes: &[Arc<dyn AnalysisPass>]) -> Self {
+        self.custom_passes.extend(passes.iter().cloned());
+        self
+    }
+
     pub fn with_libraries(mut self, libraries: &[PathBuf]) -> Self {
         self.libraries.extend_from_slice(libraries);
         self
     }
 
+    /// Use the given contents for the corresponding files, rather than the
+    /// contents of the files on disk. Paths must be canonicalized.
+    pub fn with_file_contents(mut self, file_contents: HashMap<PathBuf, String>) -> Self {
+        self.file_contents.extend(file_contents);
+        self
+    }
+
+    /// Use the given persistent cache to store analysis results, and to skip
+    /// analysis of templates and functions with cached results.
+    pub fn with_result_cache(mut self, result_cache: ResultCache) -> Self {
+        self.result_cache = Some(result_cache);
+        self
+    }
+
+    /// Reuse parsed files and analysis results from a previous run. Results
+    /// are reused for templates and functions that are unchanged, and which do
+    /// not (transitively) call a changed template or function. The previous
+    /// runner must have been created using the same configuration.
+    pub fn with_previous_run(mut self, previous: AnalysisRunner) -> Self {
+        self.previous_definitions = previous.definitions;
+        self.previous_context = previous.context;
+        self.previous_main_callees = previous.main_callees;
+        self.previous_instantiations =
+            previous.instantiations.into_inner().map(|instantiations| instantiations.templates);
+        self.previous_results = previous.results;
+        self.parse_cache = previous.parse_cache;
+        self
+    }
+
     pub fn with_files(mut self, input_files: &[PathBuf], level: usize) -> (Self, ReportCollection) {
-        let reports =
-            match parser::parse_files(input_files, &self.libraries, &config::COMPILER_VERSION) {
-                ParseResult::Program(program, warnings) => {
-                    gather_templates_expression(
-                        program.main_expression(),
-                        &mut self.callee_ids,
-                        &program,
-                        level,
-                    );
-                    self.template_asts = program.templates;
-                    self.function_asts = program.functions;
-                    self.file_library = program.file_library;
-                    warnings
+        let mut main = None;
+        let mut reports = match parser::parse_files_with_cache(
+            input_files,
+            &self.file_contents,
+            &self.libraries,
+            &config::COMPILER_VERSION,
+            &mut self.parse_cache,
+        ) {
+            ParseResult::Program(program, warnings) => {
+                gather_templates_expression(
+                    program.main_expression(),
+                    &mut self.callee_ids,
+                    &program,
+                    level,
+                );
+                main = self.main_instantiation(program.main_expression());
+                self.template_asts = program.templates;
+                self.function_asts = program.functions;
+                self.file_library = program.file_library;
+                warnings
+            }
+            ParseResult::Library(library, warnings) => {
+                self.template_asts = library.templates;
+                self.function_asts = library.functions;
+                self.file_library = library.file_library;
+                warnings
+            }
+        };
+        self.config.apply_severity_overrides(&mut reports);
+        self.init_cfg_cache();
+        self.definitions = self.definition_summaries();
+        self.context = main.map(|main| InstantiationContext { main, level });
+        if let Some(context) = &self.context {
+            self.main_callees =
+                incremental::transitive_callees(&context.main.name, &self.definitions)
+                    .into_iter()
+                    .map(ToString::to_string)
+                    .collect();
+        }
+        if !self.previous_results.is_empty() {
+            let previous_definitions = std::mem::take(&mut self.previous_definitions);
+            self.reusable_results = incremental::reusable_results(
+                &previous_definitions,
+                &self.definitions,
+                std::mem::take(&mut self.previous_results),
+            );
+            // Results for a template also depend on how it is instantiated.
+            // Instantiations are unchanged if the main component and all
+            // templates and functions called by it are unchanged.
+            let previous_main_callees = std::mem::take(&mut self.previous_main_callees);
+            if self.previous_context == self.context
+                && previous_main_callees == self.main_callees
+                && incremental::are_unchanged(
+                    &self.main_callees,
+                    &previous_definitions,
+                    &self.definitions,
+                )
+            {
+                self.previous_instantiations = None;
+            } else if self.previous_instantiations.is_none() {
+                // The previous instantiations were never enumerated, so
+                // results for templates that may be instantiated cannot be
+                // reused.
+                self.reusable_results.retain(|name, _| {
+                    !self.main_callees.contains(name) && !previous_main_callees.contains(name)
+                });
+            }
+        }
+        (self, reports)
+    }
+
+    /// Returns the instantiation of the main component, if all arguments are
+    /// constant.
+    fn main_instantiation(&self, main: &ast::Expression) -> Option<Instantiation> {
+        let ast::Expression::Call { id, args, .. } = main else {
+            return None;
+        };
+        let constants = UsefulConstants::new(&self.curve);
+        let arguments = args
+            .iter()
+            .map(|arg| ValueReduction::from_ast(arg, &constants))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Instantiation::new(id, arguments))
+    }
+
+    /// Returns the template instantiations reachable from the main component.
+    /// Instantiations are enumerated on first use.
+    fn instantiations(&self) -> &Instantiations {
+        self.instantiations.get_or_init(|| match &self.context {
+            Some(context) => self.enumerate_instantiations(context),
+            None => Instantiations::default(),
+        })
+    }
+
+    /// Returns the instantiations of the given template, if the template is
+    /// reachable from the main component. Instantiations are only enumerated
+    /// if the template is called by the main component.
+    fn template_instances(&self, name: &str) -> Option<&TemplateInstances> {
+        if !self.template_asts.contains_key(name) || !self.main_callees.contains(name) {
+            return None;
+        }
+        self.instantiations().templates.get(name)
+    }
+
+    /// Enumerates the distinct template instantiations reachable from the main
+    /// component (breadth first, so that the shortest instantiation chain is
+    /// recorded for each instantiation), and lifts a specialized CFG for each
+    /// instantiation. Templates instantiated with arguments which are not known
+    /// to be constant, or for which a specialized CFG could not be lifted, are
+    /// marked as symbolic.
+    fn enumerate_instantiations(&self, context: &InstantiationContext) -> Instantiations {
+        let mut result = Instantiations::default();
+        let mut queue = VecDeque::from([InstantiationChain::new(context.main.clone())]);
+        while let Some(chain) = queue.pop_front() {
+            let instantiation = chain.last();
+            if result.cfgs.contains_key(instantiation) {
+                continue;
+            }
+            let Some(ast) = self.template_asts.get(&instantiation.name) else {
+                continue;
+            };
+            if result.cfgs.len() >= instantiations::MAX_INSTANTIATIONS
+                || ast.get_name_of_params().len() != instantiation.arguments.len()
+            {
+                result.templates.entry(instantiation.name.clone()).or_default().symbolic = true;
+                continue;
+            }
+            let lifted_cfg = lift_instance(ast, &self.curve, &self.functions, instantiation);
+            match &lifted_cfg.cfg {
+                Some(cfg) => {
+                    for component in instantiations::component_instantiations(cfg) {
+                        match component {
+                            ComponentInstantiation::Constant(callee)
+                                if chain.depth() < context.level =>
+                            {
+                                queue.push_back(chain.push(callee));
+                            }
+                            ComponentInstantiation::Constant(Instantiation { name, .. })
+                            | ComponentInstantiation::Symbolic(name) => {
+                                if self.template_asts.contains_key(&name) {
+                                    result.templates.entry(name).or_default().symbolic = true;
+                                }
+                            }
+                        }
+                    }
                 }
-                ParseResult::Library(library, warnings) => {
-                    self.template_asts = library.templates;
-                    self.function_asts = library.functions;
-                    self.file_library = library.file_library;
-                    warnings
+                // Fall back to analyzing the template symbolically.
+                None => {
+                    result.templates.entry(instantiation.name.clone()).or_default().symbolic = true;
                 }
-            };
-        (self, reports)
+            }
+            result.cfgs.insert(instantiation.clone(), lifted_cfg);
+            result.templates.entry(instantiation.name.clone()).or_default().chains.push(chain);
+        }
+        result
+    }
+
+    /// Creates an empty CFG cache entry for each template and function.
+    fn init_cfg_cache(&mut self) {
+        self.functions = Arc::new(FunctionLibrary::new(&self.curve, &self.function_asts));
+        self.template_cfgs =
+            self.template_asts.keys().map(|name| (name.clone(), OnceCell::new())).collect();
+        self.function_cfgs =
+            self.function_asts.keys().map(|name| (name.clone(), OnceCell::new())).collect();
+    }
+
+    /// Returns a summary of each template and function.
+    fn definition_summaries(&self) -> HashMap<String, DefinitionSummary> {
+        let templates = self.template_asts.iter().filter_map(|(name, ast)| {
+            let file_id = ast.get_file_id();
+            DefinitionSummary::new(
+                file_id,
+                ast.get_param_location(),
+                ast.get_body(),
+                &self.file_library,
+            )
+            .map(|summary| (name.clone(), summary))
+        });
+        let functions = self.function_asts.iter().filter_map(|(name, ast)| {
+            let file_id = ast.get_file_id();
+            DefinitionSummary::new(
+                file_id,
+                ast.get_param_location(),
+                ast.get_body(),
+                &self.file_library,
+            )
+            .map(|summary| (name.clone(), summary))
+        });
+        templates.chain(functions).collect()
     }
 
     /// Convenience method used to generate a runner for testing purposes.
@@ -100,10 +396,17 @@ impl AnalysisRunner {
         self.template_asts = template_library.templates;
         self.function_asts = template_library.functions;
         self.file_library = template_library.file_library;
+        self.init_cfg_cache();
 
         self
     }
 
+    /// Returns all analysis passes (including passes which are not selected
+    /// by the configuration), in the order in which they are run.
+    pub fn passes(&self) -> impl Iterator<Item = &dyn AnalysisPass> {
+        analysis_passes(&self.custom_passes)
+    }
+
     pub fn file_library(&self) -> &FileLibrary {
         &self.file_library
     }
@@ -142,24 +445,12 @@ impl AnalysisRunner {
             .collect()
     }
 
-    fn analyze_template<W: LogWriter + ReportWriter>(&mut self, name: &str, writer: &mut W) {
-        writer.write_message(&format!("analyzing template '{name}'"));
-
-        // We take ownership of the CFG and any previously generated reports
-        // here to avoid holding multiple mutable and immutable references to
-        // `self`. This may lead to the CFG being regenerated during analysis if
-        // the template is invoked recursively. If it is then ¯\_(ツ)_/¯.
-        let mut reports = self.take_template_reports(name);
-        if let Ok(cfg) = self.take_template(name) {
-            for analysis_pass in get_analysis_passes() {
-                reports.append(&mut analysis_pass(self, &cfg));
-            }
-            // Re-insert the CFG into the hash map.
-            if self.replace_template(name, cfg) {
-                debug!("template `{name}` CFG was regenerated during analysis");
-            }
-        }
-        writer.write_reports(&reports, &self.file_library);
+    /// Returns the file ID and location (from the parameter list to the end of
+    /// the body) of the template or function with the given name.
+    pub fn definition_location(&self, name: &str) -> Option<(FileID, FileLocation)> {
+        self.definitions
+            .get(name)
+            .map(|definition| (definition.file_id, definition.location.clone()))
     }
 
     pub fn analyze_templates<W: LogWriter + ReportWriter>(
@@ -167,29 +458,8 @@ impl AnalysisRunner {
         writer: &mut W,
         user_input_only: bool,
     ) {
-        for name in self.template_names(user_input_only) {
-            self.analyze_template(&name, writer);
-        }
-    }
-
-    fn analyze_function<W: LogWriter + ReportWriter>(&mut self, name: &str, writer: &mut W) {
-        writer.write_message(&format!("analyzing function '{name}'"));
-
-        // We take ownership of the CFG and any previously generated reports
-        // here to avoid holding multiple mutable and immutable references to
-        // `self`. This may lead to the CFG being regenerated during analysis if
-        // the function is invoked recursively. If it is then ¯\_(ツ)_/¯.
-        let mut reports = self.take_function_reports(name);
-        if let Ok(cfg) = self.take_function(name) {
-            for analysis_pass in get_analysis_passes() {
-                reports.append(&mut analysis_pass(self, &cfg));
-            }
-            // Re-insert the CFG into the hash map.
-            if self.replace_function(name, cfg) {
-                debug!("function `{name}` CFG was regenerated during analysis");
-            }
-        }
-        writer.write_reports(&reports, &self.file_library);
+        let names = self.template_names(user_input_only);
+        self.analyze_definitions(DefinitionKind::Template, names, writer);
     }
 
     pub fn analyze_functions<W: LogWriter + ReportWriter>(
@@ -197,105 +467,241 @@ impl AnalysisRunner {
         writer: &mut W,
         user_input_only: bool,
     ) {
-        for name in self.function_names(user_input_only) {
-            self.analyze_function(&name, writer);
-        }
+        let names = self.function_names(user_input_only);
+        self.analyze_definitions(DefinitionKind::Function, names, writer);
     }
 
-    /// Report cache from CFG generation. These will be emitted when the
-    /// template is analyzed.
-    fn append_template_reports(&mut self, name: &str, reports: &mut ReportCollection) {
-        self.template_reports.entry(name.to_string()).or_default().append(reports);
+    /// Analyzes the given templates or functions in parallel. Results are
+    /// written in order, sorted by name, to ensure that the output is
+    /// deterministic.
+    fn analyze_definitions<W: LogWriter + ReportWriter>(
+        &mut self,
+        kind: DefinitionKind,
+        mut names: Vec<String>,
+        writer: &mut W,
+    ) {
+        names.sort();
+        let mut results = Vec::with_capacity(names.len());
+        let runner = &*self;
+        let names_ref = &names;
+        let (sender, receiver) = mpsc::channel();
+        thread::scope(|scope| {
+            // Definitions are analyzed on the thread pool from a separate
+            // thread, which allows this thread to write results as they become
+            // available.
+            scope.spawn(move || {
+                names_ref.par_iter().enumerate().for_each_with(sender, |sender, (index, name)| {
+                    // Sending only fails if the receiver has been dropped.
+                    let _ = sender.send((index, runner.analyze_definition(kind, name)));
+                });
+            });
+            let mut pending = BTreeMap::new();
+            for (index, result) in receiver {
+                pending.insert(index, result);
+                while let Some((message, reports)) = pending.remove(&results.len()) {
+                    writer.write_message(message);
+                    writer.write_reports(&reports, &runner.file_library);
+                    results.push(reports);
+                }
+            }
+        });
+        for (name, reports) in names.into_iter().zip(results) {
+            self.reusable_results.remove(&name);
+            self.results.insert(name, reports);
+        }
     }
 
-    /// Report cache from CFG generation. These will be emitted when the
-    /// template is analyzed.
-    fn take_template_reports(&mut self, name: &str) -> ReportCollection {
-        self.template_reports.remove(name).unwrap_or_default()
+    /// Analyzes the given template or function, and returns a log message
+    /// together with the corresponding reports.
+    fn analyze_definition(&self, kind: DefinitionKind, name: &str) -> (String, ReportCollection) {
+        if let Some(reports) = self.reusable_results(name) {
+            return (format!("reusing results for {kind} '{name}'"), reports.clone());
+        }
+        let cache_key = self.cache_key(name);
+        let (message, mut reports) = match self.cached_results(cache_key.as_deref(), name) {
+            Some(reports) => (format!("using cached results for {kind} '{name}'"), reports),
+            None => {
+                let reports = self.run_analysis_passes(kind, name);
+                self.cache_results(cache_key.as_deref(), name, &reports);
+                (format!("analyzing {kind} '{name}'"), reports)
+            }
+        };
+        for report in reports.iter_mut() {
+            report.set_definition_name(name);
+        }
+        self.config.apply_severity_overrides(&mut reports);
+        (message, reports)
     }
 
-    /// Report cache from CFG generation. These will be emitted when the
-    /// function is analyzed.
-    fn append_function_reports(&mut self, name: &str, reports: &mut ReportCollection) {
-        self.function_reports.entry(name.to_string()).or_default().append(reports);
+    /// Returns the results for the given template or function from a previous
+    /// run, if they are still valid.
+    fn reusable_results(&self, name: &str) -> Option<&ReportCollection> {
+        let reports = self.reusable_results.get(name)?;
+        match &self.previous_instantiations {
+            // The instantiations may have changed, so results are only reused
+            // if the template is instantiated in the same way.
+            Some(previous) => {
+                (previous.get(name) == self.template_instances(name)).then_some(reports)
+            }
+            None => Some(reports),
+        }
     }
 
-    /// Report cache from CFG generation. These will be emitted when the
-    /// function is analyzed.
-    fn take_function_reports(&mut self, name: &str) -> ReportCollection {
-        self.function_reports.remove(name).unwrap_or_default()
+    /// Runs all analysis passes on the given template or function. Reports
+    /// generated when lifting the definition are included in the result.
+    fn run_analysis_passes(&self, kind: DefinitionKind, name: &str) -> ReportCollection {
+        let lifted_cfg = match kind {
+            DefinitionKind::Template => self.lift_template(name),
+            DefinitionKind::Function => self.lift_function(name),
+        };
+        let Some(lifted_cfg) = lifted_cfg else {
+            return ReportCollection::new();
+        };
+        let mut reports = lifted_cfg.reports.clone();
+        let Some(cfg) = &lifted_cfg.cfg else {
+            return reports;
+        };
+        match (kind, self.template_instances(name)) {
+            (DefinitionKind::Template, Some(instances)) => {
+                // Templates reachable from the main component are analyzed
+                // once for each instantiation. Errors lifting an instantiation
+                // are reported together with the instantiation chain.
+                let mut analyses = Vec::new();
+                for chain in &instances.chains {
+                    let Some(lifted_cfg) = self.instantiations().cfgs.get(chain.last()) else {
+                        continue;
+                    };
+                    match &lifted_cfg.cfg {
+                        Some(cfg) => analyses.push((Some(chain), self.run_passes(cfg))),
+                        None => {
+                            reports.extend(lifted_cfg.reports.iter().cloned().map(|mut report| {
+                                report.add_note(format!(
+                                    "Found when the template is instantiated as `{chain}`."
+                                ));
+                                report
+                            }))
+                        }
+                    }
+                }
+                if instances.symbolic || analyses.is_empty() {
+                    analyses.push((None, self.run_passes(cfg)));
+                }
+                reports.append(&mut instantiations::merge_reports(analyses));
+            }
+            _ => reports.append(&mut self.run_passes(cfg)),
+        }
+        reports
     }
 
-    fn cache_template(&mut self, name: &str) -> Result<&Cfg, AnalysisError> {
-        if !self.template_cfgs.contains_key(name) {
-            // The template CFG needs to be generated from the AST.
-            if self.template_reports.contains_key(name) {
-                // We have already failed to generate the CFG.
-                return Err(AnalysisError::FailedToLiftTemplate { name: name.to_string() });
+    /// Runs all selected analysis passes on the given CFG.
+    fn run_passes(&self, cfg: &Cfg) -> ReportCollection {
+        let mut reports = ReportCollection::new();
+        for analysis_pass in self.passes() {
+            if self.config.pass_selection.is_selected(analysis_pass) {
+                reports.append(&mut analysis_pass.run(self, cfg));
             }
-            // Get the AST corresponding to the template.
-            let Some(ast) = self.template_asts.get(name) else {
-                trace!("failed to lift unknown template `{name}`");
-                return Err(AnalysisError::UnknownTemplate { name: name.to_string() });
-            };
-            // Generate the template CFG from the AST. Cache any reports.
-            let mut reports = ReportCollection::new();
-            let cfg = generate_cfg(ast, &self.curve, &mut reports).map_err(|report| {
-                reports.push(*report);
-                trace!("failed to lift template `{name}`");
-                AnalysisError::FailedToLiftTemplate { name: name.to_string() }
-            })?;
-            self.append_template_reports(name, &mut reports);
-            self.template_cfgs.insert(name.to_string(), cfg);
-            trace!("successfully lifted template `{name}`");
         }
-        Ok(self.template_cfgs.get(name).unwrap())
+        reports
     }
 
-    fn cache_function(&mut self, name: &str) -> Result<&Cfg, AnalysisError> {
-        if !self.function_cfgs.contains_key(name) {
-            // The function CFG needs to be generated from the AST.
-            if self.function_reports.contains_key(name) {
-                // We have already failed to generate the CFG.
-                return Err(AnalysisError::FailedToLiftFunction { name: name.to_string() });
-            }
-            // Get the AST corresponding to the function.
-            let Some(ast) = self.function_asts.get(name) else {
-                trace!("failed to lift unknown function `{name}`");
-                return Err(AnalysisError::UnknownFunction { name: name.to_string() });
-            };
-            // Generate the function CFG from the AST. Cache any reports.
-            let mut reports = ReportCollection::new();
-            let cfg = generate_cfg(ast, &self.curve, &mut reports).map_err(|report| {
-                reports.push(*report);
-                trace!("failed to lift function `{name}`");
-                AnalysisError::FailedToLiftFunction { name: name.to_string() }
-            })?;
-            self.append_function_reports(name, &mut reports);
-            self.function_cfgs.insert(name.to_string(), cfg);
-            trace!("successfully lifted function `{name}`");
+    /// Returns the key used to store the results for the given template or
+    /// function in the result cache.
+    fn cache_key(&self, name: &str) -> Option<String> {
+        self.result_cache.as_ref()?;
+        // The fingerprint of a custom pass is included to ensure that e.g.
+        // changing the pattern or message of a query invalidates cached results.
+        let custom_passes =
+            self.custom_passes.iter().map(|pass| pass.fingerprint()).collect::<Vec<_>>();
+        // Results for templates called by the main component also depend on
+        // how they are instantiated.
+        let context = self.context.as_ref().filter(|_| self.main_callees.contains(name));
+        result_cache::cache_key(
+            name,
+            &self.definitions,
+            &self.curve,
+            &self.config,
+            &custom_passes,
+            context,
+        )
+    }
+
+    /// Returns the results for the given template or function from the result
+    /// cache (if available).
+    fn cached_results(&self, key: Option<&str>, name: &str) -> Option<ReportCollection> {
+        let result_cache = self.result_cache.as_ref()?;
+        result_cache.get(key?, self.definitions.get(name)?)
+    }
+
+    /// Stores the results for the given template or function in the result
+    /// cache (if available).
+    fn cache_results(&self, key: Option<&str>, name: &str, reports: &[Report]) {
+        if let (Some(result_cache), Some(key), Some(definition)) =
+            (&self.result_cache, key, self.definitions.get(name))
+        {
+            result_cache.insert(key, definition, reports);
         }
-        Ok(self.function_cfgs.get(name).unwrap())
     }
 
-    pub fn take_template(&mut self, name: &str) -> Result<Cfg, AnalysisError> {
-        self.cache_template(name)?;
-        // The CFG must be available since caching was successful.
-        Ok(self.template_cfgs.remove(name).unwrap())
+    /// Lifts the given template to a CFG on first use. Returns `None` if the
+    /// template is unknown.
+    fn lift_template(&self, name: &str) -> Option<&LiftedCfg> {
+        let entry = self.template_cfgs.get(name)?;
+        let ast = self.template_asts.get(name)?;
+        Some(entry.get_or_init(|| lift_cfg(ast, &self.curve, &self.functions, name)))
+    }
+
+    /// Lifts the given function to a CFG on first use. Returns `None` if the
+    /// function is unknown.
+    fn lift_function(&self, name: &str) -> Option<&LiftedCfg> {
+        let entry = self.function_cfgs.get(name)?;
+        let ast = self.function_asts.get(name)?;
+        Some(entry.get_or_init(|| lift_cfg(ast, &self.curve, &self.functions, name)))
     }
 
-    pub fn take_function(&mut self, name: &str) -> Result<Cfg, AnalysisError> {
-        self.cache_function(name)?;
-        // The CFG must be available since caching was successful.
-        Ok(self.function_cfgs.remove(name).unwrap())
+    fn template_cfg(&self, name: &str) -> Result<&Cfg, AnalysisError> {
+        match self.lift_template(name) {
+            Some(LiftedCfg { cfg: Some(cfg), .. }) => Ok(cfg),
+            Some(_) => Err(AnalysisError::FailedToLiftTemplate { name: name.to_string() }),
+            None => {
+                trace!("failed to lift unknown template `{name}`");
+                Err(AnalysisError::UnknownTemplate { name: name.to_string() })
+            }
+        }
     }
 
-    pub fn replace_template(&mut self, name: &str, cfg: Cfg) -> bool {
-        self.template_cfgs.insert(name.to_string(), cfg).is_some()
+    fn function_cfg(&self, name: &str) -> Result<&Cfg, AnalysisError> {
+        match self.lift_function(name) {
+            Some(LiftedCfg { cfg: Some(cfg), .. }) => Ok(cfg),
+            Some(_) => Err(AnalysisError::FailedToLiftFunction { name: name.to_string() }),
+            None => {
+                trace!("failed to lift unknown function `{name}`");
+                Err(AnalysisError::UnknownFunction { name: name.to_string() })
+            }
+        }
     }
 
-    pub fn replace_function(&mut self, name: &str, cfg: Cfg) -> bool {
-        self.function_cfgs.insert(name.to_string(), cfg).is_some()
+    /// Returns the CFG of the template or function containing the given byte
+    /// offset in the given file. Returns `None` if the offset is not contained
+    /// in a definition, or if the definition could not be lifted.
+    pub fn cfg_at(&self, file_id: FileID, offset: usize) -> Option<&Cfg> {
+        let contains = |body: &ast::Statement, params: FileLocation| {
+            let body = body.get_meta().file_location();
+            (body.start <= offset && offset < body.end)
+                || (params.start <= offset && offset < params.end)
+        };
+        if let Some(name) = self.template_asts.iter().find_map(|(name, ast)| {
+            (ast.get_file_id() == file_id && contains(ast.get_body(), ast.get_param_location()))
+                .then_some(name)
+        }) {
+            return self.template_cfg(name).ok();
+        }
+        if let Some(name) = self.function_asts.iter().find_map(|(name, ast)| {
+            (ast.get_file_id() == file_id && contains(ast.get_body(), ast.get_param_location()))
+                .then_some(name)
+        }) {
+            return self.function_cfg(name).ok();
+        }
+        None
     }
 }
 
@@ -308,12 +714,16 @@ impl AnalysisContext for AnalysisRunner {
         self.function_asts.contains_key(name)
     }
 
-    fn template(&mut self, name: &str) -> Result<&Cfg, AnalysisError> {
-        self.cache_template(name)
+    fn template(&self, name: &str) -> Result<&Cfg, AnalysisError> {
+        self.template_cfg(name)
     }
 
-    fn function(&mut self, name: &str) -> Result<&Cfg, AnalysisError> {
-        self.cache_function(name)
+    fn function(&self, name: &str) -> Result<&Cfg, AnalysisError> {
+        self.function_cfg(name)
+    }
+
+    fn config(&self) -> &AnalysisConfig {
+        &self.config
     }
 
     fn underlying_str(
@@ -335,13 +745,64 @@ impl AnalysisContext for AnalysisRunner {
     }
 }
 
+fn lift_cfg<Ast: IntoCfg>(
+    ast: Ast,
+    curve: &Curve,
+    functions: &Arc<FunctionLibrary>,
+    name: &str,
+) -> LiftedCfg {
+    let mut reports = ReportCollection::new();
+    match generate_cfg(ast, curve, functions, &mut reports) {
+        Ok(cfg) => {
+            trace!("successfully lifted `{name}`");
+            LiftedCfg { cfg: Some(cfg), reports }
+        }
+        Err(report) => {
+            trace!("failed to lift `{name}`");
+            reports.push(*report);
+            LiftedCfg { cfg: None, reports }
+        }
+    }
+}
+
+/// Lifts a specialized CFG for the given template instantiation. If lifting
+/// fails, only the error is reported, since any other reports are also
+/// generated when lifting the template itself.
+fn lift_instance<Ast: IntoCfg>(
+    ast: Ast,
+    curve: &Curve,
+    functions: &Arc<FunctionLibrary>,
+    instantiation: &Instantiation,
+) -> LiftedCfg {
+    let mut reports = ReportCollection::new();
+    let cfg =
+        ast.into_cfg(curve, &mut reports).map_err(|error| Box::new(error.into())).and_then(|cfg| {
+            cfg.with_arguments(&instantiation.arguments)
+                .with_functions(functions.clone())
+                .into_ssa()
+                .map_err(|error| Box::new(error.into()))
+        });
+    match cfg {
+        Ok(cfg) => {
+            trace!("successfully lifted `{instantiation}`");
+            LiftedCfg { cfg: Some(cfg), reports: ReportCollection::new() }
+        }
+        Err(report) => {
+            trace!("failed to lift `{instantiation}`");
+            LiftedCfg { cfg: None, reports: vec![*report] }
+        }
+    }
+}
+
 fn generate_cfg<Ast: IntoCfg>(
     ast: Ast,
     curve: &Curve,
+    functions: &Arc<FunctionLibrary>,
     reports: &mut ReportCollection,
 ) -> Result<Cfg, Box<Report>> {
     ast.into_cfg(curve, reports)
         .map_err(|error| Box::new(error.into()))?
+        .with_functions(functions.clone())
         .into_ssa()
         .map_err(|error| Box::new(error.into()))
 }
@@ -349,12 +810,13 @@ fn generate_cfg<Ast: IntoCfg>(
 #[cfg(test)]
 mod tests {
     use program_structure::ir::Statement;
+    use program_structure::writers::{CachedWriter, StderrLogWriter};
 
     use super::*;
 
     #[test]
     fn test_function() {
-        let mut runner = AnalysisRunner::new(Curve::Goldilocks).with_src(&[r#"
+        let runner = AnalysisRunner::new(Curve::Goldilocks).with_src(&[r#"
             function foo(a) {
                 return a[0] + a[1];
             }
@@ -363,27 +825,21 @@ mod tests {
         // Check that `foo` is a known function, that we can access the CFG
         // for `foo`, and that the CFG is properly cached.
         assert!(runner.is_function("foo"));
-        assert!(!runner.function_cfgs.contains_key("foo"));
-        assert!(runner.function("foo").is_ok());
-        assert!(runner.function_cfgs.contains_key("foo"));
-
-        // Check that the `take_function` and `replace_function` APIs work as expected.
-        let cfg = runner.take_function("foo").unwrap();
-        assert!(!runner.function_cfgs.contains_key("foo"));
-        assert!(!runner.replace_function("foo", cfg));
-        assert!(runner.function_cfgs.contains_key("foo"));
+        assert!(runner.function_cfgs["foo"].get().is_none());
+        let cfg = runner.function("foo").unwrap() as *const Cfg;
+        assert!(runner.function_cfgs["foo"].get().is_some());
+        assert!(std::ptr::eq(runner.function("foo").unwrap(), cfg));
 
         // Check that `baz` is not a known function, that attempting to access
         // `baz` produces an error, and that nothing is cached.
         assert!(!runner.is_function("baz"));
-        assert!(!runner.function_cfgs.contains_key("baz"));
         assert!(matches!(runner.function("baz"), Err(AnalysisError::UnknownFunction { .. })));
         assert!(!runner.function_cfgs.contains_key("baz"));
     }
 
     #[test]
     fn test_template() {
-        let mut runner = AnalysisRunner::new(Curve::Goldilocks).with_src(&[r#"
+        let runner = AnalysisRunner::new(Curve::Goldilocks).with_src(&[r#"
             template Foo(n) {
                 signal input a[2];
 
@@ -394,20 +850,14 @@ mod tests {
         // Check that `Foo` is a known template, that we can access the CFG
         // for `Foo`, and that the CFG is properly cached.
         assert!(runner.is_template("Foo"));
-        assert!(!runner.template_cfgs.contains_key("Foo"));
-        assert!(runner.template("Foo").is_ok());
-        assert!(runner.template_cfgs.contains_key("Foo"));
-
-        // Check that the `take_template` and `replace_template` APIs work as expected.
-        let cfg = runner.take_template("Foo").unwrap();
-        assert!(!runner.template_cfgs.contains_key("Foo"));
-        assert!(!runner.replace_template("Foo", cfg));
-        assert!(runner.template_cfgs.contains_key("Foo"));
+        assert!(runner.template_cfgs["Foo"].get().is_none());
+        let cfg = runner.template("Foo").unwrap() as *const Cfg;
+        assert!(runner.template_cfgs["Foo"].get().is_some());
+        assert!(std::ptr::eq(runner.template("Foo").unwrap(), cfg));
 
         // Check that `Baz` is not a known template, that attempting to access
         // `Baz` produces an error, and that nothing is cached.
         assert!(!runner.is_template("Baz"));
-        assert!(!runner.template_cfgs.contains_key("Baz"));
         assert!(matches!(runner.template("Baz"), Err(AnalysisError::UnknownTemplate { .. })));
         assert!(!runner.template_cfgs.contains_key("Baz"));
     }
@@ -415,7 +865,7 @@ mod tests {
     #[test]
     fn test_underlying_str() {
         use Statement::*;
-        let mut runner = AnalysisRunner::new(Curve::Goldilocks).with_src(&[r#"
+        let runner = AnalysisRunner::new(Curve::Goldilocks).with_src(&[r#"
             template Foo(n) {
                 signal input a[2];
 
@@ -423,7 +873,7 @@ mod tests {
             }
         "#]);
 
-        let cfg = runner.take_template("Foo").unwrap();
+        let cfg = runner.template("Foo").unwrap();
         for stmt in cfg.entry_block().iter() {
             let file_id = stmt.meta().file_id().unwrap();
             let file_location = stmt.meta().file_location();
@@ -436,4 +886,441 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_cfg_at() {
+        use program_structure::ir::degree_meta::Degree;
+        use program_structure::ir::value_meta::ValueReduction;
+
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+                var m = 3;
+                out <== in * in + m;
+            }
+        "#;
+        let runner = AnalysisRunner::new(Curve::Goldilocks).with_src(&[src]);
+        assert!(runner.cfg_at(0, 0).is_none());
+
+        // The constant `m` in `in * in + m`.
+        let offset = src.rfind("m;").unwrap();
+        let cfg = runner.cfg_at(0, offset).unwrap();
+        assert_eq!(cfg.name(), "T");
+        let node = cfg.find_node(0, offset).unwrap();
+        assert_eq!(format!("{:?}", node.variable_name().unwrap()), "m.0");
+        assert_eq!(node.value(), Some(&ValueReduction::FieldElement { value: 3.into() }));
+        assert_eq!(node.degree().unwrap().end(), Degree::Constant);
+
+        // The product `in * in`.
+        let offset = src.find("* in").unwrap();
+        let node = runner.cfg_at(0, offset).unwrap().find_node(0, offset).unwrap();
+        assert_eq!(node.to_string(), "(in * in)");
+        assert_eq!(node.value(), None);
+        assert_eq!(node.degree().unwrap().end(), Degree::Quadratic);
+
+        // The input signal `in`.
+        let offset = src.find("in * in").unwrap();
+        let node = runner.cfg_at(0, offset).unwrap().find_node(0, offset).unwrap();
+        assert_eq!(node.variable_type().unwrap().to_string(), "signal input");
+    }
+
+    #[test]
+    fn test_function_calls() {
+        let src = [
+            r#"
+            function nbits(a) {
+                var n = 1;
+                var r = 0;
+                while (n - 1 < a) {
+                    r++;
+                    n *= 2;
+                }
+                return r;
+            }
+            "#,
+            r#"
+            template T() {
+                signal input in;
+                signal output out[8];
+                component n2b = Num2Bits(nbits(255));
+                n2b.in <== in;
+                out <== n2b.out;
+            }
+            "#,
+        ];
+        let mut runner = AnalysisRunner::new(Curve::Bn254).with_src(&src);
+        let cfg = runner.template("T").unwrap();
+        let offset = src[1].find("nbits(255)").unwrap();
+        let node = cfg.find_node(1, offset).unwrap();
+        assert_eq!(node.to_string(), "nbits(255)");
+        assert_eq!(node.value().unwrap().to_string(), "8");
+
+        // Since the size passed to `Num2Bits` is known, `Num2Bits` is not
+        // reported.
+        let mut writer = CachedWriter::new(StderrLogWriter::new());
+        runner.analyze_templates(&mut writer, false);
+        assert!(runner.results["T"].iter().all(|report| report.id() != "CS0010"));
+    }
+
+    #[test]
+    fn test_constant_tables() {
+        let src = [
+            r#"
+            function sizes() {
+                return [254, 8];
+            }
+            "#,
+            r#"
+            template T() {
+                signal input in;
+                signal output out[8];
+                var n[2] = sizes();
+                component n2b = Num2Bits(n[1]);
+                n2b.in <== in;
+                out <== n2b.out;
+            }
+            "#,
+        ];
+        let mut runner = AnalysisRunner::new(Curve::Bn254).with_src(&src);
+        let cfg = runner.template("T").unwrap();
+        let offset = src[1].find("n[1]").unwrap();
+        let node = cfg.find_node(1, offset).unwrap();
+        assert_eq!(node.to_string(), "n[1]");
+        assert_eq!(node.value().unwrap().to_string(), "8");
+
+        // Since the size passed to `Num2Bits` is known, `Num2Bits` is not
+        // reported.
+        let mut writer = CachedWriter::new(StderrLogWriter::new());
+        runner.analyze_templates(&mut writer, false);
+        assert!(runner.results["T"].iter().all(|report| report.id() != "CS0010"));
+    }
+
+    #[test]
+    fn test_analyze_templates() {
+        let src = (0..16)
+            .rev()
+            .map(|i| {
+                format!(
+                    r#"
+                    template T{i:02}() {{
+                        signal input in;
+                        signal output out;
+                        out <-- in;
+                    }}
+                    "#
+                )
+            })
+            .collect::<Vec<_>>();
+        let src = src.iter().map(String::as_str).collect::<Vec<_>>();
+        let mut runner = AnalysisRunner::new(Curve::Goldilocks).with_src(&src);
+        let mut writer = CachedWriter::new(StderrLogWriter::new());
+        runner.analyze_templates(&mut writer, false);
+
+        // Reports are written in order, sorted by template name.
+        let mut names = writer
+            .reports()
+            .iter()
+            .map(|report| report.definition_name().unwrap().to_string())
+            .collect::<Vec<_>>();
+        names.dedup();
+        let expected = (0..16).map(|i| format!("T{i:02}")).collect::<Vec<_>>();
+        assert_eq!(names, expected);
+        assert_eq!(runner.results.len(), 16);
+    }
+
+    #[test]
+    fn test_custom_passes() {
+        use crate::pass_registry::PassSelection;
+        use program_structure::report_code::ReportCode;
+
+        const CUSTOM: ReportCode = ReportCode::Custom { id: "TEST001", name: "test" };
+
+        struct CustomPass;
+
+        impl AnalysisPass for CustomPass {
+            fn id(&self) -> &str {
+                "custom"
+            }
+
+            fn name(&self) -> &str {
+                "Custom"
+            }
+
+            fn description(&self) -> &str {
+                "Reports the name of each template or function."
+            }
+
+            fn report_codes(&self) -> &[ReportCode] {
+                &[CUSTOM]
+            }
+
+            fn run(&self, _: &dyn AnalysisContext, cfg: &Cfg) -> ReportCollection {
+                vec![Report::info(cfg.name().to_string(), CUSTOM)]
+            }
+        }
+
+        let custom_passes: [Arc<dyn AnalysisPass>; 1] = [Arc::new(CustomPass)];
+        let config = AnalysisConfig {
+            pass_selection: PassSelection::new(&["custom".to_string()], &[]),
+            ..Default::default()
+        };
+        let mut runner = AnalysisRunner::new(Curve::Goldilocks)
+            .with_config(config)
+            .with_passes(&custom_passes)
+            .with_src(&["template T() { signal input in; }"]);
+        assert_eq!(runner.passes().last().unwrap().id(), "custom");
+        assert!(runner.config.pass_selection.validate(runner.passes()).is_ok());
+
+        // Only the custom pass is run.
+        let mut writer = CachedWriter::new(StderrLogWriter::new());
+        runner.analyze_templates(&mut writer, false);
+        let reports = writer.reports();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].id(), "TEST001");
+        assert_eq!(reports[0].message(), "T");
+    }
+
+    #[test]
+    fn test_instantiations() {
+        let dir =
+            std::env::temp_dir().join(format!("circomspect-instances-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("main.circom");
+        let src = r#"
+            pragma circom 2.0.0;
+
+            template Num2Bits(n) {
+                signal input in;
+                signal output out[n];
+                var lc = 0;
+                var e2 = 1;
+                for (var i = 0; i < n; i++) {
+                    out[i] <-- (in >> i) & 1;
+                    out[i] * (out[i] - 1) === 0;
+                    lc += out[i] * e2;
+                    e2 = e2 + e2;
+                }
+                lc === in;
+            }
+
+            template Foo(n) {
+                signal input in;
+                signal output out[n];
+                component n2b = Num2Bits(n);
+                n2b.in <== in;
+                out <== n2b.out;
+            }
+
+            template Main(m) {
+                signal input in;
+                signal output a[m];
+                signal output b[254];
+                component foo = Foo(m);
+                component bar = Foo(254);
+                foo.in <== in;
+                bar.in <== in;
+                a <== foo.out;
+                b <== bar.out;
+            }
+
+            component main = Main(2 * 4);
+        "#;
+        std::fs::write(&file, src).unwrap();
+        let (mut runner, _) = AnalysisRunner::new(Curve::Bn254).with_files(&[file], 10);
+        let instances = &runner.instantiations().templates;
+        assert_eq!(instances["Foo"].chains.len(), 2);
+        assert!(!instances["Foo"].symbolic);
+        assert_eq!(
+            instances["Num2Bits"].chains[1].to_string(),
+            "Main(8) -> Foo(254) -> Num2Bits(254)"
+        );
+
+        // `Num2Bits(8)` is safe, so only the second instantiation of `Foo` is
+        // reported.
+        let mut writer = CachedWriter::new(StderrLogWriter::new());
+        runner.analyze_templates(&mut writer, true);
+        let reports = &runner.results["Foo"];
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].id(), "CS0010");
+        assert_eq!(
+            reports[0].notes().last().unwrap(),
+            "Found when the template is instantiated as `Main(8) -> Foo(254)`."
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_lazy_instantiations() {
+        let dir = std::env::temp_dir().join(format!("circomspect-lazy-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("main.circom");
+        let src = r#"
+            pragma circom 2.0.0;
+
+            template A(n) {
+                signal input in;
+                signal output out[n];
+                for (var i = 0; i < n; i++) {
+                    out[i] <-- in;
+                }
+            }
+
+            template Main() {
+                signal input in;
+                signal output out[2];
+                component a = A(2);
+                a.in <== in;
+                out <== a.out;
+            }
+
+            component main = Main();
+        "#;
+        std::fs::write(&file, src).unwrap();
+        let files = [file];
+        let cache = || ResultCache::open(&dir.join("cache")).unwrap();
+        let analyze = || {
+            let (mut runner, _) = AnalysisRunner::new(Curve::Goldilocks)
+                .with_result_cache(cache())
+                .with_files(&files, 10);
+            // Instantiations are not enumerated before the analysis.
+            assert!(runner.instantiations.get().is_none());
+            let mut writer = CachedWriter::new(StderrLogWriter::new());
+            runner.analyze_templates(&mut writer, true);
+            runner
+        };
+
+        // Instantiations are enumerated when the templates are analyzed.
+        let first_runner = analyze();
+        assert!(first_runner.instantiations.get().is_some());
+        let results = first_runner.results["A"].clone();
+        assert!(!results.is_empty());
+
+        // Instantiations are not enumerated if all results are cached.
+        let second_runner = analyze();
+        assert!(second_runner.instantiations.get().is_none());
+        assert_eq!(second_runner.results["A"].len(), results.len());
+
+        // Results from a previous run are not reused if the instantiations of
+        // the template changed. If the previous run did not enumerate any
+        // instantiations, results for templates called by the main component
+        // are never reused.
+        std::fs::write(&files[0], src.replace("A(2)", "A(3)").replace("[2]", "[3]")).unwrap();
+        let with_previous_run = |previous| {
+            AnalysisRunner::new(Curve::Goldilocks)
+                .with_previous_run(previous)
+                .with_files(&files, 10)
+                .0
+        };
+        let runner = with_previous_run(second_runner);
+        assert!(!runner.reusable_results.contains_key("A"));
+        let runner = with_previous_run(first_runner);
+        assert!(runner.reusable_results.contains_key("A"));
+        assert!(runner.reusable_results("A").is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_instantiation_lift_errors() {
+        let dir =
+            std::env::temp_dir().join(format!("circomspect-lift-errors-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("main.circom");
+        let src = r#"
+            pragma circom 2.0.0;
+
+            template A(n, n) {
+                signal input in;
+                signal output out;
+                out <== in * n;
+            }
+
+            template Main() {
+                signal input in;
+                signal output out;
+                component a = A(2, 3);
+                a.in <== in;
+                out <== a.out;
+            }
+
+            component main = Main();
+        "#;
+        std::fs::write(&file, src).unwrap();
+        let (runner, _) = AnalysisRunner::new(Curve::Goldilocks).with_files(&[file], 10);
+
+        // If lifting an instantiation fails, the error is recorded and the
+        // template falls back to a symbolic analysis.
+        let instantiations = runner.instantiations();
+        assert!(instantiations.templates["A"].symbolic);
+        let arguments = [2, 3].map(|value| ValueReduction::FieldElement { value: value.into() });
+        let lifted_cfg = &instantiations.cfgs[&Instantiation::new("A", arguments.to_vec())];
+        assert!(lifted_cfg.cfg.is_none());
+        assert_eq!(lifted_cfg.reports.len(), 1);
+        assert_eq!(lifted_cfg.reports[0].id(), "CS0002");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_with_previous_run() {
+        let dir = std::env::temp_dir().join(format!("circomspect-runner-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("test.circom");
+        let src = r#"
+            pragma circom 2.0.0;
+
+            function f(n) {
+                return n + 1;
+            }
+
+            template A() {
+                signal input in;
+                signal output out;
+                out <-- in;
+            }
+
+            template B() {
+                signal input in;
+                signal output out;
+                out <-- f(1) * in;
+            }
+
+            template C() {
+                signal input in;
+                component a = A();
+                a.in <== in;
+            }
+        "#;
+        std::fs::write(&file, src).unwrap();
+        let files = [file.clone()];
+        let mut writer = CachedWriter::new(StderrLogWriter::new());
+        let (mut runner, _) = AnalysisRunner::new(Curve::Goldilocks).with_files(&files, 1);
+        runner.analyze_functions(&mut writer, true);
+        runner.analyze_templates(&mut writer, true);
+        let previous_reports = runner.results["B"].clone();
+        let previous_runner = AnalysisRunner::new(Curve::Goldilocks).with_files(&files, 1).0;
+
+        // Edit `A` (moving `B`). The results for `A` and its caller `C` must be
+        // recomputed, while the results for `B` and `f` can be reused.
+        std::fs::write(&file, src.replace("out <-- in;", "out <--   in;")).unwrap();
+        let (runner, _) =
+            AnalysisRunner::new(Curve::Goldilocks).with_previous_run(runner).with_files(&files, 1);
+        let mut reused = runner.reusable_results.keys().cloned().collect::<Vec<_>>();
+        reused.sort();
+        assert_eq!(reused, vec!["B", "f"]);
+
+        // The reused results for `B` must be moved to the new location of `B`.
+        let snippets = |runner: &AnalysisRunner, reports: &[Report]| {
+            reports
+                .iter()
+                .map(|report| {
+                    let label = &report.primary()[0];
+                    runner.underlying_str(&label.file_id, &label.range).unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        let reports = &runner.reusable_results["B"];
+        assert!(!reports.is_empty());
+        assert_eq!(snippets(&runner, reports), snippets(&previous_runner, &previous_reports));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,6 +1,153 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
 use program_structure::ast::Version;
+use program_structure::report::{Report, MessageCategory};
+use program_structure::report_code::ReportCode;
+
+use crate::pass_registry::{AnalysisPass, PassSelection};
 
 pub const COMPILER_VERSION: Version = (2, 1, 4);
 pub const DEFAULT_LEVEL: &str = "WARNING";
 pub const DEFAULT_CURVE: &str = "BN254";
 pub const DEFAULT_DEPTH: &str = "1000";
+
+/// The version of the analysis implemented by this crate. Cached results are
+/// keyed on this version, so it must be bumped whenever a change to the crate
+/// may change the results of a built-in analysis pass. (This is enforced by
+/// `result_cache::tests::test_analysis_version`.)
+pub const ANALYSIS_VERSION: u32 = 1;
+
+/// Known templates that are commonly instantiated without accessing the
+/// corresponding output signals.
+pub const DEFAULT_UNUSED_OUTPUT_SIGNAL_ALLOW_LIST: [&str; 1] = ["Num2Bits"];
+
+/// The minimum number of separate constraints that each intermediate signal
+/// should occur in.
+pub const DEFAULT_MIN_CONSTRAINT_COUNT: usize = 2;
+
+/// Analysis configuration shared by all analysis passes.
+#[derive(Clone, Debug)]
+pub struct AnalysisConfig {
+    /// Templates that may be instantiated without using the corresponding
+    /// output signals. (Used by the `unused-output-signal` pass.)
+    pub unused_output_signal_allow_list: Vec<String>,
+    /// The minimum number of separate constraints that each intermediate
+    /// signal should occur in. (Used by the `under-constrained-signal` pass.)
+    pub min_constraint_count: usize,
+    /// The maximum cyclomatic complexity of a function or template. If this is
+    /// not set, the cyclomatic complexity is not checked.
+    pub max_cyclomatic_complexity: Option<usize>,
+    /// The maximum number of parameters of a function or template. If this is
+    /// not set, the number of parameters is not checked.
+    pub max_parameters: Option<usize>,
+    /// Overrides for the category of reports, keyed by report ID or name.
+    pub severity_overrides: HashMap<String, MessageCategory>,
+    /// The analysis passes to run.
+    pub pass_selection: PassSelection,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        AnalysisConfig {
+            unused_output_signal_allow_list: DEFAULT_UNUSED_OUTPUT_SIGNAL_ALLOW_LIST
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            min_constraint_count: DEFAULT_MIN_CONSTRAINT_COUNT,
+            max_cyclomatic_complexity: None,
+            max_parameters: None,
+            severity_overrides: HashMap::new(),
+            pass_selection: PassSelection::default(),
+        }
+    }
+}
+
+impl AnalysisConfig {
+    /// Returns an error if the pass selection refers to an unknown analysis
+    /// pass, or if a severity override refers to an unknown report code.
+    pub fn validate<'a>(
+        &self,
+        passes: impl IntoIterator<Item = &'a dyn AnalysisPass>,
+    ) -> Result<()> {
+        let passes = passes.into_iter().collect::<Vec<_>>();
+        self.pass_selection.validate(passes.iter().copied())?;
+        let codes = ReportCode::builtin_codes()
+            .iter()
+            .chain(passes.iter().flat_map(|pass| pass.report_codes()))
+            .flat_map(|code| [code.id(), code.name()])
+            .collect::<Vec<_>>();
+        let mut keys = self.severity_overrides.keys().collect::<Vec<_>>();
+        keys.sort();
+        match keys.into_iter().find(|key| !codes.contains(key)) {
+            Some(key) => Err(anyhow!(
+                "unknown result `{key}` in severity overrides (use `--list-passes` to list all \
+                 result IDs)"
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Updates the category of each report with a configured override.
+    pub fn apply_severity_overrides(&self, reports: &mut [Report]) {
+        if self.severity_overrides.is_empty() {
+            return;
+        }
+        for report in reports.iter_mut() {
+            let category = self
+                .severity_overrides
+                .get(&report.id())
+                .or_else(|| self.severity_overrides.get(&report.name()));
+            if let Some(category) = category {
+                report.set_category(*category);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use program_structure::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_severity_overrides() {
+        let mut config = AnalysisConfig::default();
+        config.severity_overrides.insert("CS0005".to_string(), MessageCategory::Info);
+        config
+            .severity_overrides
+            .insert("under-constrained-signal".to_string(), MessageCategory::Error);
+
+        let mut reports = vec![
+            Report::warning("".to_string(), ReportCode::SignalAssignmentStatement),
+            Report::warning("".to_string(), ReportCode::UnderConstrainedSignal),
+            Report::warning("".to_string(), ReportCode::FieldElementComparison),
+        ];
+        config.apply_severity_overrides(&mut reports);
+        assert_eq!(reports[0].category(), &MessageCategory::Info);
+        assert_eq!(reports[1].category(), &MessageCategory::Error);
+        assert_eq!(reports[2].category(), &MessageCategory::Warning);
+    }
+
+    #[test]
+    fn test_validate() {
+        use crate::pass_registry::analysis_passes;
+
+        let mut config = AnalysisConfig::default();
+        config.severity_overrides.insert("CS0005".to_string(), MessageCategory::Info);
+        config.severity_overrides.insert("P1000".to_string(), MessageCategory::Info);
+        config.severity_overrides.insert("unused-output-signal".to_string(), MessageCategory::Info);
+        assert!(config.validate(analysis_passes(&[])).is_ok());
+
+        config.severity_overrides.insert("CS9999".to_string(), MessageCategory::Info);
+        assert!(config.validate(analysis_passes(&[])).is_err());
+
+        let config = AnalysisConfig {
+            pass_selection: PassSelection::new(&[], &["CS0005".to_string()]),
+            ..Default::default()
+        };
+        assert!(config.validate(analysis_passes(&[])).is_err());
+    }
+}
@@ -7,6 +7,8 @@ use program_structure::file_definition::{FileID, FileLocation};
 use program_structure::ir::value_meta::ValueReduction;
 use program_structure::ir::*;
 
+use crate::analysis_context::AnalysisContext;
+
 pub struct ConstantBranchConditionWarning {
     value: bool,
     file_id: Option<FileID>,
@@ -32,7 +34,19 @@ impl ConstantBranchConditionWarning {
 
 /// This analysis pass uses basic constant propagation to determine cases where
 /// an if-statement condition is always true or false.
-pub fn find_constant_conditional_statement(cfg: &Cfg) -> ReportCollection {
+pub fn find_constant_conditional_statement(
+    context: &dyn AnalysisContext,
+    cfg: &Cfg,
+) -> ReportCollection {
+    if !cfg.arguments().is_empty() {
+        // Conditions depending on the template parameters are constant in
+        // each template instantiation. To avoid reporting these, we analyze
+        // the template with symbolic parameters instead.
+        return match context.template(cfg.name()) {
+            Ok(template) => find_constant_conditional_statement(context, template),
+            Err(_) => ReportCollection::new(),
+        };
+    }
     debug!("running constant conditional analysis pass");
     let mut reports = ReportCollection::new();
     for basic_block in cfg.iter() {
@@ -66,9 +80,12 @@ fn build_report(meta: &Meta, value: bool) -> Report {
 
 #[cfg(test)]
 mod tests {
+    use num_bigint::BigInt;
     use parser::parse_definition;
     use program_structure::{cfg::IntoCfg, constants::Curve};
 
+    use crate::analysis_runner::AnalysisRunner;
+
     use super::*;
 
     #[test]
@@ -102,6 +119,33 @@ mod tests {
         validate_reports(src, 0);
     }
 
+    #[test]
+    fn test_template_instantiation() {
+        // The condition is constant for each instantiation of the template, but
+        // depends on the template parameter.
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+                if (n > 0) {
+                    out <== in;
+                } else {
+                    out <== 0;
+                }
+            }
+        "#;
+        let context = AnalysisRunner::new(Curve::default()).with_src(&[src]);
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .with_arguments(&[ValueReduction::FieldElement { value: BigInt::from(5) }])
+            .into_ssa()
+            .unwrap();
+        assert!(find_constant_conditional_statement(&context, &cfg).is_empty());
+    }
+
     fn validate_reports(src: &str, expected_len: usize) {
         // Build CFG.
         let mut reports = ReportCollection::new();
@@ -114,7 +158,8 @@ mod tests {
         assert!(reports.is_empty());
 
         // Generate report collection.
-        let reports = find_constant_conditional_statement(&cfg);
+        let context = AnalysisRunner::new(Curve::default());
+        let reports = find_constant_conditional_statement(&context, &cfg);
 
         assert_eq!(reports.len(), expected_len);
     }
@@ -2,19 +2,24 @@ use log::{debug, trace};
 use std::collections::{HashMap, HashSet};
 
 use program_structure::cfg::Cfg;
-use program_structure::intermediate_representation::variable_meta::VariableMeta;
 use program_structure::intermediate_representation::AssignOp;
 use program_structure::ir::variable_meta::VariableUse;
 use program_structure::ir::{Statement, VariableName};
 
+use crate::variable_access::{self, AccessSet, VariableAccess};
+
 /// This analysis computes the transitive closure of the constraint relation.
 /// (Note that the resulting relation will be symmetric, but not reflexive in
-/// general.)
+/// general.) Signal and component arrays are tracked element by element, so
+/// that `c[0].in` and `c[1].in` are considered separately.
 #[derive(Clone, Default)]
 pub struct ConstraintAnalysis {
-    constraint_map: HashMap<VariableName, HashSet<VariableName>>,
+    constraint_map: HashMap<VariableAccess, HashSet<VariableAccess>>,
+    // The accesses occurring in some constraint.
+    sources: AccessSet,
     declarations: HashMap<VariableName, VariableUse>,
-    definitions: HashMap<VariableName, VariableUse>,
+    definitions: Vec<(VariableAccess, VariableUse)>,
+    definition_indices: HashMap<VariableAccess, usize>,
 }
 
 impl ConstraintAnalysis {
@@ -23,28 +28,28 @@ impl ConstraintAnalysis {
     }
 
     /// Add the variable use corresponding to the definition of the variable.
-    fn add_definition(&mut self, var: &VariableUse) {
-        // TODO: Since we don't version components and signals, we may end up
-        // overwriting component initializations here. For example, in the
-        // following case the component initialization will be clobbered.
-        //
-        //   component c[2];
-        //   ...
-        //   c[0].in[0] <== 0;
-        //   c[1].in[1] <== 1;
-        //
-        // The constraint map should probably track VariableAccesses rather
-        // than VariableNames.
-        self.definitions.insert(var.name().clone(), var.clone());
+    /// Definitions of distinct elements of signal and component arrays are
+    /// tracked separately.
+    fn add_definition(&mut self, access: &VariableAccess, var: &VariableUse) {
+        match self.definition_indices.get(access) {
+            Some(index) => self.definitions[*index].1 = var.clone(),
+            None => {
+                self.definition_indices.insert(access.clone(), self.definitions.len());
+                self.definitions.push((access.clone(), var.clone()));
+            }
+        }
     }
 
     /// Get the variable use corresponding to the definition of the variable.
-    pub fn get_definition(&self, var: &VariableName) -> Option<VariableUse> {
-        self.definitions.get(var).cloned()
+    pub fn get_definition(&self, var: &VariableAccess) -> Option<VariableUse> {
+        let index = self.definition_indices.get(var)?;
+        self.definitions.get(*index).map(|(_, var)| var.clone())
     }
 
-    pub fn definitions(&self) -> impl Iterator<Item = &VariableUse> {
-        self.definitions.values()
+    /// Returns all definitions together with the defined accesses, in the
+    /// order in which they occur in the CFG.
+    pub fn definitions(&self) -> impl Iterator<Item = (&VariableAccess, &VariableUse)> {
+        self.definitions.iter().map(|(access, var)| (access, var))
     }
 
     /// Add the variable use corresponding to the declaration of the variable.
@@ -62,27 +67,63 @@ impl ConstraintAnalysis {
     }
 
     /// Add a constraint from source to sink.
-    fn add_constraint_step(&mut self, source: &VariableName, sink: &VariableName) {
+    fn add_constraint_step(&mut self, source: &VariableAccess, sink: &VariableAccess) {
+        trace!("adding constraint step with source `{source}` and sink `{sink}`");
         let sinks = self.constraint_map.entry(source.clone()).or_default();
         sinks.insert(sink.clone());
+        self.sources.insert(source.clone());
     }
 
-    /// Returns variables constrained in a single step by `source`.
-    pub fn single_step_constraint(&self, source: &VariableName) -> HashSet<VariableName> {
-        self.constraint_map.get(source).cloned().unwrap_or_default()
+    /// Returns accesses constrained in a single step by `source`. Since
+    /// accesses may alias, this includes the accesses constrained by any
+    /// access which may alias `source`.
+    pub fn single_step_access_constraint(
+        &self,
+        source: &VariableAccess,
+    ) -> HashSet<VariableAccess> {
+        self.sources
+            .aliases(source)
+            .flat_map(|other| self.constraint_map[other].iter().cloned())
+            .collect()
     }
 
-    /// Returns variables constrained in one or more steps by `source`.
-    pub fn multi_step_constraint(&self, source: &VariableName) -> HashSet<VariableName> {
+    /// Returns accesses constrained in one or more steps by `source`.
+    pub fn multi_step_access_constraint(&self, source: &VariableAccess) -> HashSet<VariableAccess> {
         let mut result = HashSet::new();
-        let mut update = self.single_step_constraint(source);
+        let mut update = self.single_step_access_constraint(source);
         while !update.is_subset(&result) {
             result.extend(update.iter().cloned());
-            update = update.iter().flat_map(|source| self.single_step_constraint(source)).collect();
+            update = update
+                .iter()
+                .flat_map(|source| self.single_step_access_constraint(source))
+                .filter(|sink| !result.contains(sink))
+                .collect();
         }
         result
     }
 
+    /// Returns true if the source constrains an access which may alias any of
+    /// the sinks.
+    pub fn access_constrains_any(&self, source: &VariableAccess, sinks: &AccessSet) -> bool {
+        self.multi_step_access_constraint(source).iter().any(|var| sinks.may_contain(var))
+    }
+
+    /// Returns variables constrained in a single step by `source`.
+    pub fn single_step_constraint(&self, source: &VariableName) -> HashSet<VariableName> {
+        self.single_step_access_constraint(&VariableAccess::from(source))
+            .iter()
+            .map(|sink| sink.name().clone())
+            .collect()
+    }
+
+    /// Returns variables constrained in one or more steps by `source`.
+    pub fn multi_step_constraint(&self, source: &VariableName) -> HashSet<VariableName> {
+        self.multi_step_access_constraint(&VariableAccess::from(source))
+            .iter()
+            .map(|sink| sink.name().clone())
+            .collect()
+    }
+
     /// Returns true if the source constrains any of the sinks.
     pub fn constrains_any(&self, source: &VariableName, sinks: &HashSet<VariableName>) -> bool {
         self.multi_step_constraint(source).iter().any(|sink| sinks.contains(sink))
@@ -91,7 +132,7 @@ impl ConstraintAnalysis {
     /// Returns the set of variables occurring in a constraint together with at
     /// least one other variable.
     pub fn constrained_variables(&self) -> HashSet<VariableName> {
-        self.constraint_map.keys().cloned().collect::<HashSet<_>>()
+        self.sources.iter().map(|access| access.name().clone()).collect::<HashSet<_>>()
     }
 }
 
@@ -105,8 +146,8 @@ pub fn run_constraint_analysis(cfg: &Cfg) -> ConstraintAnalysis {
         for stmt in basic_block.iter() {
             trace!("visiting statement `{stmt:?}`");
             // Add definitions to the result.
-            for var in stmt.variables_written() {
-                result.add_definition(var);
+            for (access, var) in variable_access::definitions(stmt) {
+                result.add_definition(&access, var);
             }
             match stmt {
                 Declaration { meta, names, .. } => {
@@ -116,15 +157,12 @@ pub fn run_constraint_analysis(cfg: &Cfg) -> ConstraintAnalysis {
                     }
                 }
                 ConstraintEquality { .. } | Substitution { op: AssignConstraintSignal, .. } => {
-                    for source in stmt.variables_used() {
-                        for sink in stmt.variables_used() {
-                            if source.name() != sink.name() {
-                                trace!(
-                                    "adding constraint step with source `{:?}` and sink `{:?}`",
-                                    source.name(),
-                                    sink.name()
-                                );
-                                result.add_constraint_step(source.name(), sink.name());
+                    let variables_used = variable_access::variables_used(stmt);
+                    for source in &variables_used {
+                        for sink in &variables_used {
+                            // Distinct elements of the same array may constrain each other.
+                            if !source.may_alias(sink) {
+                                result.add_constraint_step(source, sink);
                             }
                         }
                     }
@@ -3,6 +3,8 @@ use program_structure::report_code::ReportCode;
 use program_structure::report::{Report, ReportCollection};
 use program_structure::file_definition::{FileID, FileLocation};
 
+use crate::config::AnalysisConfig;
+
 pub struct TooManyArgumentsWarning {
     definition_name: String,
     definition_type: DefinitionType,
@@ -48,10 +50,10 @@ impl CyclomaticComplexityWarning {
     }
 }
 
-const MAX_NOF_PARAMETERS: usize = 7;
-const MAX_CYCLOMATIC_COMPLEXITY: usize = 20;
-
-pub fn run_complexity_analysis(cfg: &Cfg) -> ReportCollection {
+/// Checks the cyclomatic complexity and the number of parameters of the given
+/// function or template. Both checks are disabled by default, and are only run
+/// if the corresponding threshold is set in the analysis configuration.
+pub fn run_complexity_analysis(config: &AnalysisConfig, cfg: &Cfg) -> ReportCollection {
     // Compute the cyclomatic complexity as `M = E - N + 2P` where `E` is the
     // number of edges, `N` is the number of nodes, and `P` is the number of
     // connected components (which is always 1 here).
@@ -65,8 +67,7 @@ pub fn run_complexity_analysis(cfg: &Cfg) -> ReportCollection {
 
     let mut reports = ReportCollection::new();
     // Generate a report if the cyclomatic complexity is high.
-    /*
-    if complexity > MAX_CYCLOMATIC_COMPLEXITY {
+    if matches!(config.max_cyclomatic_complexity, Some(max) if complexity > max) {
         reports.push(
             CyclomaticComplexityWarning {
                 definition_name: cfg.name().to_string(),
@@ -76,7 +77,7 @@ pub fn run_complexity_analysis(cfg: &Cfg) -> ReportCollection {
         );
     }
     // Generate a report if the number of arguments is high.
-    if cfg.parameters().len() > MAX_NOF_PARAMETERS {
+    if matches!(config.max_parameters, Some(max) if cfg.parameters().len() > max) {
         reports.push(
             TooManyArgumentsWarning {
                 definition_name: cfg.name().to_string(),
@@ -87,7 +88,6 @@ pub fn run_complexity_analysis(cfg: &Cfg) -> ReportCollection {
             .into_report(),
         );
     }
-    */
     reports
 }
 
@@ -96,6 +96,7 @@ mod tests {
     use parser::parse_definition;
     use program_structure::{report::ReportCollection, constants::Curve, cfg::IntoCfg};
 
+    use crate::config::AnalysisConfig;
     use crate::definition_complexity::run_complexity_analysis;
 
     #[test]
@@ -107,10 +108,31 @@ mod tests {
                a <== b;
             }
         "#;
-        validate_reports(src, 0);
+        validate_reports(src, &AnalysisConfig::default(), 0);
+
+        let config = AnalysisConfig { max_cyclomatic_complexity: Some(0), ..Default::default() };
+        validate_reports(src, &config, 1);
+    }
+
+    #[test]
+    fn test_too_many_arguments() {
+        let src = r#"
+            template Example (a, b, c) {
+               signal input in;
+               signal output out;
+               out <== in;
+            }
+        "#;
+        validate_reports(src, &AnalysisConfig::default(), 0);
+
+        let config = AnalysisConfig { max_parameters: Some(3), ..Default::default() };
+        validate_reports(src, &config, 0);
+
+        let config = AnalysisConfig { max_parameters: Some(2), ..Default::default() };
+        validate_reports(src, &config, 1);
     }
 
-    fn validate_reports(src: &str, expected_len: usize) {
+    fn validate_reports(src: &str, config: &AnalysisConfig, expected_len: usize) {
         // Build CFG.
         let mut reports = ReportCollection::new();
         let cfg = parse_definition(src)
@@ -122,7 +144,7 @@ mod tests {
         assert!(reports.is_empty());
 
         // Generate report collection.
-        let reports = run_complexity_analysis(&cfg);
+        let reports = run_complexity_analysis(config, &cfg);
 
         assert_eq!(reports.len(), expected_len);
     }
@@ -1,9 +1,11 @@
 use log::debug;
+use num_bigint::BigInt;
 
 use program_structure::cfg::Cfg;
 use program_structure::report_code::ReportCode;
 use program_structure::report::{Report, ReportCollection};
 use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::range_meta::{RangeMeta, ValueRange};
 use program_structure::ir::*;
 
 pub struct FieldElementArithmeticWarning {
@@ -29,90 +31,135 @@ impl FieldElementArithmeticWarning {
     }
 }
 
+pub struct FieldElementOverflowWarning {
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+    operands: (ValueRange, ValueRange),
+}
+
+impl FieldElementOverflowWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            "Field element arithmetic may overflow past `p`, which may produce unexpected results."
+                .to_string(),
+            ReportCode::FieldElementOverflow,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                "The result of this expression may overflow.".to_string(),
+            );
+        }
+        let (lhs, rhs) = self.operands;
+        report.add_note(format!("The operands are in the ranges {lhs} and {rhs}."));
+        report
+    }
+}
+
 /// Field element arithmetic in Circom may overflow, which could produce
 /// unexpected results. Worst case, it may allow a malicious prover to forge
 /// proofs.
+///
+/// If the ranges of the operands are known, arithmetic which cannot overflow
+/// is not reported, and arithmetic which may overflow is reported as a
+/// warning.
 pub fn find_field_element_arithmetic(cfg: &Cfg) -> ReportCollection {
     debug!("running field element arithmetic analysis pass");
     let mut reports = ReportCollection::new();
+    let prime = cfg.constants().prime();
     for basic_block in cfg.iter() {
         for stmt in basic_block.iter() {
-            visit_statement(stmt, &mut reports);
+            visit_statement(stmt, prime, &mut reports);
         }
     }
     debug!("{} new reports generated", reports.len());
     reports
 }
 
-fn visit_statement(stmt: &Statement, reports: &mut ReportCollection) {
+fn visit_statement(stmt: &Statement, prime: &BigInt, reports: &mut ReportCollection) {
     use Statement::*;
     match stmt {
         Declaration { dimensions, .. } => {
             for size in dimensions {
-                visit_expression(size, reports);
+                visit_expression(size, prime, reports);
             }
         }
         LogCall { args, .. } => {
             use LogArgument::*;
             for arg in args {
                 if let Expr(value) = arg {
-                    visit_expression(value, reports);
+                    visit_expression(value, prime, reports);
                 }
             }
         }
-        IfThenElse { cond, .. } => visit_expression(cond, reports),
-        Substitution { rhe, .. } => visit_expression(rhe, reports),
-        Return { value, .. } => visit_expression(value, reports),
-        Assert { arg, .. } => visit_expression(arg, reports),
+        IfThenElse { cond, .. } => visit_expression(cond, prime, reports),
+        Substitution { rhe, .. } => visit_expression(rhe, prime, reports),
+        Return { value, .. } => visit_expression(value, prime, reports),
+        Assert { arg, .. } => visit_expression(arg, prime, reports),
         ConstraintEquality { lhe, rhe, .. } => {
-            visit_expression(lhe, reports);
-            visit_expression(rhe, reports);
+            visit_expression(lhe, prime, reports);
+            visit_expression(rhe, prime, reports);
         }
     }
 }
 
-fn visit_expression(expr: &Expression, reports: &mut ReportCollection) {
+fn visit_expression(expr: &Expression, prime: &BigInt, reports: &mut ReportCollection) {
     use Expression::*;
     match expr {
-        InfixOp { meta, infix_op, .. } if may_overflow(infix_op) => {
-            reports.push(build_report(meta));
+        InfixOp { meta, lhe, infix_op, rhe } if may_overflow(infix_op) => {
+            match (lhe.range(), rhe.range()) {
+                (Some(lhr), Some(rhr)) if lhr.is_bounded() && rhr.is_bounded() => {
+                    let range = lhr.infix_op(infix_op, rhr);
+                    if range.fits_in_field(prime) {
+                        // The operation cannot overflow.
+                        visit_expression(lhe, prime, reports);
+                        visit_expression(rhe, prime, reports);
+                    } else if range.is_bounded() {
+                        reports.push(build_overflow_report(meta, lhr, rhr));
+                    } else {
+                        reports.push(build_report(meta));
+                    }
+                }
+                _ => reports.push(build_report(meta)),
+            }
         }
         InfixOp { lhe, rhe, .. } => {
-            visit_expression(lhe, reports);
-            visit_expression(rhe, reports);
+            visit_expression(lhe, prime, reports);
+            visit_expression(rhe, prime, reports);
         }
         PrefixOp { rhe, .. } => {
-            visit_expression(rhe, reports);
+            visit_expression(rhe, prime, reports);
         }
         SwitchOp { cond, if_true, if_false, .. } => {
-            visit_expression(cond, reports);
-            visit_expression(if_true, reports);
-            visit_expression(if_false, reports);
+            visit_expression(cond, prime, reports);
+            visit_expression(if_true, prime, reports);
+            visit_expression(if_false, prime, reports);
         }
         Call { args, .. } => {
             for arg in args {
-                visit_expression(arg, reports);
+                visit_expression(arg, prime, reports);
             }
         }
         InlineArray { values, .. } => {
             for value in values {
-                visit_expression(value, reports);
+                visit_expression(value, prime, reports);
             }
         }
         Access { access, .. } => {
             for index in access {
                 if let AccessType::ArrayAccess(index) = index {
-                    visit_expression(index, reports);
+                    visit_expression(index, prime, reports);
                 }
             }
         }
         Update { access, rhe, .. } => {
             for index in access {
                 if let AccessType::ArrayAccess(index) = index {
-                    visit_expression(index, reports);
+                    visit_expression(index, prime, reports);
                 }
             }
-            visit_expression(rhe, reports);
+            visit_expression(rhe, prime, reports);
         }
         Number(_, _) | Variable { .. } | Phi { .. } => (),
     }
@@ -137,6 +184,15 @@ fn build_report(meta: &Meta) -> Report {
         .into_report()
 }
 
+fn build_overflow_report(meta: &Meta, lhr: &ValueRange, rhr: &ValueRange) -> Report {
+    FieldElementOverflowWarning {
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+        operands: (lhr.clone(), rhr.clone()),
+    }
+    .into_report()
+}
+
 #[cfg(test)]
 mod tests {
     use parser::parse_definition;
@@ -154,9 +210,46 @@ mod tests {
             }
         "#;
         validate_reports(src, 2);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+
+                component n2b = Num2Bits(64);
+                n2b.in <== in;
+                out <== 2 * in + 1;
+
+                var bits[8];
+                for (var i = 0; i < 8; i++) {
+                    bits[i] = n2b.out[i] * 2**i;
+                }
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    #[test]
+    fn test_field_overflow() {
+        let src = r#"
+            function f(a) {
+                var x = 2**200;
+                var y = x * x;
+                return a + y;
+            }
+        "#;
+        validate_reports(src, 2);
+
+        let reports = find_reports(src);
+        assert_eq!(reports[0].id(), ReportCode::FieldElementOverflow.id());
+        assert_eq!(reports[1].id(), ReportCode::FieldElementArithmetic.id());
     }
 
     fn validate_reports(src: &str, expected_len: usize) {
+        assert_eq!(find_reports(src).len(), expected_len);
+    }
+
+    fn find_reports(src: &str) -> ReportCollection {
         // Build CFG.
         let mut reports = ReportCollection::new();
         let cfg = parse_definition(src)
@@ -168,8 +261,6 @@ mod tests {
         assert!(reports.is_empty());
 
         // Generate report collection.
-        let reports = find_field_element_arithmetic(&cfg);
-
-        assert_eq!(reports.len(), expected_len);
+        find_field_element_arithmetic(&cfg)
     }
 }
@@ -0,0 +1,159 @@
+//! Evaluation of function calls with constant arguments during value
+//! propagation.
+use log::trace;
+use once_cell::sync::OnceCell;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use program_structure::cfg::{Cfg, IntoCfg, Interpreter};
+use program_structure::constants::Curve;
+use program_structure::function_data::FunctionInfo;
+use program_structure::ir::value_meta::{FunctionEvaluator, ValueReduction};
+use program_structure::report::ReportCollection;
+
+type CallKey = (String, Vec<ValueReduction>);
+
+/// The functions available to the interpreter. Function CFGs are lifted on
+/// first use, and the result of each evaluated call is cached.
+///
+/// Note that the CFGs used by the interpreter are lifted without evaluating
+/// function calls. (Calls are evaluated by the interpreter itself.) This
+/// ensures that lifting a recursive function never requires the CFG of the
+/// function itself.
+#[derive(Default)]
+pub struct FunctionLibrary {
+    curve: Curve,
+    asts: FunctionInfo,
+    cfgs: HashMap<String, OnceCell<Option<Cfg>>>,
+    results: Mutex<HashMap<CallKey, Option<ValueReduction>>>,
+}
+
+impl FunctionLibrary {
+    pub fn new(curve: &Curve, asts: &FunctionInfo) -> FunctionLibrary {
+        FunctionLibrary {
+            curve: curve.clone(),
+            asts: asts.clone(),
+            cfgs: asts.keys().map(|name| (name.clone(), OnceCell::new())).collect(),
+            results: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn cfg(&self, name: &str) -> Option<&Cfg> {
+        let entry = self.cfgs.get(name)?;
+        let ast = self.asts.get(name)?;
+        entry
+            .get_or_init(|| {
+                let mut reports = ReportCollection::new();
+                ast.into_cfg(&self.curve, &mut reports).ok()?.into_ssa().ok()
+            })
+            .as_ref()
+    }
+}
+
+impl FunctionEvaluator for FunctionLibrary {
+    fn evaluate(&self, name: &str, args: &[ValueReduction]) -> Option<ValueReduction> {
+        if !self.asts.contains_key(name) {
+            // This is either a template or an unknown function.
+            return None;
+        }
+        let key = (name.to_string(), args.to_vec());
+        if let Some(result) = self.results.lock().ok()?.get(&key) {
+            return result.clone();
+        }
+        let functions = |name: &str| self.cfg(name);
+        let result = Interpreter::new(&functions).call(name, args);
+        if let Some(value) = &result {
+            trace!("evaluated `{name}` to {value}");
+        }
+        self.results.lock().ok()?.insert(key, result.clone());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use num_bigint::BigInt;
+    use parser::parse_definition;
+    use program_structure::file_definition::FileLibrary;
+    use program_structure::template_library::TemplateLibrary;
+
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let src = [
+            r#"
+            function nbits(a) {
+                var n = 1;
+                var r = 0;
+                while (n - 1 < a) {
+                    r++;
+                    n *= 2;
+                }
+                return r;
+            }
+            "#,
+            r#"
+            function fib(n) {
+                if (n < 2) {
+                    return n;
+                }
+                return fib(n - 1) + fib(n - 2);
+            }
+            "#,
+            r#"
+            function sum(n) {
+                var values[n];
+                for (var i = 0; i < n; i++) {
+                    values[i] = nbits(i);
+                }
+                var result = 0;
+                for (var i = 0; i < n; i++) {
+                    result += values[i];
+                }
+                return result;
+            }
+            "#,
+            r#"
+            function table(n) {
+                var t[2][2] = [[1, 2], [3, 4]];
+                t[1][1] = n;
+                return t;
+            }
+            "#,
+            r#"
+            function forever(n) {
+                while (n > 0) {
+                    n += 1;
+                }
+                return n;
+            }
+            "#,
+        ];
+        let mut file_library = FileLibrary::default();
+        let mut library_contents = HashMap::new();
+        for (file_index, file_source) in src.iter().enumerate() {
+            let file_name = format!("file-{file_index}.circom");
+            let file_id = file_library.add_file(file_name, file_source.to_string(), true);
+            library_contents.insert(file_id, vec![parse_definition(file_source).unwrap()]);
+        }
+        let library = TemplateLibrary::new(library_contents, file_library);
+        let functions = FunctionLibrary::new(&Curve::default(), &library.functions);
+
+        let value = |value: usize| ValueReduction::FieldElement { value: BigInt::from(value) };
+        assert_eq!(functions.evaluate("nbits", &[value(255)]), Some(value(8)));
+        assert_eq!(functions.evaluate("nbits", &[value(256)]), Some(value(9)));
+        assert_eq!(functions.evaluate("fib", &[value(10)]), Some(value(55)));
+        assert_eq!(functions.evaluate("sum", &[value(4)]), Some(value(5)));
+        assert_eq!(
+            functions.evaluate("table", &[value(5)]).unwrap().to_string(),
+            "[[1, 2], [3, 5]]"
+        );
+        // Evaluation fails if the step budget is exceeded.
+        assert_eq!(functions.evaluate("forever", &[value(1)]), None);
+        // Evaluation fails for unknown functions.
+        assert_eq!(functions.evaluate("unknown", &[value(1)]), None);
+    }
+}
@@ -0,0 +1,318 @@
+//! Support for incremental analysis. Results from a previous analysis run are
+//! reused for templates and functions that are unchanged, and which do not
+//! call any changed templates or functions.
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use program_structure::ast::{Access, Expression, LogArgument, Statement};
+use program_structure::file_definition::{FileID, FileLibrary, FileLocation};
+use program_structure::report::{Report, ReportCollection};
+
+/// A summary of a template or function definition, used to decide if results
+/// from a previous analysis run can be reused.
+pub(crate) struct DefinitionSummary {
+    pub file_id: FileID,
+    pub file_name: String,
+    pub location: FileLocation,
+    pub source: String,
+    pub callees: HashSet<String>,
+}
+
+impl DefinitionSummary {
+    /// Creates a summary of the definition with the given parameters and
+    /// body. Returns `None` if the file ID or location is unknown.
+    pub fn new(
+        file_id: FileID,
+        param_location: FileLocation,
+        body: &Statement,
+        file_library: &FileLibrary,
+    ) -> Option<DefinitionSummary> {
+        let files = file_library.to_storage();
+        let file = files.get(file_id).ok()?;
+        let location = param_location.start..body.get_meta().file_location().end;
+        let source = file.source().get(location.clone())?.to_string();
+        let mut callees = HashSet::new();
+        visit_statement(body, &mut callees);
+        Some(DefinitionSummary {
+            file_id,
+            file_name: file.name().to_string(),
+            location,
+            source,
+            callees,
+        })
+    }
+
+    fn is_unchanged(&self, previous: &DefinitionSummary) -> bool {
+        self.file_name == previous.file_name && self.source == previous.source
+    }
+
+    fn is_moved(&self, previous: &DefinitionSummary) -> bool {
+        self.file_id != previous.file_id || self.location != previous.location
+    }
+
+    /// Returns true if the location is contained in this definition.
+    pub fn contains(&self, file_id: FileID, location: &FileLocation) -> bool {
+        file_id == self.file_id
+            && self.location.start <= location.start
+            && location.end <= self.location.end
+    }
+}
+
+/// Returns the results from a previous run that are still valid, keyed by
+/// template or function name. The results for a definition are reused if the
+/// source of the definition is unchanged, and no (transitive) callee has been
+/// changed, moved, added, or removed. Reused reports are moved to the current
+/// location of the definition.
+pub(crate) fn reusable_results(
+    previous_definitions: &HashMap<String, DefinitionSummary>,
+    current_definitions: &HashMap<String, DefinitionSummary>,
+    previous_results: HashMap<String, ReportCollection>,
+) -> HashMap<String, ReportCollection> {
+    let is_unchanged =
+        |name: &str| match (current_definitions.get(name), previous_definitions.get(name)) {
+            (Some(current), Some(previous)) => current.is_unchanged(previous),
+            _ => false,
+        };
+    // Callers of a definition must be re-analyzed if the definition is
+    // changed, moved, added, or removed.
+    let invalidates_callers = previous_definitions
+        .keys()
+        .chain(current_definitions.keys())
+        .filter(|name| {
+            !is_unchanged(name)
+                || current_definitions[name.as_str()].is_moved(&previous_definitions[name.as_str()])
+        })
+        .map(String::as_str)
+        .collect::<HashSet<_>>();
+    let mut changed = current_definitions
+        .keys()
+        .map(String::as_str)
+        .filter(|name| !is_unchanged(name))
+        .collect::<HashSet<_>>();
+    loop {
+        let callers = current_definitions
+            .iter()
+            .filter(|(name, definition)| {
+                !changed.contains(name.as_str())
+                    && definition.callees.iter().any(|callee| {
+                        changed.contains(callee.as_str())
+                            || invalidates_callers.contains(callee.as_str())
+                    })
+            })
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        if callers.is_empty() {
+            break;
+        }
+        changed.extend(callers);
+    }
+
+    previous_results
+        .into_iter()
+        .filter(|(name, _)| !changed.contains(name.as_str()))
+        .filter_map(|(name, mut reports)| {
+            let previous = previous_definitions.get(&name)?;
+            let current = current_definitions.get(&name)?;
+            relocate_reports(&mut reports, previous, current).then_some((name, reports))
+        })
+        .collect()
+}
+
+/// Returns true if each of the given definitions exists in both runs, and is
+/// unchanged.
+pub(crate) fn are_unchanged<'a>(
+    names: impl IntoIterator<Item = &'a String>,
+    previous_definitions: &HashMap<String, DefinitionSummary>,
+    current_definitions: &HashMap<String, DefinitionSummary>,
+) -> bool {
+    names.into_iter().all(|name| {
+        match (current_definitions.get(name), previous_definitions.get(name)) {
+            (Some(current), Some(previous)) => current.is_unchanged(previous),
+            _ => false,
+        }
+    })
+}
+
+/// Returns the names of the given definition and all its transitive callees,
+/// in sorted order.
+pub(crate) fn transitive_callees<'a>(
+    name: &'a str,
+    definitions: &'a HashMap<String, DefinitionSummary>,
+) -> BTreeSet<&'a str> {
+    let mut result = BTreeSet::new();
+    let mut worklist = vec![name];
+    while let Some(name) = worklist.pop() {
+        if !result.insert(name) {
+            continue;
+        }
+        if let Some(definition) = definitions.get(name) {
+            worklist.extend(definition.callees.iter().map(String::as_str));
+        }
+    }
+    result
+}
+
+/// Moves the given reports from the previous location of the definition to
+/// the current location. Returns false if a report label or suggestion refers
+/// to a location outside the definition, in which case the reports cannot be
+/// reused.
+fn relocate_reports(
+    reports: &mut [Report],
+    previous: &DefinitionSummary,
+    current: &DefinitionSummary,
+) -> bool {
+    let is_contained = |report: &Report| {
+        report
+            .primary()
+            .iter()
+            .chain(report.secondary().iter())
+            .all(|label| previous.contains(label.file_id, &label.range))
+            && report
+                .suggestions()
+                .iter()
+                .all(|suggestion| previous.contains(suggestion.file_id(), suggestion.location()))
+    };
+    if !reports.iter().all(is_contained) {
+        return false;
+    }
+    for report in reports.iter_mut() {
+        report.relocate(|_, location| {
+            let start = location.start - previous.location.start + current.location.start;
+            let end = location.end - previous.location.start + current.location.start;
+            (current.file_id, start..end)
+        });
+    }
+    true
+}
+
+fn visit_statement(stmt: &Statement, callees: &mut HashSet<String>) {
+    use Statement::*;
+    match stmt {
+        IfThenElse { cond, if_case, else_case, .. } => {
+            visit_expression(cond, callees);
+            visit_statement(if_case, callees);
+            if let Some(else_case) = else_case {
+                visit_statement(else_case, callees);
+            }
+        }
+        While { cond, stmt, .. } => {
+            visit_expression(cond, callees);
+            visit_statement(stmt, callees);
+        }
+        Return { value, .. } => visit_expression(value, callees),
+        InitializationBlock { initializations, .. } => {
+            for stmt in initializations {
+                visit_statement(stmt, callees);
+            }
+        }
+        Declaration { dimensions, .. } => {
+            for dimension in dimensions {
+                visit_expression(dimension, callees);
+            }
+        }
+        Substitution { access, rhe, .. } => {
+            visit_access(access, callees);
+            visit_expression(rhe, callees);
+        }
+        MultiSubstitution { lhe, rhe, .. } | ConstraintEquality { lhe, rhe, .. } => {
+            visit_expression(lhe, callees);
+            visit_expression(rhe, callees);
+        }
+        LogCall { args, .. } => {
+            for arg in args {
+                if let LogArgument::LogExp(expr) = arg {
+                    visit_expression(expr, callees);
+                }
+            }
+        }
+        Block { stmts, .. } => {
+            for stmt in stmts {
+                visit_statement(stmt, callees);
+            }
+        }
+        Assert { arg, .. } => visit_expression(arg, callees),
+    }
+}
+
+fn visit_expression(expr: &Expression, callees: &mut HashSet<String>) {
+    use Expression::*;
+    match expr {
+        InfixOp { lhe, rhe, .. } => {
+            visit_expression(lhe, callees);
+            visit_expression(rhe, callees);
+        }
+        PrefixOp { rhe, .. } | ParallelOp { rhe, .. } => visit_expression(rhe, callees),
+        InlineSwitchOp { cond, if_true, if_false, .. } => {
+            visit_expression(cond, callees);
+            visit_expression(if_true, callees);
+            visit_expression(if_false, callees);
+        }
+        Variable { access, .. } => visit_access(access, callees),
+        Number(..) => {}
+        Call { id, args, .. } => {
+            callees.insert(id.clone());
+            for arg in args {
+                visit_expression(arg, callees);
+            }
+        }
+        AnonymousComponent { id, params, signals, .. } => {
+            callees.insert(id.clone());
+            for expr in params.iter().chain(signals.iter()) {
+                visit_expression(expr, callees);
+            }
+        }
+        ArrayInLine { values, .. } | Tuple { values, .. } => {
+            for value in values {
+                visit_expression(value, callees);
+            }
+        }
+    }
+}
+
+fn visit_access(access: &[Access], callees: &mut HashSet<String>) {
+    for access in access {
+        if let Access::ArrayAccess(index) = access {
+            visit_expression(index, callees);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use program_structure::report_code::ReportCode;
+
+    use super::*;
+
+    fn summary(location: FileLocation) -> DefinitionSummary {
+        DefinitionSummary {
+            file_id: 0,
+            file_name: "test.circom".to_string(),
+            location,
+            source: String::new(),
+            callees: HashSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_relocate_reports() {
+        let previous = summary(10..20);
+        let current = summary(30..40);
+        let report = || {
+            let mut report =
+                Report::warning("A warning.".to_string(), ReportCode::SignalAssignmentStatement);
+            report.add_primary(12..14, 0, "A label.".to_string());
+            report
+        };
+
+        let mut reports = vec![report()];
+        reports[0].add_suggestion(12..14, 0, "<==".to_string(), "A fix.".to_string());
+        assert!(relocate_reports(&mut reports, &previous, &current));
+        assert_eq!(reports[0].primary()[0].range, 32..34);
+        assert_eq!(reports[0].suggestions()[0].location(), &(32..34));
+
+        // Suggestions outside the definition prevent the reports from being
+        // reused.
+        let mut reports = vec![report()];
+        reports[0].add_suggestion(0..2, 0, "<==".to_string(), "A fix.".to_string());
+        assert!(!relocate_reports(&mut reports, &previous, &current));
+    }
+}
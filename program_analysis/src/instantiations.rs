@@ -0,0 +1,260 @@
+//! Support for analyzing each instantiation of a template reachable from the
+//! main component separately, with the template parameters bound to the
+//! corresponding constant arguments.
+use std::collections::HashMap;
+use std::fmt;
+
+use program_structure::cfg::Cfg;
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::{AssignOp, Expression, Statement};
+use program_structure::report::{Report, ReportCollection};
+
+/// The maximum number of instantiations enumerated from the main component.
+/// Templates that are not enumerated are analyzed symbolically.
+pub const MAX_INSTANTIATIONS: usize = 256;
+
+/// The maximum number of instantiation chains added as notes to a report.
+const MAX_CHAIN_NOTES: usize = 3;
+
+/// A template instantiated with constant arguments.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Instantiation {
+    pub name: String,
+    pub arguments: Vec<ValueReduction>,
+}
+
+impl Instantiation {
+    pub fn new(name: &str, arguments: Vec<ValueReduction>) -> Instantiation {
+        Instantiation { name: name.to_string(), arguments }
+    }
+}
+
+impl fmt::Display for Instantiation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.arguments.is_empty() {
+            return write!(f, "{}", self.name);
+        }
+        let arguments =
+            self.arguments.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+        write!(f, "{}({arguments})", self.name)
+    }
+}
+
+/// The chain of instantiations leading from the main component to a template
+/// instantiation. (The last element is the instantiation itself.)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstantiationChain(Vec<Instantiation>);
+
+impl InstantiationChain {
+    pub fn new(main: Instantiation) -> InstantiationChain {
+        InstantiationChain(vec![main])
+    }
+
+    /// Returns the chain extended by the given instantiation.
+    pub fn push(&self, instantiation: Instantiation) -> InstantiationChain {
+        let mut chain = self.0.clone();
+        chain.push(instantiation);
+        InstantiationChain(chain)
+    }
+
+    /// Returns the number of instantiations in the chain.
+    pub fn depth(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the instantiation at the end of the chain.
+    pub fn last(&self) -> &Instantiation {
+        // Chains are never empty.
+        &self.0[self.0.len() - 1]
+    }
+}
+
+impl fmt::Display for InstantiationChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chain = self.0.iter().map(ToString::to_string).collect::<Vec<_>>().join(" -> ");
+        write!(f, "{chain}")
+    }
+}
+
+/// The instantiation of the main component, together with the maximum depth
+/// of enumerated instantiation chains. Together with the templates and
+/// functions reachable from the main component, this determines the
+/// instantiations of every template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstantiationContext {
+    pub main: Instantiation,
+    pub level: usize,
+}
+
+impl fmt::Display for InstantiationContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (level {})", self.main, self.level)
+    }
+}
+
+/// The instantiations of a single template reachable from the main component.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TemplateInstances {
+    /// The chain leading to each distinct instantiation, in the order in which
+    /// they were found.
+    pub chains: Vec<InstantiationChain>,
+    /// True if the template is also instantiated with arguments which are not
+    /// known to be constant. If this is the case, the template is also
+    /// analyzed symbolically.
+    pub symbolic: bool,
+}
+
+/// A component instantiation `c = T(args...)` in a template.
+pub enum ComponentInstantiation {
+    /// The arguments are all constant.
+    Constant(Instantiation),
+    /// Some argument is not known to be constant.
+    Symbolic(String),
+}
+
+/// Returns all component instantiations in the given CFG.
+pub fn component_instantiations(cfg: &Cfg) -> Vec<ComponentInstantiation> {
+    use Expression::*;
+    let mut result = Vec::new();
+    for stmt in cfg.iter().flat_map(|basic_block| basic_block.iter()) {
+        let Statement::Substitution { meta, op: AssignOp::AssignLocalOrComponent, rhe, .. } = stmt
+        else {
+            continue;
+        };
+        if !meta.type_knowledge().is_component() {
+            continue;
+        }
+        // If this is an update node, we extract the right-hand side.
+        let rhe = if let Update { rhe, .. } = rhe { rhe } else { rhe };
+        if let Call { name, args, .. } = rhe {
+            let arguments = args.iter().map(|arg| arg.value().cloned()).collect::<Option<_>>();
+            result.push(match arguments {
+                Some(arguments) => {
+                    ComponentInstantiation::Constant(Instantiation::new(name, arguments))
+                }
+                None => ComponentInstantiation::Symbolic(name.clone()),
+            });
+        }
+    }
+    result
+}
+
+/// Merges the reports generated for each instantiation of a template. (The
+/// chain is `None` for reports generated by the symbolic analysis.) Identical
+/// reports are only included once, and reports which are not generated for
+/// all analyzed instantiations are annotated with the corresponding
+/// instantiation chains.
+pub fn merge_reports(
+    analyses: Vec<(Option<&InstantiationChain>, ReportCollection)>,
+) -> ReportCollection {
+    let total = analyses.len();
+    let mut merged: Vec<(Report, usize, Vec<&InstantiationChain>)> = Vec::new();
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    for (chain, reports) in analyses {
+        for report in reports {
+            let index = *indices.entry(report_key(&report)).or_insert_with(|| {
+                merged.push((report, 0, Vec::new()));
+                merged.len() - 1
+            });
+            let (_, count, chains) = &mut merged[index];
+            *count += 1;
+            chains.extend(chain);
+        }
+    }
+    merged
+        .into_iter()
+        .map(|(mut report, count, chains)| {
+            if count < total && !chains.is_empty() {
+                report.add_note(chain_note(&chains));
+            }
+            report
+        })
+        .collect()
+}
+
+/// Returns a key identifying identical reports.
+fn report_key(report: &Report) -> String {
+    let primary = report
+        .primary()
+        .iter()
+        .map(|label| (label.file_id, label.range.clone(), label.message.clone()))
+        .collect::<Vec<_>>();
+    format!("{}{:?}{primary:?}{:?}", report.id(), report.message(), report.notes())
+}
+
+fn chain_note(chains: &[&InstantiationChain]) -> String {
+    let mut instantiations =
+        chains.iter().take(MAX_CHAIN_NOTES).map(|chain| format!("`{chain}`")).collect::<Vec<_>>();
+    let remaining = chains.len().saturating_sub(MAX_CHAIN_NOTES);
+    if remaining == 1 {
+        instantiations.push("1 other instantiation".to_string());
+    } else if remaining > 1 {
+        instantiations.push(format!("{remaining} other instantiations"));
+    }
+    let instantiations = match instantiations.len() {
+        1 => instantiations.remove(0),
+        2 => instantiations.join(" and "),
+        _ => {
+            let last = instantiations.pop().unwrap_or_default();
+            format!("{}, and {last}", instantiations.join(", "))
+        }
+    };
+    format!("Found when the template is instantiated as {instantiations}.")
+}
+
+#[cfg(test)]
+mod tests {
+    use program_structure::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_instantiation_chain() {
+        let main = Instantiation::new("Main", Vec::new());
+        let foo = Instantiation::new("Foo", vec![ValueReduction::FieldElement { value: 3.into() }]);
+        let chain = InstantiationChain::new(main).push(foo.clone());
+        assert_eq!(chain.to_string(), "Main -> Foo(3)");
+        assert_eq!(chain.last(), &foo);
+        assert_eq!(chain.depth(), 2);
+    }
+
+    #[test]
+    fn test_merge_reports() {
+        let report = |message: &str| {
+            Report::warning(message.to_string(), ReportCode::NonStrictBinaryConversion)
+        };
+        let chains = (0..5)
+            .map(|i| {
+                let arguments = vec![ValueReduction::FieldElement { value: i.into() }];
+                InstantiationChain::new(Instantiation::new("Foo", arguments))
+            })
+            .collect::<Vec<_>>();
+        let analyses = chains
+            .iter()
+            .enumerate()
+            .map(|(i, chain)| {
+                let mut reports = vec![report("all")];
+                if i == 0 {
+                    reports.push(report("first"));
+                }
+                if i > 0 {
+                    reports.push(report("rest"));
+                }
+                (Some(chain), reports)
+            })
+            .collect::<Vec<_>>();
+        let reports = merge_reports(analyses);
+        assert_eq!(reports.len(), 3);
+        assert!(reports[0].notes().is_empty());
+        assert_eq!(
+            reports[1].notes(),
+            &vec!["Found when the template is instantiated as `Foo(0)`.".to_string()]
+        );
+        assert_eq!(
+            reports[2].notes(),
+            &vec!["Found when the template is instantiated as `Foo(1)`, `Foo(2)`, `Foo(3)`, \
+                and 1 other instantiation."
+                .to_string()]
+        );
+    }
+}
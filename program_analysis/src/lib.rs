@@ -1,16 +1,19 @@
-use analysis_context::AnalysisContext;
-
-use program_structure::cfg::Cfg;
-use program_structure::report::ReportCollection;
-
 extern crate num_bigint_dig as num_bigint;
 
 pub mod constraint_analysis;
 pub mod taint_analysis;
+pub mod variable_access;
 pub mod analysis_context;
 pub mod analysis_runner;
 pub mod gather_information;
 pub mod config;
+pub mod pass_registry;
+pub mod query;
+pub mod result_cache;
+
+mod function_library;
+mod incremental;
+mod instantiations;
 
 // Intra-process analysis passes.
 mod bitwise_complement;
@@ -28,27 +31,3 @@ mod signal_assignments;
 
 // Inter-process analysis passes.
 mod unused_output_signal;
-
-/// An analysis pass is a function which takes an analysis context and a CFG and
-/// returns a set of reports.
-type AnalysisPass = dyn Fn(&mut dyn AnalysisContext, &Cfg) -> ReportCollection;
-
-pub fn get_analysis_passes() -> Vec<Box<AnalysisPass>> {
-    vec![
-        // Intra-process analysis passes.
-        Box::new(|_, cfg| bitwise_complement::find_bitwise_complement(cfg)),
-        Box::new(|_, cfg| signal_assignments::find_signal_assignments(cfg)),
-        Box::new(|_, cfg| definition_complexity::run_complexity_analysis(cfg)),
-        Box::new(|_, cfg| side_effect_analysis::run_side_effect_analysis(cfg)),
-        Box::new(|_, cfg| field_arithmetic::find_field_element_arithmetic(cfg)),
-        Box::new(|_, cfg| field_comparisons::find_field_element_comparisons(cfg)),
-        Box::new(|_, cfg| unconstrained_division::find_unconstrained_division(cfg)),
-        Box::new(|_, cfg| bn254_specific_circuit::find_bn254_specific_circuits(cfg)),
-        Box::new(|_, cfg| unconstrained_less_than::find_unconstrained_less_than(cfg)),
-        Box::new(|_, cfg| constant_conditional::find_constant_conditional_statement(cfg)),
-        Box::new(|_, cfg| under_constrained_signals::find_under_constrained_signals(cfg)),
-        Box::new(|_, cfg| nonstrict_binary_conversion::find_nonstrict_binary_conversion(cfg)),
-        // Inter-process analysis passes.
-        Box::new(unused_output_signal::find_unused_output_signals),
-    ]
-}
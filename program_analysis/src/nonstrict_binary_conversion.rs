@@ -6,7 +6,7 @@ use program_structure::constants::Curve;
 use program_structure::report_code::ReportCode;
 use program_structure::report::{Report, ReportCollection};
 use program_structure::file_definition::{FileID, FileLocation};
-use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::range_meta::RangeMeta;
 use program_structure::ir::*;
 
 pub enum NonStrictBinaryConversionWarning {
@@ -58,7 +58,7 @@ impl NonStrictBinaryConversionWarning {
 }
 
 /// If the size in bits of the input `x` to the Circomlib circuit `NumBits` is
-/// >= than the size of the prime there will be two valid bit-representations of
+/// greater than or equal to the size of the prime there will be two valid bit-representations of
 /// the input: One representation of `x` and one of `p + x`. This is typically
 /// not expected by developers and may lead to issues.
 pub fn find_nonstrict_binary_conversion(cfg: &Cfg) -> ReportCollection {
@@ -87,7 +87,6 @@ fn visit_statement(stmt: &Statement, prime_size: &BigInt, reports: &mut ReportCo
     use AssignOp::*;
     use Expression::*;
     use Statement::*;
-    use ValueReduction::*;
     if let Substitution { meta: var_meta, op: AssignLocalOrComponent, rhe, .. } = stmt {
         // If the variable `var` is declared as a local variable or signal, we exit early.
         if var_meta.type_knowledge().is_local() || var_meta.type_knowledge().is_signal() {
@@ -103,10 +102,8 @@ fn visit_statement(stmt: &Statement, prime_size: &BigInt, reports: &mut ReportCo
                 let arg = &args[0];
                 // If the input size is known to be less than the prime size, this
                 // initialization is safe.
-                if let Some(FieldElement { value }) = arg.value() {
-                    if value < prime_size {
-                        return;
-                    }
+                if is_less_than(arg, prime_size) {
+                    return;
                 }
                 reports.push(build_num2bits(component_meta));
             }
@@ -115,10 +112,8 @@ fn visit_statement(stmt: &Statement, prime_size: &BigInt, reports: &mut ReportCo
                 let arg = &args[0];
                 // If the input size is known to be less than the prime size, this
                 // initialization is safe.
-                if let Some(FieldElement { value }) = arg.value() {
-                    if value < prime_size {
-                        return;
-                    }
+                if is_less_than(arg, prime_size) {
+                    return;
                 }
                 reports.push(build_bits2num(component_meta));
             }
@@ -126,6 +121,12 @@ fn visit_statement(stmt: &Statement, prime_size: &BigInt, reports: &mut ReportCo
     }
 }
 
+/// Returns true if the value of the expression is known to be less than the
+/// given bound.
+fn is_less_than(expr: &Expression, bound: &BigInt) -> bool {
+    matches!(expr.range().and_then(|range| range.upper()), Some(upper) if upper < bound)
+}
+
 fn build_num2bits(meta: &Meta) -> Report {
     NonStrictBinaryConversionWarning::Num2Bits {
         file_id: meta.file_id(),
@@ -179,6 +180,21 @@ mod tests {
             }
         "#;
         validate_reports(src, 0);
+
+        let src = r#"
+            template F(n) {
+                signal input in[n];
+                signal output out[n];
+
+                component b2n[8];
+                for (var i = 0; i < 8; i++) {
+                    b2n[i] = Bits2Num(i + 1);
+                    b2n[i].in <== in;
+                    out[i] <== b2n[i].out;
+                }
+            }
+        "#;
+        validate_reports(src, 0);
     }
 
     fn validate_reports(src: &str, expected_len: usize) {
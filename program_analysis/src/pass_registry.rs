@@ -0,0 +1,431 @@
+//! The analysis pass API, together with a registry of all built-in analysis
+//! passes. Each pass is described by a unique ID, a name and description, the
+//! report codes emitted by the pass, the default severity of the emitted
+//! reports, and whether the pass is intra- or inter-process.
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+
+use program_structure::cfg::Cfg;
+use program_structure::report::{MessageCategory, ReportCollection};
+use program_structure::report_code::ReportCode;
+
+use crate::analysis_context::AnalysisContext;
+use crate::{
+    bitwise_complement, bn254_specific_circuit, constant_conditional, definition_complexity,
+    field_arithmetic, field_comparisons, nonstrict_binary_conversion, side_effect_analysis,
+    signal_assignments, unconstrained_division, unconstrained_less_than, under_constrained_signals,
+    unused_output_signal,
+};
+
+/// The signature of the functions implementing the built-in analysis passes.
+type PassFn = fn(&dyn AnalysisContext, &Cfg) -> ReportCollection;
+
+/// Intra-process passes only consider the analyzed function or template, while
+/// inter-process passes may also inspect the CFGs of other templates and
+/// functions through the analysis context.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PassScope {
+    IntraProcess,
+    InterProcess,
+}
+
+impl fmt::Display for PassScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PassScope::IntraProcess => write!(f, "intra-process"),
+            PassScope::InterProcess => write!(f, "inter-process"),
+        }
+    }
+}
+
+/// An analysis pass takes an analysis context and the CFG of a template or
+/// function (in SSA form), and returns a set of reports.
+///
+/// This trait is implemented by all built-in passes, and may be implemented by
+/// downstream crates to add custom passes to the analysis (see
+/// `AnalysisRunner::with_passes`). Passes are run concurrently on different
+/// templates and functions, and must therefore be `Send + Sync`.
+pub trait AnalysisPass: Send + Sync {
+    /// A unique ID identifying the pass (e.g. `signal-assignment`).
+    fn id(&self) -> &str;
+
+    /// A short human readable name.
+    fn name(&self) -> &str;
+
+    /// A one-sentence description of what the pass looks for.
+    fn description(&self) -> &str;
+
+    /// The report codes that may be emitted by the pass. Custom passes should
+    /// use `ReportCode::Custom` to define their own codes.
+    fn report_codes(&self) -> &[ReportCode];
+
+    /// The severity of reports emitted by the pass (unless overridden). For
+    /// passes emitting reports with different severities, this is the highest
+    /// severity emitted by the pass.
+    fn default_severity(&self) -> MessageCategory {
+        MessageCategory::Warning
+    }
+
+    /// Returns `PassScope::InterProcess` if the pass accesses the CFGs of
+    /// other templates or functions through the analysis context.
+    fn scope(&self) -> PassScope {
+        PassScope::IntraProcess
+    }
+
+    /// A string identifying everything that affects the reports emitted by
+    /// the pass. This is used to invalidate cached results when a custom pass
+    /// changes, so configurable passes should override this to include all
+    /// options affecting the emitted reports. The default includes the ID,
+    /// description, report codes, and default severity of the pass.
+    fn fingerprint(&self) -> String {
+        let report_codes = self
+            .report_codes()
+            .iter()
+            .map(|code| format!("{} ({})", code.id(), code.name()))
+            .collect::<Vec<_>>();
+        format!(
+            "{}\0{}\0{}\0{}",
+            self.id(),
+            self.description(),
+            report_codes.join(", "),
+            self.default_severity()
+        )
+    }
+
+    /// Runs the analysis pass on the given CFG.
+    fn run(&self, context: &dyn AnalysisContext, cfg: &Cfg) -> ReportCollection;
+}
+
+/// Describes a built-in analysis pass.
+pub struct PassDescriptor {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    report_codes: &'static [ReportCode],
+    default_severity: MessageCategory,
+    scope: PassScope,
+    run: PassFn,
+}
+
+impl AnalysisPass for PassDescriptor {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn report_codes(&self) -> &[ReportCode] {
+        self.report_codes
+    }
+
+    fn default_severity(&self) -> MessageCategory {
+        self.default_severity
+    }
+
+    fn scope(&self) -> PassScope {
+        self.scope
+    }
+
+    fn run(&self, context: &dyn AnalysisContext, cfg: &Cfg) -> ReportCollection {
+        (self.run)(context, cfg)
+    }
+}
+
+static ANALYSIS_PASSES: [PassDescriptor; 13] = [
+    // Intra-process analysis passes.
+    PassDescriptor {
+        id: "bitwise-complement",
+        name: "Bitwise complement",
+        description: "Finds uses of the bitwise complement `~x`, which is reduced modulo `p`.",
+        report_codes: &[ReportCode::FieldElementArithmetic],
+        default_severity: MessageCategory::Info,
+        scope: PassScope::IntraProcess,
+        run: |_, cfg| bitwise_complement::find_bitwise_complement(cfg),
+    },
+    PassDescriptor {
+        id: "signal-assignment",
+        name: "Signal assignment",
+        description: "Finds signal assignments using `<--`, which do not constrain the signal.",
+        report_codes: &[
+            ReportCode::SignalAssignmentStatement,
+            ReportCode::UnnecessarySignalAssignment,
+        ],
+        default_severity: MessageCategory::Warning,
+        scope: PassScope::IntraProcess,
+        run: |context, cfg| signal_assignments::find_signal_assignments(context, cfg),
+    },
+    PassDescriptor {
+        id: "overly-complex-function-or-template",
+        name: "Overly complex function or template",
+        description: "Checks the cyclomatic complexity and number of parameters against the \
+                      configured thresholds.",
+        report_codes: &[ReportCode::CyclomaticComplexity, ReportCode::TooManyArguments],
+        default_severity: MessageCategory::Warning,
+        scope: PassScope::IntraProcess,
+        run: |context, cfg| definition_complexity::run_complexity_analysis(context.config(), cfg),
+    },
+    PassDescriptor {
+        id: "side-effect-free-assignment",
+        name: "Side-effect free assignment",
+        description: "Finds variables, parameters, and signals that do not affect witness or \
+                      constraint generation.",
+        report_codes: &[
+            ReportCode::VariableWithoutSideEffect,
+            ReportCode::UnusedVariableValue,
+            ReportCode::UnusedParameterValue,
+            ReportCode::UnconstrainedSignal,
+        ],
+        default_severity: MessageCategory::Warning,
+        scope: PassScope::IntraProcess,
+        run: |_, cfg| side_effect_analysis::run_side_effect_analysis(cfg),
+    },
+    PassDescriptor {
+        id: "field-element-arithmetic",
+        name: "Field element arithmetic",
+        description: "Finds field element arithmetic which may overflow.",
+        report_codes: &[ReportCode::FieldElementArithmetic, ReportCode::FieldElementOverflow],
+        default_severity: MessageCategory::Warning,
+        scope: PassScope::IntraProcess,
+        run: |_, cfg| field_arithmetic::find_field_element_arithmetic(cfg),
+    },
+    PassDescriptor {
+        id: "field-element-comparison",
+        name: "Field element comparison",
+        description: "Finds comparisons of field elements, which are normalized to `(-p/2, p/2]` \
+                      before they are compared.",
+        report_codes: &[ReportCode::FieldElementComparison],
+        default_severity: MessageCategory::Info,
+        scope: PassScope::IntraProcess,
+        run: |_, cfg| field_comparisons::find_field_element_comparisons(cfg),
+    },
+    PassDescriptor {
+        id: "unconstrained-division",
+        name: "Unconstrained division",
+        description: "Finds signal assignments `c <-- a / b` where `b` is not constrained to be \
+                      non-zero.",
+        report_codes: &[ReportCode::UnconstrainedDivision],
+        default_severity: MessageCategory::Warning,
+        scope: PassScope::IntraProcess,
+        run: |_, cfg| unconstrained_division::find_unconstrained_division(cfg),
+    },
+    PassDescriptor {
+        id: "bn254-specific-circuit",
+        name: "BN254 specific circuit",
+        description: "Finds Circomlib templates which are hard-coded for BN254 when another \
+                      curve is used.",
+        report_codes: &[ReportCode::Bn254SpecificCircuit],
+        default_severity: MessageCategory::Warning,
+        scope: PassScope::IntraProcess,
+        run: |_, cfg| bn254_specific_circuit::find_bn254_specific_circuits(cfg),
+    },
+    PassDescriptor {
+        id: "unconstrained-less-than",
+        name: "Unconstrained less-than",
+        description: "Finds uses of `LessThan` where the inputs are not constrained to be less \
+                      than `p/2`.",
+        report_codes: &[ReportCode::UnconstrainedLessThan],
+        default_severity: MessageCategory::Warning,
+        scope: PassScope::IntraProcess,
+        run: |_, cfg| unconstrained_less_than::find_unconstrained_less_than(cfg),
+    },
+    PassDescriptor {
+        id: "constant-branch-condition",
+        name: "Constant branching condition",
+        description: "Finds if-statement conditions which are always true or always false.",
+        report_codes: &[ReportCode::ConstantBranchCondition],
+        default_severity: MessageCategory::Warning,
+        scope: PassScope::IntraProcess,
+        run: |context, cfg| constant_conditional::find_constant_conditional_statement(context, cfg),
+    },
+    PassDescriptor {
+        id: "under-constrained-signal",
+        name: "Under-constrained signal",
+        description: "Finds intermediate signals which occur in too few separate constraints.",
+        report_codes: &[ReportCode::UnderConstrainedSignal],
+        default_severity: MessageCategory::Warning,
+        scope: PassScope::IntraProcess,
+        run: |context, cfg| {
+            under_constrained_signals::find_under_constrained_signals(context.config(), cfg)
+        },
+    },
+    PassDescriptor {
+        id: "non-strict-binary-conversion",
+        name: "Non-strict binary conversion",
+        description: "Finds uses of `Num2Bits` and `Bits2Num` where the input may have two valid \
+                      bit-representations.",
+        report_codes: &[ReportCode::NonStrictBinaryConversion],
+        default_severity: MessageCategory::Warning,
+        scope: PassScope::IntraProcess,
+        run: |_, cfg| nonstrict_binary_conversion::find_nonstrict_binary_conversion(cfg),
+    },
+    // Inter-process analysis passes.
+    PassDescriptor {
+        id: "unused-output-signal",
+        name: "Unused output signal",
+        description: "Finds sub-components whose output signals are never used.",
+        report_codes: &[ReportCode::UnusedOutputSignal],
+        default_severity: MessageCategory::Warning,
+        scope: PassScope::InterProcess,
+        run: unused_output_signal::find_unused_output_signals,
+    },
+];
+
+/// Returns all built-in analysis passes, in the order in which they are run.
+pub fn builtin_passes() -> &'static [PassDescriptor] {
+    &ANALYSIS_PASSES
+}
+
+/// Returns the built-in analysis passes followed by the given custom passes,
+/// in the order in which they are run.
+pub fn analysis_passes(
+    custom_passes: &[Arc<dyn AnalysisPass>],
+) -> impl Iterator<Item = &dyn AnalysisPass> {
+    builtin_passes()
+        .iter()
+        .map(|pass| pass as &dyn AnalysisPass)
+        .chain(custom_passes.iter().map(|pass| pass.as_ref()))
+}
+
+/// Selects which analysis passes to run. If the list of passes to run is
+/// empty, all passes which are not explicitly skipped are run.
+#[derive(Clone, Debug, Default)]
+pub struct PassSelection {
+    only: Vec<String>,
+    skip: Vec<String>,
+}
+
+impl PassSelection {
+    /// Creates a new selection from the given lists of pass IDs.
+    pub fn new(only: &[String], skip: &[String]) -> PassSelection {
+        PassSelection { only: only.to_vec(), skip: skip.to_vec() }
+    }
+
+    /// Returns the selection obtained by applying the given lists of pass IDs
+    /// on top of this selection (e.g. command line options on top of the
+    /// configuration file). A non-empty list of passes to run replaces the
+    /// current list, while skipped passes are added to the current list.
+    #[must_use]
+    pub fn with_overrides(&self, only: &[String], skip: &[String]) -> PassSelection {
+        let mut result = self.clone();
+        if !only.is_empty() {
+            result.only = only.to_vec();
+        }
+        result.skip.extend(skip.iter().filter(|id| !self.skip.contains(id)).cloned());
+        result
+    }
+
+    /// Returns an error if a pass ID does not correspond to one of the given
+    /// passes.
+    pub fn validate<'a>(
+        &self,
+        passes: impl IntoIterator<Item = &'a dyn AnalysisPass>,
+    ) -> Result<()> {
+        let ids = passes.into_iter().map(|pass| pass.id()).collect::<Vec<_>>();
+        match self.only.iter().chain(self.skip.iter()).find(|id| !ids.contains(&id.as_str())) {
+            Some(id) => Err(anyhow!(
+                "unknown analysis pass `{id}` (use `--list-passes` to list all passes)"
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the IDs of the passes to run. (If this is empty, all passes
+    /// which are not skipped are run.)
+    pub fn only(&self) -> &[String] {
+        &self.only
+    }
+
+    /// Returns the IDs of the passes to skip.
+    pub fn skip(&self) -> &[String] {
+        &self.skip
+    }
+
+    /// Returns true if the given pass is selected.
+    pub fn is_selected(&self, pass: &dyn AnalysisPass) -> bool {
+        let id = pass.id().to_string();
+        (self.only.is_empty() || self.only.contains(&id)) && !self.skip.contains(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn passes() -> impl Iterator<Item = &'static dyn AnalysisPass> {
+        analysis_passes(&[])
+    }
+
+    #[test]
+    fn test_builtin_passes() {
+        let ids = passes().map(|pass| pass.id()).collect::<HashSet<_>>();
+        assert_eq!(ids.len(), builtin_passes().len());
+        assert!(passes().all(|pass| !pass.report_codes().is_empty()));
+        assert!(!ids.contains("CS0005"));
+        let pass = passes().find(|pass| pass.id() == "field-element-arithmetic").unwrap();
+        assert!(matches!(pass.default_severity(), MessageCategory::Warning));
+    }
+
+    #[test]
+    fn test_pass_selection() {
+        let ids = |selection: &PassSelection| {
+            passes()
+                .filter(|pass| selection.is_selected(*pass))
+                .map(|pass| pass.id())
+                .collect::<Vec<_>>()
+        };
+        let selection = PassSelection::default();
+        assert_eq!(ids(&selection).len(), builtin_passes().len());
+
+        let only = vec!["signal-assignment".to_string(), "unused-output-signal".to_string()];
+        let skip = vec!["unused-output-signal".to_string()];
+        let selection = PassSelection::new(&only, &skip);
+        assert!(selection.validate(passes()).is_ok());
+        assert_eq!(ids(&selection), vec!["signal-assignment"]);
+
+        let selection = PassSelection::new(&[], &skip);
+        assert_eq!(ids(&selection).len(), builtin_passes().len() - 1);
+        assert!(!ids(&selection).contains(&"unused-output-signal"));
+
+        let selection = PassSelection::new(&["unknown".to_string()], &[]);
+        assert!(selection.validate(passes()).is_err());
+    }
+
+    #[test]
+    fn test_pass_selection_overrides() {
+        let ids = |selection: &PassSelection| {
+            passes()
+                .filter(|pass| selection.is_selected(*pass))
+                .map(|pass| pass.id())
+                .collect::<Vec<_>>()
+        };
+        let to_vec = |ids: &[&str]| ids.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let config = PassSelection::new(
+            &to_vec(&["signal-assignment", "unused-output-signal"]),
+            &to_vec(&["field-element-arithmetic"]),
+        );
+
+        // Without overrides, the original selection is used.
+        assert_eq!(ids(&config.with_overrides(&[], &[])), ids(&config));
+
+        // Skipped passes are added to the skipped passes from the original
+        // selection.
+        let selection = config.with_overrides(&[], &to_vec(&["unused-output-signal"]));
+        assert_eq!(ids(&selection), vec!["signal-assignment"]);
+
+        // A non-empty list of passes to run replaces the original list, but
+        // passes skipped by the original selection are still skipped.
+        let selection = config
+            .with_overrides(&to_vec(&["field-element-arithmetic", "bitwise-complement"]), &[]);
+        assert_eq!(ids(&selection), vec!["bitwise-complement"]);
+    }
+}
@@ -0,0 +1,1041 @@
+//! A structural pattern language for querying the IR of templates and
+//! functions.
+//!
+//! A pattern is either a single expression, or a list of statements separated
+//! by `;`. Patterns use Circom syntax, extended with
+//!
+//!   1. metavariables like `$x`, which match any expression (or template or
+//!      function name when used as the callee of a call),
+//!   2. typed metavariables like `$x:input`, which only match expressions of
+//!      the given kind (`input`, `output`, `intermediate`, `signal`, `var`,
+//!      `component`, or `constant`),
+//!   3. the wildcard `_`, which matches any expression, and
+//!   4. `...` as the last argument of a call, which matches any remaining
+//!      arguments.
+//!
+//! Expression patterns match any sub-expression of any statement. Statement
+//! patterns match substitutions (using `<==`, `<--`, `=`, `==>`, or `-->`) and
+//! constraints (using `===`). If a pattern contains multiple statements, each
+//! statement must match a distinct statement in the same template or function
+//! (in any order). Each metavariable must match the same expression (up to SSA
+//! versions) everywhere it occurs. E.g. the pattern
+//!
+//! ```text
+//! $c = LessThan($n); $c.in[0] <== $x:input
+//! ```
+//!
+//! matches instantiations of `LessThan` where the first input is assigned
+//! directly from an input signal. To bound the time spent on patterns with
+//! many statements, the search for matches of a statement pattern in a single
+//! template or function is stopped (with a warning) after `MAX_SEARCH_STATES`
+//! candidate statements have been tried.
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, bail, Result};
+use log::warn;
+use num_bigint::BigInt;
+use once_cell::sync::Lazy;
+
+use program_structure::cfg::{Cfg, IrNode};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::*;
+use program_structure::report::{MessageCategory, Report, ReportCollection};
+use program_structure::report_code::ReportCode;
+
+use crate::analysis_context::AnalysisContext;
+use crate::pass_registry::AnalysisPass;
+
+/// The maximum number of candidate statements tried when searching for
+/// matches of a statement pattern in a single CFG.
+const MAX_SEARCH_STATES: usize = 100_000;
+
+/// A parsed query pattern.
+#[derive(Clone)]
+pub struct Pattern {
+    source: String,
+    kind: PatternKind,
+}
+
+#[derive(Clone)]
+enum PatternKind {
+    Expression(ExpressionPattern),
+    Statements(Vec<StatementPattern>),
+}
+
+#[derive(Clone)]
+enum StatementPattern {
+    Substitution { target: ExpressionPattern, op: AssignOp, value: ExpressionPattern },
+    Constraint { lhe: ExpressionPattern, rhe: ExpressionPattern },
+}
+
+#[derive(Clone)]
+enum ExpressionPattern {
+    Wildcard,
+    Metavariable {
+        name: String,
+        kind: Option<MetavariableKind>,
+    },
+    Identifier(String),
+    Number(BigInt),
+    Call {
+        callee: Box<ExpressionPattern>,
+        args: Vec<ExpressionPattern>,
+        has_rest: bool,
+    },
+    Access {
+        var: Box<ExpressionPattern>,
+        access: Vec<AccessPattern>,
+    },
+    InfixOp {
+        lhe: Box<ExpressionPattern>,
+        op: ExpressionInfixOpcode,
+        rhe: Box<ExpressionPattern>,
+    },
+    PrefixOp {
+        op: ExpressionPrefixOpcode,
+        rhe: Box<ExpressionPattern>,
+    },
+    SwitchOp {
+        cond: Box<ExpressionPattern>,
+        if_true: Box<ExpressionPattern>,
+        if_false: Box<ExpressionPattern>,
+    },
+}
+
+#[derive(Clone)]
+enum AccessPattern {
+    Array(ExpressionPattern),
+    Component(String),
+}
+
+/// Restricts the expressions matched by a metavariable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MetavariableKind {
+    Input,
+    Output,
+    Intermediate,
+    Signal,
+    Var,
+    Component,
+    Constant,
+}
+
+impl MetavariableKind {
+    fn from_name(name: &str) -> Option<MetavariableKind> {
+        use MetavariableKind::*;
+        match name {
+            "input" => Some(Input),
+            "output" => Some(Output),
+            "intermediate" => Some(Intermediate),
+            "signal" => Some(Signal),
+            "var" => Some(Var),
+            "component" => Some(Component),
+            "constant" => Some(Constant),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, meta: &Meta) -> bool {
+        use MetavariableKind::*;
+        let signal_type = match meta.type_knowledge().variable_type() {
+            Some(VariableType::Signal(signal_type, _)) => Some(*signal_type),
+            _ => None,
+        };
+        match self {
+            Input => signal_type == Some(SignalType::Input),
+            Output => signal_type == Some(SignalType::Output),
+            Intermediate => signal_type == Some(SignalType::Intermediate),
+            Signal => meta.type_knowledge().is_signal(),
+            Var => meta.type_knowledge().is_local(),
+            Component => meta.type_knowledge().is_component(),
+            Constant => meta.value_knowledge().is_constant(),
+        }
+    }
+}
+
+/// A match of a pattern in a CFG.
+#[derive(Clone, Debug)]
+pub struct QueryMatch {
+    /// The locations of the matched statements (in pattern order), or the
+    /// location of the matched expression.
+    pub locations: Vec<(Option<FileID>, FileLocation)>,
+    /// The expression (or name) bound to each metavariable.
+    pub bindings: BTreeMap<String, String>,
+}
+
+impl QueryMatch {
+    /// Converts the match into a report with the given message and code.
+    pub fn to_report(&self, message: &str, code: ReportCode, category: MessageCategory) -> Report {
+        let mut report = match category {
+            MessageCategory::Error => Report::error(message.to_string(), code),
+            MessageCategory::Warning => Report::warning(message.to_string(), code),
+            MessageCategory::Info => Report::info(message.to_string(), code),
+        };
+        let mut locations = self.locations.iter();
+        if let Some((Some(file_id), location)) = locations.next() {
+            report.add_primary(location.clone(), *file_id, "Pattern matched here.".to_string());
+        }
+        for (file_id, location) in locations {
+            if let Some(file_id) = file_id {
+                report.add_secondary(location.clone(), *file_id, None);
+            }
+        }
+        if !self.bindings.is_empty() {
+            let bindings = self
+                .bindings
+                .iter()
+                .map(|(name, value)| format!("`${name}` = `{value}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            report.add_note(format!("Metavariables: {bindings}."));
+        }
+        report
+    }
+}
+
+type Bindings = BTreeMap<String, String>;
+
+impl Pattern {
+    /// Returns all matches of the pattern in the given CFG, in source order.
+    pub fn find_matches(&self, cfg: &Cfg) -> Vec<QueryMatch> {
+        self.find_matches_with_limit(cfg, MAX_SEARCH_STATES)
+    }
+
+    fn find_matches_with_limit(&self, cfg: &Cfg, max_states: usize) -> Vec<QueryMatch> {
+        let statements = cfg
+            .iter()
+            .flat_map(|basic_block| basic_block.iter())
+            .filter(|stmt| !is_phi_statement(stmt))
+            .collect::<Vec<_>>();
+        let mut matches = Vec::new();
+        match &self.kind {
+            PatternKind::Expression(pattern) => {
+                let mut worklist =
+                    statements.iter().map(|stmt| IrNode::Statement(stmt)).collect::<Vec<_>>();
+                while let Some(node) = worklist.pop() {
+                    if let IrNode::Expression(expr) = node {
+                        let mut bindings = Bindings::new();
+                        if !matches!(expr, Expression::Update { .. })
+                            && match_expression(pattern, expr, &mut bindings)
+                        {
+                            let meta = expr.meta();
+                            matches.push(QueryMatch {
+                                locations: vec![(meta.file_id(), meta.file_location())],
+                                bindings,
+                            });
+                        }
+                    }
+                    worklist.extend(node.children());
+                }
+            }
+            PatternKind::Statements(patterns) => {
+                // Each statement pattern can only match statements that match
+                // the pattern on its own (with no metavariables bound).
+                let candidates = patterns
+                    .iter()
+                    .map(|pattern| {
+                        statements
+                            .iter()
+                            .copied()
+                            .filter(|stmt| match_statement(pattern, stmt, &mut Bindings::new()))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                let mut budget = max_states;
+                if candidates.iter().all(|candidates| !candidates.is_empty()) {
+                    search_statements(
+                        patterns,
+                        &candidates,
+                        &mut Vec::new(),
+                        Bindings::new(),
+                        &mut matches,
+                        &mut budget,
+                    );
+                }
+                if budget == 0 {
+                    warn!(
+                        "stopped searching for matches of `{self}` in `{}` after trying \
+                         {max_states} statements (some matches may be missing)",
+                        cfg.name()
+                    );
+                }
+            }
+        }
+        matches.sort_by_key(|m| {
+            m.locations.first().map(|(file_id, location)| (*file_id, location.start, location.end))
+        });
+        matches
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = anyhow::Error;
+
+    fn from_str(source: &str) -> Result<Pattern> {
+        let tokens = tokenize(source)?;
+        let kind = Parser { tokens, index: 0 }.parse_pattern()?;
+        Ok(Pattern { source: source.to_string(), kind })
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// A custom analysis pass reporting each match of a pattern.
+pub struct QueryPass {
+    id: String,
+    description: String,
+    message: String,
+    pattern: Pattern,
+    category: MessageCategory,
+    report_codes: [ReportCode; 1],
+}
+
+impl QueryPass {
+    /// Creates a new pass with the given pass ID, which reports each match of
+    /// the pattern using the given report ID, message, and category.
+    pub fn new(
+        id: &str,
+        report_id: &str,
+        pattern: Pattern,
+        message: &str,
+        category: MessageCategory,
+    ) -> QueryPass {
+        let code = ReportCode::Custom { id: intern(report_id), name: intern(id) };
+        QueryPass {
+            id: id.to_string(),
+            description: format!("Finds matches of the pattern `{pattern}`."),
+            message: message.to_string(),
+            pattern,
+            category,
+            report_codes: [code],
+        }
+    }
+}
+
+impl AnalysisPass for QueryPass {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        "Custom query"
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn report_codes(&self) -> &[ReportCode] {
+        &self.report_codes
+    }
+
+    fn default_severity(&self) -> MessageCategory {
+        self.category
+    }
+
+    /// The fingerprint includes the pattern, the report ID and message, and
+    /// the severity, since changing any of these changes the reports.
+    fn fingerprint(&self) -> String {
+        let code = &self.report_codes[0];
+        format!(
+            "{}\0{}\0{} ({})\0{}\0{}",
+            self.id,
+            self.pattern,
+            code.id(),
+            code.name(),
+            self.message,
+            self.category
+        )
+    }
+
+    fn run(&self, _: &dyn AnalysisContext, cfg: &Cfg) -> ReportCollection {
+        self.pattern
+            .find_matches(cfg)
+            .iter()
+            .map(|m| m.to_report(&self.message, self.report_codes[0], self.category))
+            .collect()
+    }
+}
+
+/// Report codes require static strings. Since query passes may be created
+/// repeatedly (e.g. by the language server), strings are interned to ensure
+/// that each string is only leaked once.
+fn intern(value: &str) -> &'static str {
+    static STRINGS: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+    let mut strings = STRINGS.lock().unwrap_or_else(|err| err.into_inner());
+    if let Some(value) = strings.get(value) {
+        return value;
+    }
+    let value: &'static str = Box::leak(value.to_string().into_boxed_str());
+    strings.insert(value);
+    value
+}
+
+fn is_phi_statement(stmt: &Statement) -> bool {
+    matches!(stmt, Statement::Substitution { rhe: Expression::Phi { .. }, .. })
+}
+
+/// Matches each statement pattern against a distinct statement from the
+/// corresponding list of candidates, and records each complete match. The
+/// search is stopped once `budget` candidate statements have been tried.
+fn search_statements<'a>(
+    patterns: &[StatementPattern],
+    candidates: &[Vec<&'a Statement>],
+    matched: &mut Vec<&'a Statement>,
+    bindings: Bindings,
+    matches: &mut Vec<QueryMatch>,
+    budget: &mut usize,
+) {
+    let (Some((pattern, patterns)), Some((statements, candidates))) =
+        (patterns.split_first(), candidates.split_first())
+    else {
+        let locations =
+            matched.iter().map(|stmt| (stmt.meta().file_id(), stmt.meta().file_location()));
+        matches.push(QueryMatch { locations: locations.collect(), bindings });
+        return;
+    };
+    for stmt in statements {
+        if matched.iter().any(|other| std::ptr::eq(*other, *stmt)) {
+            continue;
+        }
+        if *budget == 0 {
+            return;
+        }
+        *budget -= 1;
+        let mut new_bindings = bindings.clone();
+        if match_statement(pattern, stmt, &mut new_bindings) {
+            matched.push(stmt);
+            search_statements(patterns, candidates, matched, new_bindings, matches, budget);
+            matched.pop();
+        }
+    }
+}
+
+fn match_statement(pattern: &StatementPattern, stmt: &Statement, bindings: &mut Bindings) -> bool {
+    match (pattern, stmt) {
+        (
+            StatementPattern::Substitution { target, op, value },
+            Statement::Substitution { meta, var, op: stmt_op, rhe },
+        ) => {
+            if op != stmt_op {
+                return false;
+            }
+            // Array and component signal assignments are lifted to `Update`
+            // expressions. The type of the updated variable is tracked by the
+            // statement metadata.
+            let var_expr = Expression::Variable { meta: meta.clone(), name: var.clone() };
+            let (access, rhe) = match rhe {
+                Expression::Update { access, rhe, .. } => (access.as_slice(), rhe.as_ref()),
+                _ => (&[][..], rhe),
+            };
+            let target_matches = match target {
+                ExpressionPattern::Access { var: var_pattern, access: access_pattern } => {
+                    match_expression(var_pattern, &var_expr, bindings)
+                        && match_access(access_pattern, access, bindings)
+                }
+                _ => access.is_empty() && match_expression(target, &var_expr, bindings),
+            };
+            target_matches && match_expression(value, rhe, bindings)
+        }
+        (
+            StatementPattern::Constraint { lhe, rhe },
+            Statement::ConstraintEquality { lhe: l, rhe: r, .. },
+        ) => {
+            // Constraints are symmetric.
+            let mut reversed = bindings.clone();
+            if match_expression(lhe, l, bindings) && match_expression(rhe, r, bindings) {
+                true
+            } else if match_expression(lhe, r, &mut reversed)
+                && match_expression(rhe, l, &mut reversed)
+            {
+                *bindings = reversed;
+                true
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Binds the metavariable to the given value. Returns false if the
+/// metavariable is already bound to a different value.
+fn bind(name: &str, value: String, bindings: &mut Bindings) -> bool {
+    match bindings.get(name) {
+        Some(bound) => *bound == value,
+        None => {
+            bindings.insert(name.to_string(), value);
+            true
+        }
+    }
+}
+
+fn match_expression(
+    pattern: &ExpressionPattern,
+    expr: &Expression,
+    bindings: &mut Bindings,
+) -> bool {
+    use ExpressionPattern as P;
+    match (pattern, expr) {
+        (P::Wildcard, _) => true,
+        // Metavariables are bound to the string representation of the
+        // expression, which ignores SSA versions.
+        (P::Metavariable { name, kind }, _) => {
+            kind.map_or(true, |kind| kind.matches(expr.meta()))
+                && bind(name, expr.to_string(), bindings)
+        }
+        (P::Identifier(name), Expression::Variable { name: var, .. }) => var.name() == name,
+        (P::Number(value), Expression::Number(_, number)) => value == number,
+        (P::Call { callee, args, has_rest }, Expression::Call { name, args: call_args, .. }) => {
+            let callee_matches = match callee.as_ref() {
+                P::Identifier(callee) => callee == name,
+                P::Metavariable { name: metavariable, kind: None } => {
+                    bind(metavariable, name.clone(), bindings)
+                }
+                _ => false,
+            };
+            let arity_matches = if *has_rest {
+                args.len() <= call_args.len()
+            } else {
+                args.len() == call_args.len()
+            };
+            callee_matches
+                && arity_matches
+                && args
+                    .iter()
+                    .zip(call_args)
+                    .all(|(arg, expr)| match_expression(arg, expr, bindings))
+        }
+        (
+            P::Access { var, access },
+            Expression::Access { meta, var: name, access: expr_access },
+        ) => {
+            let var_expr = Expression::Variable { meta: meta.clone(), name: name.clone() };
+            match_expression(var, &var_expr, bindings)
+                && match_access(access, expr_access, bindings)
+        }
+        (P::InfixOp { lhe, op, rhe }, Expression::InfixOp { lhe: l, infix_op, rhe: r, .. }) => {
+            op == infix_op
+                && match_expression(lhe, l, bindings)
+                && match_expression(rhe, r, bindings)
+        }
+        (P::PrefixOp { op, rhe }, Expression::PrefixOp { prefix_op, rhe: r, .. }) => {
+            op == prefix_op && match_expression(rhe, r, bindings)
+        }
+        (
+            P::SwitchOp { cond, if_true, if_false },
+            Expression::SwitchOp { cond: c, if_true: t, if_false: f, .. },
+        ) => {
+            match_expression(cond, c, bindings)
+                && match_expression(if_true, t, bindings)
+                && match_expression(if_false, f, bindings)
+        }
+        _ => false,
+    }
+}
+
+fn match_access(
+    patterns: &[AccessPattern],
+    access: &[AccessType],
+    bindings: &mut Bindings,
+) -> bool {
+    patterns.len() == access.len()
+        && patterns.iter().zip(access).all(|(pattern, access)| match (pattern, access) {
+            (AccessPattern::Array(pattern), AccessType::ArrayAccess(index)) => {
+                match_expression(pattern, index, bindings)
+            }
+            (AccessPattern::Component(name), AccessType::ComponentAccess(signal)) => name == signal,
+            _ => false,
+        })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Identifier(String),
+    Metavariable(String, Option<MetavariableKind>),
+    Number(BigInt),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Identifier(name) => write!(f, "{name}"),
+            Token::Metavariable(name, _) => write!(f, "${name}"),
+            Token::Number(value) => write!(f, "{value}"),
+            Token::Symbol(symbol) => write!(f, "{symbol}"),
+        }
+    }
+}
+
+/// Symbols ordered such that longer symbols are matched first.
+const SYMBOLS: [&str; 38] = [
+    "...", "<==", "<--", "==>", "-->", "===", "==", "!=", "<=", ">=", "<<", ">>", "&&", "||", "**",
+    "+", "-", "*", "/", "\\", "%", "<", ">", "!", "~", "&", "|", "^", "(", ")", "[", "]", ",", ".",
+    ";", "?", ":", "=",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let is_identifier_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut tokens = Vec::new();
+    let mut rest = source.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if c.is_ascii_alphabetic() || c == '_' {
+            let end = rest.find(|c| !is_identifier_char(c)).unwrap_or(rest.len());
+            tokens.push(Token::Identifier(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if c == '$' {
+            let name_end = rest[1..].find(|c| !is_identifier_char(c)).map_or(rest.len(), |i| i + 1);
+            let name = &rest[1..name_end];
+            if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+                bail!("invalid metavariable name at `{rest}`");
+            }
+            rest = &rest[name_end..];
+            // A metavariable may be directly followed by `:kind`.
+            let mut kind = None;
+            if let Some(tail) = rest.strip_prefix(':') {
+                let kind_end = tail.find(|c| !is_identifier_char(c)).unwrap_or(tail.len());
+                if let Some(parsed) = MetavariableKind::from_name(&tail[..kind_end]) {
+                    kind = Some(parsed);
+                    rest = &tail[kind_end..];
+                }
+            }
+            tokens.push(Token::Metavariable(name.to_string(), kind));
+        } else if c.is_ascii_digit() {
+            let end = rest.find(|c| !is_identifier_char(c)).unwrap_or(rest.len());
+            let literal = &rest[..end];
+            let value = match literal.strip_prefix("0x") {
+                Some(hex) => BigInt::parse_bytes(hex.as_bytes(), 16),
+                None => BigInt::parse_bytes(literal.as_bytes(), 10),
+            };
+            let Some(value) = value else {
+                bail!("invalid number `{literal}`");
+            };
+            tokens.push(Token::Number(value));
+            rest = &rest[end..];
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        } else {
+            bail!("unexpected character `{c}` in pattern");
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Binary operators, ordered by increasing precedence.
+const INFIX_OPERATORS: [&[(&str, ExpressionInfixOpcode)]; 11] = {
+    use ExpressionInfixOpcode::*;
+    [
+        &[("||", BoolOr)],
+        &[("&&", BoolAnd)],
+        &[("|", BitOr)],
+        &[("^", BitXor)],
+        &[("&", BitAnd)],
+        &[("==", Eq), ("!=", NotEq)],
+        &[("<=", LesserEq), (">=", GreaterEq), ("<", Lesser), (">", Greater)],
+        &[("<<", ShiftL), (">>", ShiftR)],
+        &[("+", Add), ("-", Sub)],
+        &[("*", Mul), ("/", Div), ("\\", IntDiv), ("%", Mod)],
+        &[("**", Pow)],
+    ]
+};
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    /// Consumes the given symbol if it is the next token.
+    fn eat(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(next)) if *next == symbol) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<()> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{symbol}`")))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> anyhow::Error {
+        match self.peek() {
+            Some(token) => anyhow!("expected {expected}, found `{token}`"),
+            None => anyhow!("expected {expected}, found end of pattern"),
+        }
+    }
+
+    fn parse_pattern(&mut self) -> Result<PatternKind> {
+        let mut statements = Vec::new();
+        let mut expressions = Vec::new();
+        loop {
+            if self.peek().is_none() {
+                break;
+            }
+            match self.parse_statement()? {
+                Ok(statement) => statements.push(statement),
+                Err(expression) => expressions.push(expression),
+            }
+            if !self.eat(";") {
+                break;
+            }
+        }
+        if self.peek().is_some() {
+            return Err(self.unexpected("`;`"));
+        }
+        match (statements.is_empty(), expressions.len()) {
+            (true, 0) => bail!("empty pattern"),
+            (true, 1) => Ok(PatternKind::Expression(expressions.remove(0))),
+            (false, 0) => Ok(PatternKind::Statements(statements)),
+            _ => bail!("a pattern must be either a single expression or a list of statements"),
+        }
+    }
+
+    /// Parses a statement. Returns the expression if there is no statement
+    /// operator following the first expression.
+    fn parse_statement(
+        &mut self,
+    ) -> Result<std::result::Result<StatementPattern, ExpressionPattern>> {
+        let lhe = self.parse_expression()?;
+        let (op, reversed) = if self.eat("<==") {
+            (AssignOp::AssignConstraintSignal, false)
+        } else if self.eat("<--") {
+            (AssignOp::AssignSignal, false)
+        } else if self.eat("=") {
+            (AssignOp::AssignLocalOrComponent, false)
+        } else if self.eat("==>") {
+            (AssignOp::AssignConstraintSignal, true)
+        } else if self.eat("-->") {
+            (AssignOp::AssignSignal, true)
+        } else if self.eat("===") {
+            let rhe = self.parse_expression()?;
+            return Ok(Ok(StatementPattern::Constraint { lhe, rhe }));
+        } else {
+            return Ok(Err(lhe));
+        };
+        let rhe = self.parse_expression()?;
+        let (target, value) = if reversed { (rhe, lhe) } else { (lhe, rhe) };
+        let is_valid_target = match &target {
+            ExpressionPattern::Access { var, .. } => matches!(
+                var.as_ref(),
+                ExpressionPattern::Identifier(_)
+                    | ExpressionPattern::Metavariable { .. }
+                    | ExpressionPattern::Wildcard
+            ),
+            ExpressionPattern::Identifier(_)
+            | ExpressionPattern::Metavariable { .. }
+            | ExpressionPattern::Wildcard => true,
+            _ => false,
+        };
+        if !is_valid_target {
+            bail!("the target of an assignment must be a variable, signal, or component");
+        }
+        Ok(Ok(StatementPattern::Substitution { target, op, value }))
+    }
+
+    fn parse_expression(&mut self) -> Result<ExpressionPattern> {
+        let cond = self.parse_infix(0)?;
+        if !self.eat("?") {
+            return Ok(cond);
+        }
+        let if_true = self.parse_expression()?;
+        self.expect(":")?;
+        let if_false = self.parse_expression()?;
+        Ok(ExpressionPattern::SwitchOp {
+            cond: Box::new(cond),
+            if_true: Box::new(if_true),
+            if_false: Box::new(if_false),
+        })
+    }
+
+    fn parse_infix(&mut self, level: usize) -> Result<ExpressionPattern> {
+        if level == INFIX_OPERATORS.len() {
+            return self.parse_prefix();
+        }
+        let mut lhe = self.parse_infix(level + 1)?;
+        while let Some(op) = self.eat_infix_operator(level) {
+            // Exponentiation is right-associative.
+            let rhe = if op == ExpressionInfixOpcode::Pow {
+                self.parse_infix(level)?
+            } else {
+                self.parse_infix(level + 1)?
+            };
+            lhe = ExpressionPattern::InfixOp { lhe: Box::new(lhe), op, rhe: Box::new(rhe) };
+        }
+        Ok(lhe)
+    }
+
+    fn eat_infix_operator(&mut self, level: usize) -> Option<ExpressionInfixOpcode> {
+        let Some(Token::Symbol(symbol)) = self.peek() else {
+            return None;
+        };
+        let (_, op) = INFIX_OPERATORS[level].iter().find(|(name, _)| name == symbol)?;
+        self.index += 1;
+        Some(*op)
+    }
+
+    fn parse_prefix(&mut self) -> Result<ExpressionPattern> {
+        let op = if self.eat("-") {
+            ExpressionPrefixOpcode::Sub
+        } else if self.eat("!") {
+            ExpressionPrefixOpcode::BoolNot
+        } else if self.eat("~") {
+            ExpressionPrefixOpcode::Complement
+        } else {
+            return self.parse_postfix();
+        };
+        let rhe = self.parse_prefix()?;
+        Ok(ExpressionPattern::PrefixOp { op, rhe: Box::new(rhe) })
+    }
+
+    fn parse_postfix(&mut self) -> Result<ExpressionPattern> {
+        let mut expr = self.parse_primary()?;
+        if matches!(expr, ExpressionPattern::Identifier(_) | ExpressionPattern::Metavariable { .. })
+            && self.eat("(")
+        {
+            let (args, has_rest) = self.parse_arguments()?;
+            return Ok(ExpressionPattern::Call { callee: Box::new(expr), args, has_rest });
+        }
+        let mut access = Vec::new();
+        loop {
+            if self.eat("[") {
+                access.push(AccessPattern::Array(self.parse_expression()?));
+                self.expect("]")?;
+            } else if self.eat(".") {
+                match self.next() {
+                    Some(Token::Identifier(name)) => access.push(AccessPattern::Component(name)),
+                    _ => bail!("expected signal name after `.`"),
+                }
+            } else {
+                break;
+            }
+        }
+        if !access.is_empty() {
+            expr = ExpressionPattern::Access { var: Box::new(expr), access };
+        }
+        Ok(expr)
+    }
+
+    fn parse_arguments(&mut self) -> Result<(Vec<ExpressionPattern>, bool)> {
+        let mut args = Vec::new();
+        if self.eat(")") {
+            return Ok((args, false));
+        }
+        loop {
+            if self.eat("...") {
+                self.expect(")")?;
+                return Ok((args, true));
+            }
+            args.push(self.parse_expression()?);
+            if self.eat(")") {
+                return Ok((args, false));
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<ExpressionPattern> {
+        match self.next() {
+            Some(Token::Identifier(name)) if name == "_" => Ok(ExpressionPattern::Wildcard),
+            Some(Token::Identifier(name)) => Ok(ExpressionPattern::Identifier(name)),
+            Some(Token::Metavariable(name, kind)) => {
+                Ok(ExpressionPattern::Metavariable { name, kind })
+            }
+            Some(Token::Number(value)) => Ok(ExpressionPattern::Number(value)),
+            Some(Token::Symbol("(")) => {
+                let expr = self.parse_expression()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            _ => {
+                self.index -= 1;
+                Err(self.unexpected("an expression"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use program_structure::constants::Curve;
+
+    use crate::analysis_runner::AnalysisRunner;
+
+    use super::*;
+
+    fn find_matches(pattern: &str, src: &str) -> Vec<QueryMatch> {
+        let runner = AnalysisRunner::new(Curve::Goldilocks).with_src(&[src]);
+        let name = runner.template_names(false).pop().unwrap();
+        let cfg = runner.template(&name).unwrap();
+        pattern.parse::<Pattern>().unwrap().find_matches(cfg)
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        for pattern in [
+            "LessThan($n)",
+            "$c = LessThan($n); $c.in[0] <== $x:input",
+            "$a * $b === $c",
+            "Num2Bits(...)",
+            "$f($x, ...)",
+            "$x <-- $a / $b",
+            "$a ==> $b.in",
+            "$c ? -$a : ~$b ** 2 ** 3",
+            "0x10 + (1 << 2)",
+        ] {
+            assert!(pattern.parse::<Pattern>().is_ok(), "failed to parse `{pattern}`");
+        }
+        for pattern in ["", "$", "LessThan(", "a + b <== c", "a; b <== c", "a b", "#"] {
+            assert!(pattern.parse::<Pattern>().is_err(), "parsed `{pattern}`");
+        }
+    }
+
+    #[test]
+    fn test_expression_pattern() {
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                signal output c;
+                c <-- a / b;
+                c * b === a;
+            }
+        "#;
+        let matches = find_matches("$x / $y", src);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].bindings["x"], "a");
+        assert_eq!(matches[0].bindings["y"], "b");
+        let location = &matches[0].locations[0].1;
+        assert_eq!(&src[location.clone()], "a / b");
+
+        // Metavariables must match the same expression everywhere.
+        assert_eq!(find_matches("$x * $x", src).len(), 0);
+        assert_eq!(find_matches("$x * $y:input", src).len(), 1);
+        assert_eq!(find_matches("$x:input * $y", src).len(), 0);
+    }
+
+    #[test]
+    fn test_statement_pattern() {
+        let src = r#"
+            template T() {
+                signal input in[2];
+                signal input x;
+                signal output out;
+
+                component lt = LessThan(8);
+                lt.in[0] <== x;
+                lt.in[1] <== in[1];
+                out <== lt.out;
+
+                component gt = GreaterThan(8);
+                gt.in[0] <== in[0] + 1;
+                gt.in[1] <== x;
+            }
+        "#;
+        let matches = find_matches("$c = LessThan($n); $c.in[0] <== $x:input", src);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].locations.len(), 2);
+        assert_eq!(matches[0].bindings["c"], "lt");
+        assert_eq!(matches[0].bindings["n"], "8");
+        assert_eq!(matches[0].bindings["x"], "x");
+
+        let matches = find_matches("$c = $T(8); $c.in[1] <== x", src);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].bindings["T"], "GreaterThan");
+
+        assert_eq!(find_matches("$c.in[0] <== $x:input", src).len(), 1);
+        assert_eq!(find_matches("$c.in[_] <== $x", src).len(), 4);
+        assert_eq!(find_matches("$x <== $c.out", src).len(), 1);
+        assert_eq!(find_matches("$c.out ==> out", src).len(), 1);
+        assert_eq!(find_matches("$c = LessThan(...)", src).len(), 1);
+        assert_eq!(find_matches("$c = LessThan()", src).len(), 0);
+        assert_eq!(find_matches("$c.in[0] <-- $x", src).len(), 0);
+    }
+
+    #[test]
+    fn test_search_limit() {
+        let mut src =
+            "template T() {\n    signal input in;\n    signal output out[32];\n".to_string();
+        for i in 0..32 {
+            src.push_str(&format!("    out[{i}] <== in;\n"));
+        }
+        src.push_str("}\n");
+        let pattern =
+            "$a[_] <== $x; $b[_] <== $x; $c[_] <== $x; $d[_] <== $x".parse::<Pattern>().unwrap();
+        let runner = AnalysisRunner::new(Curve::Goldilocks).with_src(&[&src]);
+        let cfg = runner.template("T").unwrap();
+
+        // The search is stopped once the limit is reached.
+        let matches = pattern.find_matches_with_limit(cfg, 1000);
+        assert!(!matches.is_empty() && matches.len() <= 1000);
+
+        // Statements which cannot match a pattern are never tried.
+        let pattern =
+            "$a[_] <== $x; $b[_] <== $x; $c[_] <== $x; $d === $x".parse::<Pattern>().unwrap();
+        assert!(pattern.find_matches_with_limit(cfg, 1).is_empty());
+        let pattern = "out[0] <== $x; out[1] <== $x".parse::<Pattern>().unwrap();
+        assert_eq!(pattern.find_matches_with_limit(cfg, 2).len(), 1);
+    }
+
+    #[test]
+    fn test_query_pass() {
+        let pattern = "$a === $b * $c".parse().unwrap();
+        let pass = QueryPass::new("test-query", "TEST001", pattern, "Test.", MessageCategory::Info);
+        assert_eq!(pass.id(), "test-query");
+        assert_eq!(pass.name(), "Custom query");
+        assert_eq!(pass.report_codes()[0].id(), "TEST001");
+
+        // The fingerprint changes if anything affecting the reports changes.
+        let fingerprint = |report_id, pattern: &str, message, category| {
+            let pattern = pattern.parse().unwrap();
+            QueryPass::new("test-query", report_id, pattern, message, category).fingerprint()
+        };
+        let info = MessageCategory::Info;
+        assert_eq!(pass.fingerprint(), fingerprint("TEST001", "$a === $b * $c", "Test.", info));
+        assert_ne!(pass.fingerprint(), fingerprint("TEST002", "$a === $b * $c", "Test.", info));
+        assert_ne!(pass.fingerprint(), fingerprint("TEST001", "$a === $b", "Test.", info));
+        assert_ne!(pass.fingerprint(), fingerprint("TEST001", "$a === $b * $c", "Other.", info));
+        assert_ne!(
+            pass.fingerprint(),
+            fingerprint("TEST001", "$a === $b * $c", "Test.", MessageCategory::Error)
+        );
+
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                a * b === b;
+            }
+        "#;
+        let runner = AnalysisRunner::new(Curve::Goldilocks).with_src(&[src]);
+        let reports = pass.run(&runner, runner.template("T").unwrap());
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].id(), "TEST001");
+        assert_eq!(reports[0].notes()[0], "Metavariables: `$a` = `b`, `$b` = `a`, `$c` = `b`.");
+    }
+}
@@ -0,0 +1,418 @@
+//! A persistent cache of analysis results. Results are stored per template or
+//! function, keyed by a hash of the definition, its transitive callees, the
+//! curve, the analysis configuration, and the analysis passes. This allows unchanged definitions to
+//! be skipped entirely when the analysis is re-run (e.g. in CI).
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use program_structure::constants::Curve;
+use program_structure::report::{MessageCategory, Report, ReportCollection, ReportLabel, Suggestion};
+use program_structure::report_code::ReportCode;
+
+use crate::config::{AnalysisConfig, ANALYSIS_VERSION};
+use crate::incremental::{transitive_callees, DefinitionSummary};
+use crate::instantiations::InstantiationContext;
+use crate::pass_registry::{builtin_passes, AnalysisPass};
+
+/// A persistent cache of analysis results, stored as one file per key in the
+/// cache directory.
+pub struct ResultCache {
+    dir: PathBuf,
+}
+
+/// A report with locations relative to the start of the analyzed definition.
+#[derive(Serialize, Deserialize)]
+struct CachedReport {
+    category: String,
+    message: String,
+    code: ReportCode,
+    primary: Vec<CachedLabel>,
+    secondary: Vec<CachedLabel>,
+    notes: Vec<String>,
+    #[serde(default)]
+    suggestions: Vec<CachedSuggestion>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedLabel {
+    start: usize,
+    end: usize,
+    message: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedSuggestion {
+    start: usize,
+    end: usize,
+    replacement: String,
+    message: String,
+}
+
+impl ResultCache {
+    /// Opens the cache in the given directory. The directory is created if it
+    /// does not exist.
+    pub fn open(dir: &Path) -> Result<ResultCache> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create cache directory `{}`", dir.display()))?;
+        Ok(ResultCache { dir: dir.to_path_buf() })
+    }
+
+    /// Returns the cached results for the given key, relocated to the current
+    /// location of the definition.
+    pub(crate) fn get(
+        &self,
+        key: &str,
+        definition: &DefinitionSummary,
+    ) -> Option<ReportCollection> {
+        let contents = fs::read_to_string(self.path(key)).ok()?;
+        let reports: Vec<CachedReport> = match serde_json::from_str(&contents) {
+            Ok(reports) => reports,
+            Err(err) => {
+                debug!("ignoring invalid cache entry `{key}`: {err}");
+                return None;
+            }
+        };
+        reports.into_iter().map(|report| report.into_report(definition)).collect()
+    }
+
+    /// Stores the results for the given key. Results are only cached if all
+    /// report locations are contained in the analyzed definition.
+    pub(crate) fn insert(&self, key: &str, definition: &DefinitionSummary, reports: &[Report]) {
+        let Some(reports) = reports
+            .iter()
+            .map(|report| CachedReport::from_report(report, definition))
+            .collect::<Option<Vec<_>>>()
+        else {
+            debug!("not caching results for `{key}` since they cannot be relocated or serialized");
+            return;
+        };
+        // Write to a temporary file first to avoid exposing partial entries to
+        // concurrent readers.
+        let path = self.path(key);
+        let tmp_path = path.with_extension(format!("tmp-{}", std::process::id()));
+        let result = serde_json::to_string(&reports)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| Ok(fs::write(&tmp_path, contents)?))
+            .and_then(|_| Ok(fs::rename(&tmp_path, &path)?));
+        if let Err(err) = result {
+            warn!("failed to write cache entry `{}`: {err}", path.display());
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+impl CachedReport {
+    fn from_report(report: &Report, definition: &DefinitionSummary) -> Option<CachedReport> {
+        // Custom report codes cannot be serialized.
+        if matches!(report.code(), ReportCode::Custom { .. }) {
+            return None;
+        }
+        let to_label = |label: &ReportLabel| {
+            if !definition.contains(label.file_id, &label.range) {
+                return None;
+            }
+            Some(CachedLabel {
+                start: label.range.start - definition.location.start,
+                end: label.range.end - definition.location.start,
+                message: label.message.clone(),
+            })
+        };
+        let to_suggestion = |suggestion: &Suggestion| {
+            if !definition.contains(suggestion.file_id(), suggestion.location()) {
+                return None;
+            }
+            Some(CachedSuggestion {
+                start: suggestion.location().start - definition.location.start,
+                end: suggestion.location().end - definition.location.start,
+                replacement: suggestion.replacement().to_string(),
+                message: suggestion.message().to_string(),
+            })
+        };
+        Some(CachedReport {
+            category: report.category().to_string(),
+            message: report.message().clone(),
+            code: *report.code(),
+            primary: report.primary().iter().map(to_label).collect::<Option<_>>()?,
+            secondary: report.secondary().iter().map(to_label).collect::<Option<_>>()?,
+            notes: report.notes().clone(),
+            suggestions: report.suggestions().iter().map(to_suggestion).collect::<Option<_>>()?,
+        })
+    }
+
+    fn into_report(self, definition: &DefinitionSummary) -> Option<Report> {
+        let mut report = match self.category.parse().ok()? {
+            MessageCategory::Error => Report::error(self.message, self.code),
+            MessageCategory::Warning => Report::warning(self.message, self.code),
+            MessageCategory::Info => Report::info(self.message, self.code),
+        };
+        let offset = definition.location.start;
+        for label in self.primary {
+            let location = (label.start + offset)..(label.end + offset);
+            report.add_primary(location, definition.file_id, label.message);
+        }
+        for label in self.secondary {
+            let location = (label.start + offset)..(label.end + offset);
+            let message = if label.message.is_empty() { None } else { Some(label.message) };
+            report.add_secondary(location, definition.file_id, message);
+        }
+        for note in self.notes {
+            report.add_note(note);
+        }
+        for suggestion in self.suggestions {
+            let location = (suggestion.start + offset)..(suggestion.end + offset);
+            report.add_suggestion(
+                location,
+                definition.file_id,
+                suggestion.replacement,
+                suggestion.message,
+            );
+        }
+        Some(report)
+    }
+}
+
+/// Returns the cache key for the definition with the given name. The key is a
+/// hash of the source of the definition and all its transitive callees, the
+/// curve, the analysis configuration, the version of the analyzer and of the
+/// built-in analysis passes (see `config::ANALYSIS_VERSION`), and the
+/// fingerprints of all built-in and custom analysis passes. For templates reachable from the main component,
+/// the instantiation context is given. The key then also includes the main
+/// component and all templates and functions reachable from it, since these
+/// determine how the template is instantiated. Returns `None` if the
+/// definition is unknown.
+pub(crate) fn cache_key(
+    name: &str,
+    definitions: &HashMap<String, DefinitionSummary>,
+    curve: &Curve,
+    config: &AnalysisConfig,
+    custom_passes: &[String],
+    context: Option<&InstantiationContext>,
+) -> Option<String> {
+    definitions.get(name)?;
+
+    let mut hasher = Sha256::new();
+    update(&mut hasher, env!("CARGO_PKG_VERSION"));
+    update(&mut hasher, format!("analysis-version {ANALYSIS_VERSION}"));
+    update(&mut hasher, curve.to_string());
+    update_config(&mut hasher, config);
+    for pass in builtin_passes() {
+        update(&mut hasher, pass.fingerprint());
+    }
+    for fingerprint in custom_passes {
+        update(&mut hasher, fingerprint);
+    }
+    match context {
+        Some(context) => {
+            update(&mut hasher, format!("main {context}"));
+            update_sources(&mut hasher, &context.main.name, definitions);
+        }
+        None => update(&mut hasher, "no main"),
+    }
+    update(&mut hasher, name);
+    update_sources(&mut hasher, name, definitions);
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+/// Adds the source of the given definition and all its transitive callees to
+/// the hash.
+fn update_sources(
+    hasher: &mut Sha256,
+    name: &str,
+    definitions: &HashMap<String, DefinitionSummary>,
+) {
+    for name in transitive_callees(name, definitions) {
+        update(hasher, name);
+        match definitions.get(name) {
+            Some(definition) => update(hasher, &definition.source),
+            None => update(hasher, "<undefined>"),
+        }
+    }
+}
+
+/// Adds the given value to the hash, followed by a separator.
+fn update(hasher: &mut Sha256, value: impl AsRef<[u8]>) {
+    hasher.update(value);
+    hasher.update([0]);
+}
+
+/// Adds each field of the analysis configuration affecting the analysis
+/// results to the hash. (Severity overrides are applied after results are
+/// read from the cache, and are therefore not included.)
+fn update_config(hasher: &mut Sha256, config: &AnalysisConfig) {
+    // The configuration is destructured to ensure that new fields are added
+    // to the key.
+    let AnalysisConfig {
+        unused_output_signal_allow_list,
+        min_constraint_count,
+        max_cyclomatic_complexity,
+        max_parameters,
+        severity_overrides: _,
+        pass_selection,
+    } = config;
+    let mut allow_list = unused_output_signal_allow_list.iter().collect::<Vec<_>>();
+    allow_list.sort();
+    update(hasher, format!("unused-output-signal-allow-list {}", allow_list.len()));
+    for name in allow_list {
+        update(hasher, name);
+    }
+    update(hasher, format!("min-constraint-count {min_constraint_count}"));
+    update(hasher, format!("max-cyclomatic-complexity {max_cyclomatic_complexity:?}"));
+    update(hasher, format!("max-parameters {max_parameters:?}"));
+    for (kind, ids) in [("only", pass_selection.only()), ("skip", pass_selection.skip())] {
+        let mut ids = ids.iter().collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+        update(hasher, format!("{kind} {}", ids.len()));
+        for id in ids {
+            update(hasher, id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::instantiations::Instantiation;
+    use crate::pass_registry::PassSelection;
+
+    use super::*;
+
+    fn definition(source: &str, callees: &[&str]) -> DefinitionSummary {
+        DefinitionSummary {
+            file_id: 0,
+            file_name: "test.circom".to_string(),
+            location: 10..10 + source.len(),
+            source: source.to_string(),
+            callees: callees.iter().map(ToString::to_string).collect::<HashSet<_>>(),
+        }
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let config = AnalysisConfig::default();
+        let mut definitions = HashMap::new();
+        definitions.insert("A".to_string(), definition("() { B(); }", &["B"]));
+        definitions.insert("B".to_string(), definition("() { C(); }", &["C"]));
+        definitions.insert("C".to_string(), definition("() {}", &[]));
+        definitions.insert("D".to_string(), definition("() {}", &[]));
+        let key = |name, definitions: &HashMap<_, _>, curve| {
+            cache_key(name, definitions, &curve, &config, &[], None).unwrap()
+        };
+        let a = key("A", &definitions, Curve::Bn254);
+        let d = key("D", &definitions, Curve::Bn254);
+        assert_ne!(a, key("A", &definitions, Curve::Goldilocks));
+        assert!(cache_key("E", &definitions, &Curve::Bn254, &config, &[], None).is_none());
+        let custom_passes = ["custom".to_string()];
+        let custom_key = cache_key("A", &definitions, &Curve::Bn254, &config, &custom_passes, None);
+        assert_ne!(a, custom_key.unwrap());
+
+        // For templates reachable from the main component, the main component
+        // and its transitive callees are part of the key.
+        definitions.insert("Main".to_string(), definition("() { A(); D(); }", &["A", "D"]));
+        let context =
+            |level| InstantiationContext { main: Instantiation::new("Main", Vec::new()), level };
+        let context_key = |definitions: &HashMap<_, _>, level| {
+            cache_key("A", definitions, &Curve::Bn254, &config, &[], Some(&context(level))).unwrap()
+        };
+        let main_key = context_key(&definitions, 10);
+        assert_ne!(a, main_key);
+        assert_ne!(main_key, context_key(&definitions, 2));
+        definitions.insert("D".to_string(), definition("() { }", &[]));
+        assert_ne!(main_key, context_key(&definitions, 10));
+        definitions.insert("D".to_string(), definition("() {}", &[]));
+
+        // Configuration options affecting the results are part of the key, but
+        // severity overrides (which are applied after reading the cache) are not.
+        let config_key = |config: &AnalysisConfig| {
+            cache_key("A", &definitions, &Curve::Bn254, config, &[], None).unwrap()
+        };
+        let mut severity_overrides = HashMap::new();
+        severity_overrides.insert("CS0005".to_string(), MessageCategory::Error);
+        assert_eq!(a, config_key(&AnalysisConfig { severity_overrides, ..config.clone() }));
+        assert_ne!(a, config_key(&AnalysisConfig { max_parameters: Some(4), ..config.clone() }));
+        let pass_selection = PassSelection::new(&[], &["signal-assignment".to_string()]);
+        assert_ne!(a, config_key(&AnalysisConfig { pass_selection, ..config.clone() }));
+
+        // Changing a transitive callee changes the key.
+        definitions.insert("C".to_string(), definition("() { }", &[]));
+        assert_ne!(a, key("A", &definitions, Curve::Bn254));
+        assert_eq!(d, key("D", &definitions, Curve::Bn254));
+    }
+
+    /// The SHA-256 hash of the crate sources (except this file) at the current
+    /// `ANALYSIS_VERSION`.
+    const ANALYSIS_SOURCES_HASH: &str =
+        "d669f0b82099f1098f6867cd67c2042ab86f560bdd0eb54ccdb203bd83459ce2";
+
+    #[test]
+    fn test_analysis_version() {
+        // Any change to the crate sources may change the analysis results,
+        // which requires a new analysis version to invalidate cached results.
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut paths = fs::read_dir(&src)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.file_name().unwrap() != "result_cache.rs")
+            .collect::<Vec<_>>();
+        paths.sort();
+        let mut hasher = Sha256::new();
+        for path in paths {
+            update(&mut hasher, path.file_name().unwrap().to_string_lossy().as_bytes());
+            update(&mut hasher, fs::read_to_string(&path).unwrap().replace("\r\n", "\n"));
+        }
+        let hash = format!("{:x}", hasher.finalize());
+        assert_eq!(
+            (ANALYSIS_VERSION, hash.as_str()),
+            (1, ANALYSIS_SOURCES_HASH),
+            "The crate sources have changed. If the change may affect the analysis results, \
+             bump `config::ANALYSIS_VERSION`. Then update the expected version and hash."
+        );
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let dir = std::env::temp_dir().join(format!("circomspect-cache-{}", std::process::id()));
+        let cache = ResultCache::open(&dir).unwrap();
+        let previous = definition("() { signal input in; }", &[]);
+        let mut report = Report::warning("message".to_string(), ReportCode::UnusedVariableValue);
+        report.add_primary(15..30, 0, "primary".to_string());
+        report.add_secondary(12..14, 0, None);
+        report.add_note("note".to_string());
+        report.add_suggestion(16..18, 0, "<==".to_string(), "suggestion".to_string());
+        cache.insert("key", &previous, &[report]);
+
+        // The cached report is moved to the current location of the definition.
+        let mut current = definition("() { signal input in; }", &[]);
+        current.file_id = 1;
+        current.location = 20..20 + current.source.len();
+        let reports = cache.get("key", &current).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].id(), "CS0006");
+        assert_eq!(reports[0].primary()[0].range, 25..40);
+        assert_eq!(reports[0].primary()[0].file_id, 1);
+        assert_eq!(reports[0].secondary()[0].range, 22..24);
+        assert_eq!(reports[0].notes(), &vec!["note".to_string()]);
+        assert_eq!(reports[0].suggestions()[0].location(), &(26..28));
+        assert_eq!(reports[0].suggestions()[0].file_id(), 1);
+        assert_eq!(reports[0].suggestions()[0].replacement(), "<==");
+        assert!(cache.get("unknown", &current).is_none());
+
+        // Reports referring to other locations are not cached.
+        let mut report = Report::warning("message".to_string(), ReportCode::UnusedVariableValue);
+        report.add_primary(0..5, 0, "primary".to_string());
+        cache.insert("other", &previous, &[report]);
+        assert!(cache.get("other", &current).is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
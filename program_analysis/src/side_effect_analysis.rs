@@ -12,6 +12,7 @@ use program_structure::ir::{Expression, SignalType, Statement, VariableType};
 
 use crate::constraint_analysis::run_constraint_analysis;
 use crate::taint_analysis::run_taint_analysis;
+use crate::variable_access::{self, AccessSet, VariableAccess};
 
 pub struct UnusedVariableWarning {
     var: VariableUse,
@@ -281,26 +282,24 @@ pub fn run_side_effect_analysis(cfg: &Cfg) -> ReportCollection {
     // Compute the set of variables tainted by input and output signals.
     let exported_sinks = exported_signals
         .iter()
-        .flat_map(|source| taint_analysis.multi_step_taint(source))
+        .flat_map(|source| taint_analysis.multi_step_access_taint(&VariableAccess::from(source)))
         .collect::<HashSet<_>>();
-    // println!("exported sinks: {exported_sinks:?}");
 
     // Collect variables constraining input and output sinks.
     let mut sinks = exported_sinks
         .iter()
         .flat_map(|source| {
-            let mut result = constraint_analysis.multi_step_constraint(source);
+            let mut result = constraint_analysis.multi_step_access_constraint(source);
             // If the source is part of a constraint we include it in the result.
             if !result.is_empty() {
                 result.insert(source.clone());
             }
             result
         })
-        .collect::<HashSet<_>>();
+        .collect::<AccessSet>();
 
     // Add input and output signals to this set.
-    sinks.extend(exported_signals);
-    // println!("constraint sinks: {sinks:?}");
+    sinks.extend(exported_signals.iter().map(VariableAccess::from));
 
     // Add variables occurring in declarations, return values, asserts, and
     // control-flow conditions.
@@ -311,26 +310,27 @@ pub fn run_side_effect_analysis(cfg: &Cfg) -> ReportCollection {
                 Declaration { .. } | Return { .. } | Assert { .. } | IfThenElse { .. } => {
                     // If a variable used in a dimension expression is side-effect free,
                     // the declared variable must also be side-effect free.
-                    sinks.extend(stmt.variables_read().map(|var| var.name().clone()));
+                    sinks.extend(variable_access::variables_read(stmt));
                 }
                 _ => {}
             }
         }
     }
-    // println!("all sinks: {sinks:?}");
-    // println!("variables read: {variables_read:?}");
 
     let mut reports = ReportCollection::new();
     let mut reported_vars = HashSet::new();
 
-    // Generate a report for any variable that does not taint a sink.
+    // Generate a report for any variable that does not taint a sink. Since
+    // elements of signal and component arrays are defined separately, we only
+    // report the first definition of each variable.
     //
-    // TODO: The call to TaintAnalysis::taints_any chokes on CFGs containing
-    // large (65536 element) arrays.
-    for source in taint_analysis.definitions() {
+    // TODO: The call to TaintAnalysis::access_taints_any chokes on CFGs
+    // containing large (65536 element) arrays.
+    let mut reported_definitions = HashSet::new();
+    for (access, source) in taint_analysis.definitions() {
         // Circom 2.1.2 introduces `_` for ignored variables in tuple
         // assignments. We respect this convention here as well.
-        if source.to_string() == "_" {
+        if source.to_string() == "_" || reported_definitions.contains(source.name()) {
             continue;
         }
         if !variables_read.contains(source.name()) {
@@ -340,16 +340,18 @@ pub fn run_side_effect_analysis(cfg: &Cfg) -> ReportCollection {
             } else {
                 reports.push(build_unused_variable(source));
             }
-            reported_vars.insert(source.name().to_string());
-        } else if !taint_analysis.taints_any(source.name(), &sinks) {
+        } else if !taint_analysis.access_taints_any(access, &sinks) {
             // If the variable does not flow into any of the sinks, it is side-effect free.
             if cfg.parameters().contains(source.name()) {
                 reports.push(build_param_without_side_effect(source, cfg.definition_type()));
             } else {
                 reports.push(build_variable_without_side_effect(source, cfg.definition_type()));
             }
-            reported_vars.insert(source.name().to_string());
+        } else {
+            continue;
         }
+        reported_definitions.insert(source.name().clone());
+        reported_vars.insert(source.name().to_string());
     }
     // Generate reports for unused or unconstrained signals.
     //
@@ -540,6 +542,55 @@ mod tests {
         "#;
         validate_reports(src, 0);
 
+        // The elements of `c` are tracked separately, so the assignment to
+        // `c[1]` should be detected.
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                component c[2];
+
+                c[0] = C();
+                c[1] = C();
+                c[0].in <== in;
+                c[1].in <== 1;
+                out <== c[0].out;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        // The elements of `tmp` are tracked separately, so the assignment to
+        // `tmp[1]` should be detected.
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                signal tmp[2];
+
+                tmp[0] <== in * in;
+                tmp[1] <-- 2 * in;
+                out <== tmp[0];
+            }
+        "#;
+        validate_reports(src, 1);
+
+        // Component inputs flow to the outputs of the same component, so
+        // `tmp` should not be reported.
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                signal tmp;
+                component c[2];
+
+                tmp <-- 2 * in;
+                c[0] = C();
+                c[0].in <== tmp;
+                out <== c[0].out;
+            }
+        "#;
+        validate_reports(src, 0);
+
         // TODO: The assignment to `tmp` should be detected.
         let src = r#"
             template T() {
@@ -3,12 +3,15 @@ use program_structure::intermediate_representation::degree_meta::{DegreeRange, D
 use std::collections::HashSet;
 
 use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::file_definition::FileLocation;
 use program_structure::report_code::ReportCode;
 use program_structure::report::{Report, ReportCollection};
 use program_structure::ir::*;
 use program_structure::ir::AccessType;
 use program_structure::ir::variable_meta::VariableMeta;
 
+use crate::analysis_context::AnalysisContext;
+
 pub struct SignalAssignmentWarning {
     signal: VariableName,
     access: Vec<AccessType>,
@@ -64,6 +67,9 @@ pub struct UnecessarySignalAssignmentWarning {
     signal: VariableName,
     access: Vec<AccessType>,
     assignment_meta: Meta,
+    /// The location of the signal assignment operator together with the
+    /// corresponding constraint assignment operator (if known).
+    operator: Option<(FileLocation, &'static str)>,
 }
 
 impl UnecessarySignalAssignmentWarning {
@@ -89,6 +95,16 @@ impl UnecessarySignalAssignmentWarning {
             "Consider rewriting the statement using the constraint assignment operator `<==`."
                 .to_string(),
         );
+        if let (Some(file_id), Some((location, replacement))) =
+            (self.assignment_meta.file_id, self.operator)
+        {
+            report.add_suggestion(
+                location,
+                file_id,
+                replacement.to_string(),
+                format!("Replace the signal assignment operator with `{replacement}`."),
+            );
+        }
         report
     }
 }
@@ -205,7 +221,7 @@ impl SignalUse {
 /// The signal assignment operator `y <-- x` does not constrain the signal `y`.
 /// If the developer meant to use the constraint assignment operator `<==` this
 /// could lead to unexpected results.
-pub fn find_signal_assignments(cfg: &Cfg) -> ReportCollection {
+pub fn find_signal_assignments(context: &dyn AnalysisContext, cfg: &Cfg) -> ReportCollection {
     use DefinitionType::*;
     if matches!(cfg.definition_type(), Function | CustomTemplate) {
         // Exit early if this is a function or custom template.
@@ -225,6 +241,7 @@ pub fn find_signal_assignments(cfg: &Cfg) -> ReportCollection {
                 &assignment.signal,
                 &assignment.access,
                 &assignment.meta,
+                find_assignment_operator(context, &assignment.meta),
             ))
         } else {
             let constraint_metas =
@@ -270,15 +287,37 @@ fn visit_statement(stmt: &Statement, signal_use: &mut SignalUse) {
     }
 }
 
+/// Returns the location of the signal assignment operator (`<--` or `-->`) in
+/// the given assignment, together with the corresponding constraint
+/// assignment operator. Returns `None` if the operator cannot be uniquely
+/// identified in the source.
+fn find_assignment_operator(
+    context: &dyn AnalysisContext,
+    assignment_meta: &Meta,
+) -> Option<(FileLocation, &'static str)> {
+    let file_id = assignment_meta.file_id?;
+    let source = context.underlying_str(&file_id, &assignment_meta.location).ok()?;
+    for (operator, replacement) in [("<--", "<=="), ("-->", "==>")] {
+        let mut offsets = source.match_indices(operator).map(|(offset, _)| offset);
+        if let (Some(offset), None) = (offsets.next(), offsets.next()) {
+            let start = assignment_meta.location.start + offset;
+            return Some((start..start + operator.len(), replacement));
+        }
+    }
+    None
+}
+
 fn build_unecessary_assignment_report(
     signal: &VariableName,
     access: &[AccessType],
     assignment_meta: &Meta,
+    operator: Option<(FileLocation, &'static str)>,
 ) -> Report {
     UnecessarySignalAssignmentWarning {
         signal: signal.clone(),
         access: access.to_owned(),
         assignment_meta: assignment_meta.clone(),
+        operator,
     }
     .into_report()
 }
@@ -305,8 +344,9 @@ fn access_to_string(access: &[AccessType]) -> String {
 
 #[cfg(test)]
 mod tests {
-    use parser::parse_definition;
-    use program_structure::{cfg::IntoCfg, constants::Curve};
+    use program_structure::constants::Curve;
+
+    use crate::analysis_runner::AnalysisRunner;
 
     use super::*;
 
@@ -355,24 +395,42 @@ mod tests {
         validate_reports(src, 1);
     }
 
-    fn validate_reports(src: &str, expected_len: usize) {
+    #[test]
+    fn test_signal_assignment_suggestions() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out[2];
+
+                out[0] <-- in * in;
+                in * 2 --> out[1];
+            }
+        "#;
+        let reports = validate_reports(src, 2);
+        let mut fixed = src.to_string();
+        let mut suggestions =
+            reports.iter().flat_map(|report| report.suggestions()).collect::<Vec<_>>();
+        suggestions.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.location().start));
+        for suggestion in suggestions {
+            fixed.replace_range(suggestion.location().clone(), suggestion.replacement());
+        }
+        assert!(fixed.contains("out[0] <== in * in;"));
+        assert!(fixed.contains("in * 2 ==> out[1];"));
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) -> ReportCollection {
         // Build CFG.
         println!("{}", src);
-        let mut reports = ReportCollection::new();
-        let cfg = parse_definition(src)
-            .unwrap()
-            .into_cfg(&Curve::default(), &mut reports)
-            .unwrap()
-            .into_ssa()
-            .unwrap();
-        assert!(reports.is_empty());
+        let context = AnalysisRunner::new(Curve::default()).with_src(&[src]);
+        let cfg = context.template("T").unwrap();
 
         // Generate report collection.
-        let reports = find_signal_assignments(&cfg);
+        let reports = find_signal_assignments(&context, cfg);
         for report in &reports {
             println!("{}", report.message())
         }
 
         assert_eq!(reports.len(), expected_len);
+        reports
     }
 }
@@ -8,11 +8,19 @@ use program_structure::cfg::Cfg;
 use program_structure::ir::variable_meta::{VariableMeta, VariableUse};
 use program_structure::ir::{Expression, Statement, VariableName};
 
+use crate::variable_access::{self, AccessSet, VariableAccess};
+
+/// This analysis computes the taint relation (that is, the data flow between
+/// variables). Signal and component arrays are tracked element by element, so
+/// that `c[0].in` and `c[1].in` are considered separately.
 #[derive(Clone, Default)]
 pub struct TaintAnalysis {
-    taint_map: HashMap<VariableName, HashSet<VariableName>>,
+    taint_map: HashMap<VariableAccess, HashSet<VariableAccess>>,
+    // The accesses tainting some other variable.
+    sources: AccessSet,
     declarations: HashMap<VariableName, VariableUse>,
-    definitions: HashMap<VariableName, VariableUse>,
+    definitions: Vec<(VariableAccess, VariableUse)>,
+    definition_indices: HashMap<VariableAccess, usize>,
 }
 
 impl TaintAnalysis {
@@ -23,34 +31,34 @@ impl TaintAnalysis {
         for name in parameters.iter() {
             trace!("adding parameter declaration for `{name:?}`");
             let definition = VariableUse::new(&meta, name, &Vec::new());
-            result.add_definition(&definition);
+            result.add_definition(&VariableAccess::from(name), &definition);
         }
         result
     }
 
     /// Add the variable use corresponding to the definition of the variable.
-    fn add_definition(&mut self, var: &VariableUse) {
-        // TODO: Since we don't version components and signals, we may end up
-        // overwriting component initializations here. For example, in the
-        // following case the component initialization will be clobbered.
-        //
-        //   component c[2];
-        //   ...
-        //   c[0].in[0] <== 0;
-        //   c[1].in[1] <== 1;
-        //
-        // As long as the initialized component flows to a constraint it will
-        // not be flagged during side-effect analysis.
-        self.definitions.insert(var.name().clone(), var.clone());
+    /// Definitions of distinct elements of signal and component arrays are
+    /// tracked separately.
+    fn add_definition(&mut self, access: &VariableAccess, var: &VariableUse) {
+        match self.definition_indices.get(access) {
+            Some(index) => self.definitions[*index].1 = var.clone(),
+            None => {
+                self.definition_indices.insert(access.clone(), self.definitions.len());
+                self.definitions.push((access.clone(), var.clone()));
+            }
+        }
     }
 
     /// Get the variable use corresponding to the definition of the variable.
-    pub fn get_definition(&self, var: &VariableName) -> Option<VariableUse> {
-        self.definitions.get(var).cloned()
+    pub fn get_definition(&self, var: &VariableAccess) -> Option<VariableUse> {
+        let index = self.definition_indices.get(var)?;
+        self.definitions.get(*index).map(|(_, var)| var.clone())
     }
 
-    pub fn definitions(&self) -> impl Iterator<Item = &VariableUse> {
-        self.definitions.values()
+    /// Returns all definitions together with the defined accesses, in the
+    /// order in which they occur in the CFG.
+    pub fn definitions(&self) -> impl Iterator<Item = (&VariableAccess, &VariableUse)> {
+        self.definitions.iter().map(|(access, var)| (access, var))
     }
 
     /// Add the variable use corresponding to the declaration of the variable.
@@ -68,30 +76,65 @@ impl TaintAnalysis {
     }
 
     /// Add a single step taint from source to sink.
-    fn add_taint_step(&mut self, source: &VariableName, sink: &VariableName) {
+    fn add_taint_step(&mut self, source: &VariableAccess, sink: &VariableAccess) {
+        trace!("adding taint step with source `{source}` and sink `{sink}`");
         let sinks = self.taint_map.entry(source.clone()).or_default();
         sinks.insert(sink.clone());
+        self.sources.insert(source.clone());
     }
 
-    /// Returns variables tainted in a single step by `source`.
-    pub fn single_step_taint(&self, source: &VariableName) -> HashSet<VariableName> {
-        self.taint_map.get(source).cloned().unwrap_or_default()
+    /// Returns accesses tainted in a single step by `source`. Since accesses
+    /// may alias, this includes the accesses tainted by any access which may
+    /// alias `source`.
+    pub fn single_step_access_taint(&self, source: &VariableAccess) -> HashSet<VariableAccess> {
+        self.sources
+            .aliases(source)
+            .flat_map(|other| self.taint_map[other].iter().cloned())
+            .collect()
     }
 
-    /// Returns variables tainted in zero or more steps by `source`.
-    pub fn multi_step_taint(&self, source: &VariableName) -> HashSet<VariableName> {
+    /// Returns accesses tainted in zero or more steps by `source`.
+    pub fn multi_step_access_taint(&self, source: &VariableAccess) -> HashSet<VariableAccess> {
         let mut result = HashSet::new();
         let mut update = HashSet::from([source.clone()]);
         while !update.is_subset(&result) {
             result.extend(update.iter().cloned());
-            update = update.iter().flat_map(|source| self.single_step_taint(source)).collect();
+            update = update
+                .iter()
+                .flat_map(|source| self.single_step_access_taint(source))
+                .filter(|sink| !result.contains(sink))
+                .collect();
         }
         result
     }
 
+    /// Returns true if the source taints an access which may alias any of the
+    /// sinks.
+    pub fn access_taints_any(&self, source: &VariableAccess, sinks: &AccessSet) -> bool {
+        self.multi_step_access_taint(source).iter().any(|var| sinks.may_contain(var))
+    }
+
+    /// Returns variables tainted in a single step by `source`.
+    pub fn single_step_taint(&self, source: &VariableName) -> HashSet<VariableName> {
+        self.single_step_access_taint(&VariableAccess::from(source))
+            .iter()
+            .map(|sink| sink.name().clone())
+            .collect()
+    }
+
+    /// Returns variables tainted in zero or more steps by `source`.
+    pub fn multi_step_taint(&self, source: &VariableName) -> HashSet<VariableName> {
+        self.multi_step_access_taint(&VariableAccess::from(source))
+            .iter()
+            .map(|sink| sink.name().clone())
+            .collect()
+    }
+
     /// Returns true if the source taints any of the sinks.
     pub fn taints_any(&self, source: &VariableName, sinks: &HashSet<VariableName>) -> bool {
-        self.multi_step_taint(source).iter().any(|sink| sinks.contains(sink))
+        self.multi_step_access_taint(&VariableAccess::from(source))
+            .iter()
+            .any(|sink| sinks.contains(sink.name()))
     }
 }
 
@@ -107,20 +150,16 @@ pub fn run_taint_analysis(cfg: &Cfg) -> TaintAnalysis {
             match stmt {
                 Substitution { .. } => {
                     // Variables read taint variables written by the statement.
-                    for sink in stmt.variables_written() {
+                    let variables_read = variable_access::variables_read(stmt);
+                    for (sink, definition) in variable_access::definitions(stmt) {
                         if !matches!(stmt, Substitution { rhe: Phi { .. }, .. }) {
                             // Add the definition to the result.
-                            trace!("adding variable assignment for `{:?}`", sink.name());
-                            result.add_definition(sink);
+                            trace!("adding variable assignment for `{sink}`");
+                            result.add_definition(&sink, definition);
                         }
-                        for source in stmt.variables_read() {
+                        for source in &variables_read {
                             // Add each taint step to the result.
-                            trace!(
-                                "adding taint step with source `{:?}` and sink `{:?}`",
-                                source.name(),
-                                sink.name()
-                            );
-                            result.add_taint_step(source.name(), sink.name());
+                            result.add_taint_step(source, &sink);
                         }
                     }
                 }
@@ -130,7 +169,10 @@ pub fn run_taint_analysis(cfg: &Cfg) -> TaintAnalysis {
                         result.add_declaration(&VariableUse::new(meta, sink, &Vec::new()));
                         for size in dimensions {
                             for source in size.variables_read() {
-                                result.add_taint_step(source.name(), sink)
+                                result.add_taint_step(
+                                    &VariableAccess::from(source),
+                                    &VariableAccess::from(sink),
+                                )
                             }
                         }
                     }
@@ -145,15 +187,10 @@ pub fn run_taint_analysis(cfg: &Cfg) -> TaintAnalysis {
                     let false_branch = cfg.get_false_branch(basic_block);
                     for body in true_branch.iter().chain(false_branch.iter()) {
                         // Add taint for assigned variables.
-                        for sink in body.variables_written() {
+                        for sink in body.iter().flat_map(variable_access::variables_written) {
                             for source in cond.variables_read() {
                                 // Add each taint step to the result.
-                                trace!(
-                                    "adding taint step with source `{:?}` and sink `{:?}`",
-                                    source.name(),
-                                    sink.name()
-                                );
-                                result.add_taint_step(source.name(), sink.name());
+                                result.add_taint_step(&VariableAccess::from(source), &sink);
                             }
                         }
                     }
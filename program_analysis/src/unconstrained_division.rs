@@ -77,7 +77,7 @@ impl Component {
         match value {
             Some(FieldElement { value }) => Some(!value.is_zero()),
             Some(Boolean { value }) => Some(*value),
-            None => None,
+            Some(Array { .. }) | None => None,
         }
     }
 }
@@ -1,18 +1,17 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt;
 
 use log::{debug, trace};
 
-use num_bigint::BigInt;
 use program_structure::cfg::Cfg;
-use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::range_meta::{RangeMeta, ValueRange};
 use program_structure::report_code::ReportCode;
 use program_structure::report::{Report, ReportCollection};
 use program_structure::ir::*;
 
 pub struct UnconstrainedLessThanWarning {
     value: Expression,
-    bit_sizes: Vec<(Meta, Expression)>,
+    range: Option<ValueRange>,
 }
 impl UnconstrainedLessThanWarning {
     fn primary_meta(&self) -> &Meta {
@@ -31,13 +30,9 @@ impl UnconstrainedLessThanWarning {
                 file_id,
                 format!("`{}` needs to be constrained to ensure that it is <= p/2.", self.value),
             );
-            for (meta, size) in self.bit_sizes {
-                report.add_secondary(
-                    meta.file_location(),
-                    file_id,
-                    Some(format!("`{}` is constrained to `{}` bits here.", self.value, size)),
-                );
-            }
+        }
+        if let Some(range) = self.range.filter(|range| range.is_bounded()) {
+            report.add_note(format!("`{}` is only known to be in the range {range}.", self.value));
         }
         report
     }
@@ -56,51 +51,6 @@ impl VariableAccess {
     }
 }
 
-/// Tracks component instantiations `var = T(...)` where then template `T` is
-/// either `LessThan` or `Num2Bits`.
-enum Component {
-    LessThan,
-    Num2Bits { bit_size: Box<Expression> },
-}
-
-impl Component {
-    fn less_than() -> Self {
-        Self::LessThan
-    }
-
-    fn num_2_bits(bit_size: &Expression) -> Self {
-        Self::Num2Bits { bit_size: Box::new(bit_size.clone()) }
-    }
-}
-
-/// Tracks component input signal initializations on the form `T.in <== input`
-/// where `T` is either `LessThan` or `Num2Bits`.
-enum ComponentInput {
-    LessThan { value: Box<Expression> },
-    Num2Bits { value: Box<Expression>, bit_size: Box<Expression> },
-}
-
-impl ComponentInput {
-    fn less_than(value: &Expression) -> Self {
-        Self::LessThan { value: Box::new(value.clone()) }
-    }
-
-    fn num_2_bits(value: &Expression, bit_size: &Expression) -> Self {
-        Self::Num2Bits { value: Box::new(value.clone()), bit_size: Box::new(bit_size.clone()) }
-    }
-}
-
-/// Tracks constraints for a single input to `LessThan`.
-#[derive(Default)]
-struct ConstraintData {
-    /// Input to `LessThan`.
-    pub less_than: Vec<Meta>,
-    /// Input to `Num2Bits`.
-    pub num_2_bits: Vec<Meta>,
-    /// Size constraints enforced by `Num2Bits`.
-    pub bit_sizes: Vec<Expression>,
-}
-
 /// The `LessThan` template from Circomlib does not constrain the individual
 /// inputs to the input size `n` bits, or to be positive. If the inputs are
 /// allowed to be greater than p/2 it is possible to find field elements `a` and
@@ -113,10 +63,12 @@ struct ConstraintData {
 ///   5. lt.out = 1
 ///
 /// This analysis pass looks for instantiations of `LessThan` where the inputs
-/// are not constrained to be <= p/2 using `Num2Bits`.
+/// are not known to be in the range [0, 2^(k - 2)), where `k` is the size of
+/// the prime, using the value ranges inferred for the inputs. (Inputs are
+/// typically constrained to this range using `Num2Bits`.)
 pub fn find_unconstrained_less_than(cfg: &Cfg) -> ReportCollection {
     debug!("running unconstrained less-than analysis pass");
-    let mut components = HashMap::new();
+    let mut components = HashSet::new();
     for basic_block in cfg.iter() {
         for stmt in basic_block.iter() {
             update_components(stmt, &mut components);
@@ -128,51 +80,28 @@ pub fn find_unconstrained_less_than(cfg: &Cfg) -> ReportCollection {
             update_inputs(stmt, &components, &mut inputs);
         }
     }
-    let mut constraints = HashMap::<Expression, ConstraintData>::new();
-    for input in inputs {
-        match input {
-            ComponentInput::LessThan { value } => {
-                let entry = constraints.entry(*value.clone()).or_default();
-                entry.less_than.push(value.meta().clone());
-            }
-            ComponentInput::Num2Bits { value, bit_size, .. } => {
-                let entry = constraints.entry(*value.clone()).or_default();
-                entry.num_2_bits.push(value.meta().clone());
-                entry.bit_sizes.push(*bit_size.clone());
-            }
-        }
-    }
 
-    // Generate a report for each input to `LessThan` where the input size is
-    // not constrained to be positive using `Num2Bits`.
+    // Generate a report for each input to `LessThan` which is not known to be
+    // non-negative and less than 2^(k - 2).
     let mut reports = ReportCollection::new();
-    let max_value = BigInt::from(cfg.constants().prime_size() - 1);
-    for (value, data) in constraints {
-        // Check if the the value is used as input for `LessThan`.
-        if data.less_than.is_empty() {
+    let max_size = cfg.constants().prime_size() - 2;
+    let mut visited = HashSet::new();
+    for value in inputs {
+        if !visited.insert(value.clone()) {
             continue;
         }
-        // Check if the value is constrained to be positive.
-        let mut is_positive = false;
-        for bit_size in &data.bit_sizes {
-            if let Some(ValueReduction::FieldElement { value }) = bit_size.value() {
-                if value < &max_value {
-                    is_positive = true;
-                    break;
-                }
-            }
-        }
-        if is_positive {
+        let range = value.range();
+        if matches!(range.and_then(ValueRange::bit_width), Some(size) if size <= max_size) {
             continue;
         }
         // We failed to prove that the input is positive. Generate a report.
-        reports.push(build_report(&value, &data));
+        reports.push(build_report(&value, range));
     }
     debug!("{} new reports generated", reports.len());
     reports
 }
 
-fn update_components(stmt: &Statement, components: &mut HashMap<VariableAccess, Component>) {
+fn update_components(stmt: &Statement, components: &mut HashSet<VariableAccess>) {
     use AssignOp::*;
     use Statement::*;
     use Expression::*;
@@ -194,16 +123,7 @@ fn update_components(stmt: &Statement, components: &mut HashMap<VariableAccess,
                     "`LessThan` template instantiation `{var}{}` found",
                     vec_to_display(&access, "")
                 );
-                let component = VariableAccess::new(var, &access);
-                components.insert(component, Component::less_than());
-            } else if component_name == "Num2Bits" && args.len() == 1 {
-                // We assume this is the `Num2Bits` circuit from Circomlib.
-                trace!(
-                    "`LessThan` template instantiation `{var}{}` found",
-                    vec_to_display(&access, "")
-                );
-                let component = VariableAccess::new(var, &access);
-                components.insert(component, Component::num_2_bits(&args[0]));
+                components.insert(VariableAccess::new(var, &access));
             }
         }
     }
@@ -211,8 +131,8 @@ fn update_components(stmt: &Statement, components: &mut HashMap<VariableAccess,
 
 fn update_inputs(
     stmt: &Statement,
-    components: &HashMap<VariableAccess, Component>,
-    inputs: &mut Vec<ComponentInput>,
+    components: &HashSet<VariableAccess>,
+    inputs: &mut Vec<Expression>,
 ) {
     use AssignOp::*;
     use Statement::*;
@@ -222,22 +142,6 @@ fn update_inputs(
         var, op: AssignConstraintSignal, rhe: Update { access, rhe, .. }, ..
     } = stmt
     {
-        // If this is a `Num2Bits` input signal assignment, the input signal
-        // access would be the last element of the `access` vector.
-        let mut component_access = access.clone();
-        let signal_access = component_access.pop();
-        let component = VariableAccess::new(var, &component_access);
-        if let Some(Component::Num2Bits { bit_size, .. }) = components.get(&component) {
-            let Some(ComponentAccess(signal_name)) = signal_access else {
-                return;
-            };
-            if signal_name != "in" {
-                return;
-            }
-            trace!("`Num2Bits` input signal assignment `{rhe}` found");
-            inputs.push(ComponentInput::num_2_bits(rhe, bit_size));
-        }
-
         // If this is a `LessThan` input signal assignment, the input index
         // access would be the last element, and the input signal access
         // would be the next to last element of the `access` vector.
@@ -245,7 +149,7 @@ fn update_inputs(
         let index_access = component_access.pop();
         let signal_access = component_access.pop();
         let component = VariableAccess::new(var, &component_access);
-        if let Some(Component::LessThan { .. }) = components.get(&component) {
+        if components.contains(&component) {
             let (Some(ComponentAccess(signal_name)), Some(ArrayAccess(_))) =
                 (signal_access, index_access)
             else {
@@ -255,18 +159,14 @@ fn update_inputs(
                 return;
             }
             trace!("`LessThan` input signal assignment `{rhe}` found");
-            inputs.push(ComponentInput::less_than(rhe));
+            inputs.push(*rhe.clone());
         }
     }
 }
 
 #[must_use]
-fn build_report(value: &Expression, data: &ConstraintData) -> Report {
-    UnconstrainedLessThanWarning {
-        value: value.clone(),
-        bit_sizes: data.num_2_bits.iter().cloned().zip(data.bit_sizes.iter().cloned()).collect(),
-    }
-    .into_report()
+fn build_report(value: &Expression, range: Option<&ValueRange>) -> Report {
+    UnconstrainedLessThanWarning { value: value.clone(), range: range.cloned() }.into_report()
 }
 
 #[must_use]
@@ -367,6 +267,26 @@ mod tests {
             }
         "#;
         validate_reports(src, 0);
+
+        let src = r#"
+            template Test() {
+              signal input small;
+              signal input large;
+              signal output ok;
+
+              // Constrain inputs to be boolean.
+              small * (small - 1) === 0;
+              large * (1 - large) === 0;
+
+              // Check that small < large.
+              component lt = LessThan(1);
+              lt.in[0] <== small;
+              lt.in[1] <== large + 1;
+
+              ok <== lt.out;
+            }
+        "#;
+        validate_reports(src, 0);
     }
 
     fn validate_reports(src: &str, expected_len: usize) {
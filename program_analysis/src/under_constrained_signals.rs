@@ -1,16 +1,13 @@
-use std::collections::{HashMap, HashSet};
-
 use log::debug;
 use program_structure::cfg::Cfg;
 use program_structure::file_definition::{FileID, FileLocation};
-use program_structure::intermediate_representation::variable_meta::VariableMeta;
 use program_structure::report::{ReportCollection, Report};
 use program_structure::ir::*;
 use program_structure::report_code::ReportCode;
 
-use crate::taint_analysis::{run_taint_analysis, TaintAnalysis};
-
-const MIN_CONSTRAINT_COUNT: usize = 2;
+use crate::config::AnalysisConfig;
+use crate::taint_analysis::run_taint_analysis;
+use crate::variable_access::{self, AccessSet, VariableAccess};
 
 #[derive(PartialEq, Eq, Hash)]
 enum ConstraintLocation {
@@ -28,11 +25,27 @@ impl ConstraintLocation {
     }
 }
 
-type ConstraintLocations = HashMap<VariableName, Vec<ConstraintLocation>>;
+/// An intermediate signal. If the dimensions of the signal are known, each
+/// element is tracked separately.
+struct IntermediateSignal {
+    name: VariableName,
+    expanded: bool,
+    elements: Vec<TrackedElement>,
+}
+
+struct TrackedElement {
+    access: VariableAccess,
+    // The set of accesses tainted by the element.
+    tainted: AccessSet,
+    locations: Vec<ConstraintLocation>,
+}
 
 pub struct UnderConstrainedSignalWarning {
     name: VariableName,
     dimensions: Vec<Expression>,
+    // The under-constrained elements, if only some elements of the signal
+    // array are under-constrained.
+    elements: Vec<String>,
     file_id: Option<FileID>,
     primary_location: FileLocation,
     secondary_location: Option<FileLocation>,
@@ -80,86 +93,111 @@ impl UnderConstrainedSignalWarning {
                 }
             }
         }
+        if !self.elements.is_empty() {
+            report.add_note(format!(
+                "The following signals in `{}` occur in too few constraints: {}.",
+                self.name,
+                elements_to_string(&self.elements)
+            ));
+        }
         report
     }
 }
 
 // Intermediate signals should occur in at least two separate constraints. One
 // to define the value of the signal and one to constrain an input or output
-// signal.
-pub fn find_under_constrained_signals(cfg: &Cfg) -> ReportCollection {
+// signal. (The minimum number of constraints is configurable.) If the
+// dimensions of an intermediate signal array are known, this is checked for
+// each element of the array.
+pub fn find_under_constrained_signals(config: &AnalysisConfig, cfg: &Cfg) -> ReportCollection {
     debug!("running under-constrained signals analysis pass");
 
     // Run taint analysis to be able to track data flow.
     let taint_analysis = run_taint_analysis(cfg);
 
     // Compute the set of intermediate signals.
-    let mut constraint_locations = cfg
+    let mut signals = cfg
         .variables()
-        .filter_map(|name| {
-            if matches!(cfg.get_type(name), Some(VariableType::Signal(SignalType::Intermediate, _)))
-            {
-                Some((name.clone(), Vec::new()))
-            } else {
-                None
-            }
+        .filter(|name| {
+            matches!(cfg.get_type(name), Some(VariableType::Signal(SignalType::Intermediate, _)))
+        })
+        .map(|name| {
+            let access = VariableAccess::from(name);
+            let elements = variable_access::declared_dimensions(cfg, name)
+                .and_then(|dimensions| access.elements(&dimensions));
+            let expanded = elements.is_some();
+            let elements = elements
+                .unwrap_or_else(|| vec![access])
+                .into_iter()
+                .map(|access| TrackedElement {
+                    tainted: taint_analysis.multi_step_access_taint(&access).into_iter().collect(),
+                    access,
+                    locations: Vec::new(),
+                })
+                .collect();
+            IntermediateSignal { name: name.clone(), expanded, elements }
         })
-        .collect::<ConstraintLocations>();
+        .collect::<Vec<_>>();
 
     // Iterate through the CFG to identify intermediate signal constraints.
     for basic_block in cfg.iter() {
         for stmt in basic_block.iter() {
-            visit_statement(
-                stmt,
-                basic_block.in_loop(),
-                &taint_analysis,
-                &mut constraint_locations,
-            );
+            visit_statement(stmt, basic_block.in_loop(), &mut signals);
         }
     }
 
     // Generate reports.
     let mut reports = ReportCollection::new();
-    for (signal, locations) in constraint_locations {
-        if locations.len() < MIN_CONSTRAINT_COUNT && !locations.contains(&ConstraintLocation::Loop)
-        {
-            let secondary_location =
-                locations.first().and_then(|location| location.file_location());
-            if let Some(declaration) = cfg.get_declaration(&signal) {
-                reports.push(build_report(
-                    &signal,
-                    declaration.dimensions(),
-                    declaration.file_id(),
-                    declaration.file_location(),
-                    secondary_location,
-                ))
-            }
+    for signal in signals {
+        let total = signal.elements.len();
+        let under_constrained = signal
+            .elements
+            .into_iter()
+            .filter(|element| {
+                element.locations.len() < config.min_constraint_count
+                    && !element.locations.contains(&ConstraintLocation::Loop)
+            })
+            .collect::<Vec<_>>();
+        let Some(first) = under_constrained.first() else {
+            continue;
+        };
+        let secondary_location =
+            first.locations.first().and_then(|location| location.file_location());
+        let elements = if signal.expanded && under_constrained.len() < total {
+            under_constrained.iter().map(|element| element.access.to_string()).collect()
+        } else {
+            Vec::new()
+        };
+        if let Some(declaration) = cfg.get_declaration(&signal.name) {
+            reports.push(build_report(
+                &signal.name,
+                declaration.dimensions(),
+                elements,
+                declaration.file_id(),
+                declaration.file_location(),
+                secondary_location,
+            ))
         }
     }
     debug!("{} new reports generated", reports.len());
     reports
 }
 
-fn visit_statement(
-    stmt: &Statement,
-    in_loop: bool,
-    taint_analysis: &TaintAnalysis,
-    constraint_counts: &mut ConstraintLocations,
-) {
+fn visit_statement(stmt: &Statement, in_loop: bool, signals: &mut [IntermediateSignal]) {
     use AssignOp::*;
     use Statement::*;
     match stmt {
-        // Update the constraint count for each intermediate signal. If the
-        // statement occurs in a loop, we consider the minimum count to be
+        // Update the constraint count for each intermediate signal element. If
+        // the statement occurs in a loop, we consider the minimum count to be
         // reached immediately.
         Substitution { meta, op: AssignConstraintSignal, .. } | ConstraintEquality { meta, .. } => {
-            let sinks = stmt.variables_used().map(|var| var.name().clone()).collect::<HashSet<_>>();
-            for (source, locations) in constraint_counts.iter_mut() {
-                if taint_analysis.taints_any(source, &sinks) {
+            let sinks = variable_access::variables_used(stmt);
+            for element in signals.iter_mut().flat_map(|signal| signal.elements.iter_mut()) {
+                if sinks.iter().any(|sink| element.tainted.may_contain(sink)) {
                     if in_loop {
-                        locations.push(ConstraintLocation::Loop);
+                        element.locations.push(ConstraintLocation::Loop);
                     } else {
-                        locations.push(ConstraintLocation::Ordinary(meta.file_location()))
+                        element.locations.push(ConstraintLocation::Ordinary(meta.file_location()))
                     }
                 }
             }
@@ -168,9 +206,26 @@ fn visit_statement(
     }
 }
 
+/// The maximum number of elements listed in a report.
+const MAX_LISTED_ELEMENTS: usize = 8;
+
+fn elements_to_string(elements: &[String]) -> String {
+    let mut result = elements
+        .iter()
+        .take(MAX_LISTED_ELEMENTS)
+        .map(|element| format!("`{element}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if elements.len() > MAX_LISTED_ELEMENTS {
+        result.push_str(&format!(", and {} more", elements.len() - MAX_LISTED_ELEMENTS));
+    }
+    result
+}
+
 fn build_report(
     signal: &VariableName,
     dimensions: &[Expression],
+    elements: Vec<String>,
     file_id: Option<FileID>,
     primary_location: FileLocation,
     secondary_location: Option<FileLocation>,
@@ -178,6 +233,7 @@ fn build_report(
     UnderConstrainedSignalWarning {
         name: signal.clone(),
         dimensions: dimensions.to_vec(),
+        elements,
         file_id,
         primary_location,
         secondary_location,
@@ -255,9 +311,26 @@ mod tests {
             }
         "#;
         validate_reports(src, 0);
+
+        let src = r#"
+            template Test(n) {
+              signal input a;
+              signal b[2];
+              signal output c;
+
+              b[0] <== a * a;
+              b[1] <== a * a;
+              c <== a * b[0];
+            }
+        "#;
+        let reports = validate_reports(src, 1);
+        assert_eq!(
+            reports[0].notes(),
+            &vec!["The following signals in `b` occur in too few constraints: `b[1]`.".to_string()]
+        );
     }
 
-    fn validate_reports(src: &str, expected_len: usize) {
+    fn validate_reports(src: &str, expected_len: usize) -> ReportCollection {
         // Build CFG.
         let mut reports = ReportCollection::new();
         let cfg = parse_definition(src)
@@ -269,7 +342,8 @@ mod tests {
         assert!(reports.is_empty());
 
         // Generate report collection.
-        let reports = find_under_constrained_signals(&cfg);
+        let reports = find_under_constrained_signals(&AnalysisConfig::default(), &cfg);
         assert_eq!(reports.len(), expected_len);
+        reports
     }
 }
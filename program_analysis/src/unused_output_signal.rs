@@ -1,9 +1,11 @@
 use log::debug;
+use num_traits::ToPrimitive;
 use std::collections::HashSet;
 
 use program_structure::{
     ir::*,
-    ir::value_meta::ValueMeta,
+    ir::range_meta::RangeMeta,
+    ir::value_meta::{ValueMeta, ValueReduction},
     report_code::ReportCode,
     cfg::{Cfg, DefinitionType},
     report::{Report, ReportCollection},
@@ -11,10 +13,7 @@ use program_structure::{
 };
 
 use crate::analysis_context::AnalysisContext;
-
-// Known templates that are commonly instantiated without accessing the
-// corresponding output signals.
-const ALLOW_LIST: [&str; 1] = ["Num2Bits"];
+use crate::variable_access::{self, AccessElement, AccessSet, VariableAccess, MAX_ELEMENTS};
 
 struct UnusedOutputSignalWarning {
     // Location of template instantiation.
@@ -24,8 +23,11 @@ struct UnusedOutputSignalWarning {
     current_template: String,
     // The instantiated template with an unused output signal.
     component_template: String,
-    // The name of the unused output signal.
+    // The name of the unused output signal (or signal array element).
     signal_name: String,
+    // The component array element, if the signal is only unused for some
+    // elements of the component array.
+    component_element: Option<String>,
 }
 
 impl UnusedOutputSignalWarning {
@@ -44,88 +46,12 @@ impl UnusedOutputSignalWarning {
                 format!("The template `{}` is instantiated here.", self.component_template),
             );
         }
-        report
-    }
-}
-
-#[derive(Clone, Debug)]
-struct VariableAccess {
-    pub var: VariableName,
-    pub access: Vec<AccessType>,
-}
-
-impl VariableAccess {
-    fn new(var: &VariableName, access: &[AccessType]) -> Self {
-        // We disregard the version to make sure accesses are not order dependent.
-        VariableAccess { var: var.without_version(), access: access.to_vec() }
-    }
-}
-
-/// A reflexive and symmetric relation capturing partial information about
-/// equality.
-trait MaybeEqual {
-    fn maybe_equal(&self, other: &Self) -> bool;
-}
-
-/// This is a reflexive and symmetric (but not transitive!) relation
-/// identifying all array accesses where the indices are not explicitly known
-/// to be different (e.g. from constant propagation). The relation is not
-/// transitive since `v[0] == v[i]` and `v[i] == v[1]`, but `v[0] != v[1]`.
-///
-/// Since `maybe_equal` is not transitive we cannot use it to define
-/// `PartialEq` for `VariableAccess`. This also means that we cannot use hash
-/// sets or hash maps to track variable accesses using this as our equality
-/// relation.
-impl MaybeEqual for VariableAccess {
-    fn maybe_equal(&self, other: &VariableAccess) -> bool {
-        use AccessType::*;
-        if self.var.name() != other.var.name() {
-            return false;
-        }
-        if self.access.len() != other.access.len() {
-            return false;
-        }
-        for (self_access, other_access) in self.access.iter().zip(other.access.iter()) {
-            match (self_access, other_access) {
-                (ArrayAccess(_), ComponentAccess(_)) => {
-                    return false;
-                }
-                (ComponentAccess(_), ArrayAccess(_)) => {
-                    return false;
-                }
-                (ComponentAccess(self_name), ComponentAccess(other_name))
-                    if self_name != other_name =>
-                {
-                    return false;
-                }
-                (ArrayAccess(self_index), ArrayAccess(other_index)) => {
-                    match (self_index.value(), other_index.value()) {
-                        (Some(self_value), Some(other_value)) if self_value != other_value => {
-                            return false;
-                        }
-                        // Identify all other array accesses.
-                        _ => {}
-                    }
-                }
-                // Identify all array accesses.
-                _ => {}
-            }
+        if let Some(component_element) = self.component_element {
+            report.add_note(format!(
+                "The signal is not constrained for the component `{component_element}`."
+            ));
         }
-        true
-    }
-}
-
-/// A relation capturing partial information about containment.
-trait MaybeContains<T> {
-    fn maybe_contains(&self, element: &T) -> bool;
-}
-
-impl<T> MaybeContains<T> for Vec<T>
-where
-    T: MaybeEqual,
-{
-    fn maybe_contains(&self, element: &T) -> bool {
-        self.iter().any(|item| item.maybe_equal(element))
+        report
     }
 }
 
@@ -150,33 +76,57 @@ impl ComponentData {
             template_name: template_name.to_string(),
         }
     }
-}
 
-struct SignalData {
-    pub meta: Meta,
-    pub template_name: String,
-    pub signal_name: String,
-    pub signal_access: VariableAccess,
+    /// Returns the component array elements which may be instantiated by the
+    /// statement. If the component is instantiated in a loop (as `c[i]`), and
+    /// both the range of `i` and the dimensions of `c` are known, this is
+    /// each element of `c` in the range of `i`. Otherwise, this is the single
+    /// access `c[i]`.
+    pub fn elements(&self, cfg: &Cfg) -> Vec<VariableAccess> {
+        // We disregard the version to make sure accesses are not order dependent.
+        let var_name = self.var_name.without_version();
+        let access = VariableAccess::new(&var_name, &self.var_access);
+        let Some(dimensions) = variable_access::declared_dimensions(cfg, &self.var_name) else {
+            return vec![access];
+        };
+        let mut result = vec![VariableAccess::from(&var_name)];
+        for (index, access_type) in self.var_access.iter().enumerate() {
+            let indices = match (access_type, dimensions.get(index)) {
+                (AccessType::ArrayAccess(index), Some(size)) => index_range(index, *size),
+                _ => None,
+            };
+            let Some(indices) = indices else {
+                return vec![access];
+            };
+            result = result
+                .iter()
+                .flat_map(|access| {
+                    indices.clone().map(|index| access.push(AccessElement::Index(index)))
+                })
+                .collect();
+            if result.len() > MAX_ELEMENTS {
+                return vec![access];
+            }
+        }
+        result
+    }
 }
 
-impl SignalData {
-    pub fn new(
-        meta: &Meta,
-        template_name: &str,
-        signal_name: &str,
-        signal_access: VariableAccess,
-    ) -> SignalData {
-        SignalData {
-            meta: meta.clone(),
-            template_name: template_name.to_string(),
-            signal_name: signal_name.to_string(),
-            signal_access,
-        }
+/// Returns the possible values of the given array index, if the index is
+/// bounded.
+fn index_range(index: &Expression, size: usize) -> Option<std::ops::Range<usize>> {
+    if let Some(ValueReduction::FieldElement { value }) = index.value() {
+        let index = value.to_usize()?;
+        return Some(index..index + 1);
     }
+    let range = index.range()?;
+    let lower = range.lower()?.max(&0.into()).to_usize()?;
+    let upper = range.upper()?.to_usize()?.min(size.checked_sub(1)?);
+    Some(lower..upper + 1)
 }
 
 pub fn find_unused_output_signals(
-    context: &mut dyn AnalysisContext,
+    context: &dyn AnalysisContext,
     current_cfg: &Cfg,
 ) -> ReportCollection {
     // Exit early if the given CFG represents a function.
@@ -184,96 +134,109 @@ pub fn find_unused_output_signals(
         return ReportCollection::new();
     }
     debug!("running unused output signal analysis pass");
-    let allow_list = HashSet::from(ALLOW_LIST);
+    // Templates on the allow list are commonly instantiated without accessing
+    // the corresponding output signals.
+    let allow_list =
+        context.config().unused_output_signal_allow_list.iter().cloned().collect::<HashSet<_>>();
 
     // Collect all instantiated components.
     let mut components = Vec::new();
-    let mut accesses = Vec::new();
+    let mut accesses = AccessSet::new();
     for basic_block in current_cfg.iter() {
         for stmt in basic_block.iter() {
             visit_statement(stmt, current_cfg, &mut components, &mut accesses);
         }
     }
-    let mut output_signals = Vec::new();
+    let mut reports = ReportCollection::new();
     for component in components {
         // Ignore templates on the allow list.
-        if allow_list.contains(&component.template_name[..]) {
+        if allow_list.contains(&component.template_name) {
             continue;
         }
-        if let Ok(component_cfg) = context.template(&component.template_name) {
-            for output_signal in component_cfg.output_signals() {
-                if let Some(declaration) = component_cfg.get_declaration(output_signal) {
-                    // The signal access pattern is given by the component
-                    // access pattern, followed by the output signal name,
-                    // followed by an array access corresponding to each
-                    // dimension entry for the signal.
-                    //
-                    // E.g., for the component `c[i]` with an output signal
-                    // `out` which is a double array, we get `c[i].out[j][k]`.
-                    // Since we identify array accesses we simply use `i` for
-                    // each array access corresponding to the dimensions of the
-                    // signal.
-                    let mut var_access = component.var_access.clone();
-                    var_access.push(AccessType::ComponentAccess(output_signal.name().to_string()));
-                    for _ in declaration.dimensions() {
-                        let meta = Meta::new(&(0..0), &None);
-                        let index =
-                            Expression::Variable { meta, name: VariableName::from_string("i") };
-                        var_access.push(AccessType::ArrayAccess(Box::new(index)));
+        let Ok(component_cfg) = context.template(&component.template_name) else {
+            continue;
+        };
+        let component_elements = component.elements(current_cfg);
+        for output_signal in component_cfg.output_signals() {
+            if component_cfg.get_declaration(output_signal).is_none() {
+                continue;
+            }
+            // If the dimensions of the output signal are known, each element of
+            // the signal array is considered separately. Otherwise, it is
+            // enough that the signal array is accessed (e.g. `n2b.out`), or
+            // that some element of the signal array may be accessed (e.g.
+            // `n2b.out[i]`).
+            let signal = AccessElement::Signal(output_signal.name().to_string());
+            let dimensions = variable_access::declared_dimensions(component_cfg, output_signal);
+            let signal_elements = |component: &VariableAccess| {
+                let signal = component.push(signal.clone());
+                dimensions
+                    .as_ref()
+                    .and_then(|dimensions| signal.elements(dimensions))
+                    .unwrap_or_else(|| vec![signal])
+            };
+            // `unused[i][j]` is true if the element `j` of the output signal is
+            // not accessed for the component `i`.
+            let unused = component_elements
+                .iter()
+                .map(|component| {
+                    signal_elements(component)
+                        .iter()
+                        .map(|element| !accesses.may_contain(element))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            if unused.iter().flatten().all(|unused| *unused) {
+                // The output signal is not accessed at all.
+                reports.push(build_report(
+                    &component,
+                    current_cfg.name(),
+                    output_signal.name(),
+                    None,
+                ));
+                continue;
+            }
+            let Some(first) = component_elements.first() else {
+                continue;
+            };
+            for (index, element) in signal_elements(first).iter().enumerate() {
+                let signal_name = format!(
+                    "{}{}",
+                    output_signal.name(),
+                    element.path()[first.path().len() + 1..]
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<String>()
+                );
+                if unused.iter().all(|unused| unused[index]) {
+                    // The element is not accessed for any of the components.
+                    reports.push(build_report(&component, current_cfg.name(), &signal_name, None));
+                    continue;
+                }
+                for (component_element, unused) in component_elements.iter().zip(&unused) {
+                    if unused[index] {
+                        let component_element = component_element.to_string();
+                        reports.push(build_report(
+                            &component,
+                            current_cfg.name(),
+                            &signal_name,
+                            Some(component_element),
+                        ));
                     }
-                    let signal_access = VariableAccess::new(&component.var_name, &var_access);
-                    output_signals.push(SignalData::new(
-                        &component.meta,
-                        &component.template_name,
-                        output_signal.name(),
-                        signal_access,
-                    ));
                 }
             }
         }
     }
-    let mut reports = ReportCollection::new();
-    for output_signal in output_signals {
-        if !maybe_accesses(&accesses, &output_signal.signal_access) {
-            reports.push(build_report(
-                &output_signal.meta,
-                current_cfg.name(),
-                &output_signal.template_name,
-                &output_signal.signal_name,
-            ))
-        }
-    }
 
     debug!("{} new reports generated", reports.len());
     reports
 }
 
-// Check if there is an access to a prefix of the output signal access which
-// contains the output signal name. E.g. for the output signal `n2b[1].out[0]`
-// it is enough that the list of all variable accesses `maybe_contains` the
-// prefix `n2b[1].out`. This is to catch instances where the template passes the
-// output signal as input to a function.
-fn maybe_accesses(accesses: &Vec<VariableAccess>, signal_access: &VariableAccess) -> bool {
-    use AccessType::*;
-    let mut signal_access = signal_access.clone();
-    while !accesses.maybe_contains(&signal_access) {
-        if let Some(ComponentAccess(_)) = signal_access.access.last() {
-            // The output signal name is the last component access in the access
-            // array. If it is not included in the access, the output signal is
-            // not accessed by the template.
-            return false;
-        } else {
-            signal_access.access.pop();
-        }
-    }
-    true
-}
-
 fn visit_statement(
     stmt: &Statement,
     cfg: &Cfg,
     components: &mut Vec<ComponentData>,
-    accesses: &mut Vec<VariableAccess>,
+    accesses: &mut AccessSet,
 ) {
     use Statement::*;
     use Expression::*;
@@ -306,7 +269,7 @@ fn visit_statement(
     }
 }
 
-fn visit_expression(expr: &Expression, accesses: &mut Vec<VariableAccess>) {
+fn visit_expression(expr: &Expression, accesses: &mut AccessSet) {
     use Expression::*;
     match expr {
         PrefixOp { rhe, .. } => {
@@ -332,7 +295,8 @@ fn visit_expression(expr: &Expression, accesses: &mut Vec<VariableAccess>) {
             }
         }
         Access { var, access, .. } => {
-            accesses.push(VariableAccess::new(var, access));
+            // We disregard the version to make sure accesses are not order dependent.
+            accesses.insert(VariableAccess::new(&var.without_version(), access));
         }
         Update { rhe, .. } => {
             // We ignore accesses in assignments.
@@ -343,17 +307,18 @@ fn visit_expression(expr: &Expression, accesses: &mut Vec<VariableAccess>) {
 }
 
 fn build_report(
-    meta: &Meta,
+    component: &ComponentData,
     current_template: &str,
-    component_template: &str,
     signal_name: &str,
+    component_element: Option<String>,
 ) -> Report {
     UnusedOutputSignalWarning {
-        file_id: meta.file_id(),
-        file_location: meta.file_location(),
+        file_id: component.meta.file_id(),
+        file_location: component.meta.file_location(),
         current_template: current_template.to_string(),
-        component_template: component_template.to_string(),
+        component_template: component.template_name.clone(),
         signal_name: signal_name.to_string(),
+        component_element,
     }
     .into_report()
 }
@@ -363,17 +328,19 @@ mod tests {
     use num_bigint_dig::BigInt;
     use program_structure::{
         constants::Curve,
+        report::ReportCollection,
         intermediate_representation::{
             VariableName, AccessType, Expression, Meta, value_meta::ValueReduction,
         },
     };
 
     use crate::{
+        analysis_context::AnalysisContext,
         analysis_runner::AnalysisRunner,
-        unused_output_signal::{MaybeEqual, MaybeContains, maybe_accesses},
+        variable_access::{AccessSet, VariableAccess},
     };
 
-    use super::{find_unused_output_signals, VariableAccess};
+    use super::find_unused_output_signals;
 
     #[test]
     fn test_maybe_equal() {
@@ -399,45 +366,38 @@ mod tests {
 
         // `var[0].out`
         let first_access = VariableAccess::new(
-            &var.with_version(1),
+            &var,
             &[ArrayAccess(zero.clone()), ComponentAccess("out".to_string())],
         );
         // `var[i].out`
         let second_access = VariableAccess::new(
-            &var.with_version(2),
+            &var,
             &[ArrayAccess(i.clone()), ComponentAccess("out".to_string())],
         );
         // `var[1].out`
-        let third_access = VariableAccess::new(
-            &var.with_version(3),
-            &[ArrayAccess(one), ComponentAccess("out".to_string())],
-        );
+        let third_access =
+            VariableAccess::new(&var, &[ArrayAccess(one), ComponentAccess("out".to_string())]);
         // `var[i].out[0]`
         let fourth_access = VariableAccess::new(
-            &var.with_version(4),
+            &var,
             &[ArrayAccess(i), ComponentAccess("out".to_string()), ArrayAccess(zero)],
         );
 
         // The first and second accesses should be identified.
-        assert!(first_access.maybe_equal(&second_access));
+        assert!(first_access.may_alias(&second_access));
         // The first and third accesses should not be identified.
-        assert!(!first_access.maybe_equal(&third_access));
+        assert!(!first_access.may_alias(&third_access));
 
-        let accesses = vec![first_access];
+        let accesses = AccessSet::from_iter([first_access]);
 
         // The first and second accesses should be identified.
-        assert!(accesses.maybe_contains(&second_access));
+        assert!(accesses.may_contain(&second_access));
         // The first and third accesses should not be identified.
-        assert!(!accesses.maybe_contains(&third_access));
-
+        assert!(!accesses.may_contain(&third_access));
         // The fourth access is not equal to the first, but a prefix is.
-        assert!(!accesses.maybe_contains(&fourth_access));
-        assert!(maybe_accesses(&accesses, &fourth_access));
+        assert!(accesses.may_contain(&fourth_access));
     }
 
-    #[test]
-    fn test_maybe_accesses() {}
-
     #[test]
     fn test_unused_output_signal() {
         // The output signal `out` in `Test` is not accessed, for any of the two
@@ -555,7 +515,7 @@ mod tests {
         ];
         validate_reports("Main", &src, 0);
 
-        // TODO: Should detect that `test[i].out[1]` is not accessed.
+        // Should detect that `test[i].out[1]` is not accessed.
         let src = [
             r#"
             template Test() {
@@ -581,9 +541,14 @@ mod tests {
             }
         "#,
         ];
-        validate_reports("Main", &src, 0);
+        let reports = validate_reports("Main", &src, 1);
+        assert_eq!(
+            reports[0].message(),
+            "The output signal `out[1]` defined by the template `Test` is not constrained in `Main`."
+        );
+        assert!(reports[0].notes().is_empty());
 
-        // TODO: Should detect that `test[1].out` is not accessed.
+        // Should detect that `test[1].out` is not accessed.
         let src = [
             r#"
             template Test() {
@@ -607,13 +572,44 @@ mod tests {
             }
         "#,
         ];
+        let reports = validate_reports("Main", &src, 1);
+        assert_eq!(
+            reports[0].notes(),
+            &vec!["The signal is not constrained for the component `test[1]`.".to_string()]
+        );
+
+        // Should not generate a warning if the component index is unbounded.
+        let src = [
+            r#"
+            template Test() {
+                signal input in;
+                signal output out;
+
+                out <== 2 * in + 1;
+            }
+        "#,
+            r#"
+            template Main(n) {
+                signal input in[n];
+
+                component test[2];
+                for (var i = 0; i < n; i++) {
+                    test[i] = Test();
+                    test[i].in <== in[i];
+                }
+
+                test[0].out === 1;
+            }
+        "#,
+        ];
         validate_reports("Main", &src, 0);
     }
 
-    fn validate_reports(name: &str, src: &[&str], expected_len: usize) {
-        let mut context = AnalysisRunner::new(Curve::Goldilocks).with_src(src);
-        let cfg = context.take_template(name).unwrap();
-        let reports = find_unused_output_signals(&mut context, &cfg);
+    fn validate_reports(name: &str, src: &[&str], expected_len: usize) -> ReportCollection {
+        let context = AnalysisRunner::new(Curve::Goldilocks).with_src(src);
+        let cfg = context.template(name).unwrap();
+        let reports = find_unused_output_signals(&context, cfg);
         assert_eq!(reports.len(), expected_len);
+        reports
     }
 }
@@ -0,0 +1,337 @@
+//! Variable accesses with constant array indices resolved. These are used to
+//! track signal and component arrays element by element.
+use num_traits::ToPrimitive;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use program_structure::cfg::Cfg;
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::variable_meta::{VariableMeta, VariableUse};
+use program_structure::ir::{AccessType, Expression, Statement, VariableName};
+
+/// The maximum number of elements tracked separately for a single array.
+/// Larger arrays are tracked as a single variable.
+pub const MAX_ELEMENTS: usize = 256;
+
+/// A single step in an access path.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AccessElement {
+    /// An array access with a constant index.
+    Index(usize),
+    /// An array access with an index which is not known to be constant.
+    Unknown,
+    /// A component signal access.
+    Signal(String),
+}
+
+impl AccessElement {
+    fn from_access(access: &AccessType) -> AccessElement {
+        match access {
+            AccessType::ArrayAccess(index) => match index.value() {
+                Some(ValueReduction::FieldElement { value }) => {
+                    value.to_usize().map(AccessElement::Index).unwrap_or(AccessElement::Unknown)
+                }
+                _ => AccessElement::Unknown,
+            },
+            AccessType::ComponentAccess(name) => AccessElement::Signal(name.clone()),
+        }
+    }
+
+    /// Returns false if the two elements are known to access different parts
+    /// of the same variable.
+    fn may_alias(&self, other: &AccessElement) -> bool {
+        use AccessElement::*;
+        match (self, other) {
+            (Index(lhs), Index(rhs)) => lhs == rhs,
+            (Signal(lhs), Signal(rhs)) => lhs == rhs,
+            (Index(_) | Unknown, Index(_) | Unknown) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for AccessElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessElement::Index(index) => write!(f, "[{index}]"),
+            AccessElement::Unknown => write!(f, "[?]"),
+            AccessElement::Signal(name) => write!(f, ".{name}"),
+        }
+    }
+}
+
+/// A variable together with an access path, like `c[0].in[i]`. Array indices
+/// which are not known to be constant are treated conservatively, so that
+/// `c[0].in[i]` may alias both `c[0].in[0]` and `c[0].in[1]`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VariableAccess {
+    var: VariableName,
+    path: Vec<AccessElement>,
+}
+
+impl VariableAccess {
+    pub fn new(var: &VariableName, access: &[AccessType]) -> VariableAccess {
+        VariableAccess {
+            var: var.clone(),
+            path: access.iter().map(AccessElement::from_access).collect(),
+        }
+    }
+
+    pub fn name(&self) -> &VariableName {
+        &self.var
+    }
+
+    pub fn path(&self) -> &[AccessElement] {
+        &self.path
+    }
+
+    /// Returns an access to the component (array element) accessed by `var`.
+    /// The signals of a component are related through the constraints of the
+    /// instantiated template, so component signal accesses like `c[0].in` and
+    /// `c[0].out` are both tracked as accesses to `c[0]`.
+    pub fn component(var: &VariableUse) -> VariableAccess {
+        let path = var
+            .access()
+            .iter()
+            .map(AccessElement::from_access)
+            .take_while(|element| !matches!(element, AccessElement::Signal(_)))
+            .collect();
+        VariableAccess { var: var.name().clone(), path }
+    }
+
+    /// Returns the access extended by the given element.
+    #[must_use]
+    pub fn push(&self, element: AccessElement) -> VariableAccess {
+        let mut result = self.clone();
+        result.path.push(element);
+        result
+    }
+
+    /// Returns true unless the two accesses are known to access disjoint
+    /// parts of the same variable. Note that an access aliases all accesses
+    /// that it is a prefix of (e.g. `c[0]` aliases `c[0].out`).
+    ///
+    /// The relation is reflexive and symmetric, but not transitive, since
+    /// `v[0]` and `v[i]` may alias, and `v[i]` and `v[1]` may alias, but
+    /// `v[0]` and `v[1]` do not.
+    #[must_use]
+    pub fn may_alias(&self, other: &VariableAccess) -> bool {
+        self.var == other.var
+            && self.path.iter().zip(other.path.iter()).all(|(lhs, rhs)| lhs.may_alias(rhs))
+    }
+
+    /// Returns each element of the array with the given dimensions accessed
+    /// by `self`. Returns `None` if the number of elements exceeds
+    /// `MAX_ELEMENTS`.
+    #[must_use]
+    pub fn elements(&self, dimensions: &[usize]) -> Option<Vec<VariableAccess>> {
+        let size = dimensions.iter().try_fold(1_usize, |size, n| size.checked_mul(*n))?;
+        if size > MAX_ELEMENTS {
+            return None;
+        }
+        let mut result = vec![self.clone()];
+        for size in dimensions {
+            result = result
+                .iter()
+                .flat_map(|access| (0..*size).map(|index| access.push(AccessElement::Index(index))))
+                .collect();
+        }
+        Some(result)
+    }
+}
+
+impl From<&VariableName> for VariableAccess {
+    /// Returns an access to the entire variable.
+    fn from(var: &VariableName) -> VariableAccess {
+        VariableAccess { var: var.clone(), path: Vec::new() }
+    }
+}
+
+impl From<&VariableUse> for VariableAccess {
+    fn from(var: &VariableUse) -> VariableAccess {
+        VariableAccess::new(var.name(), var.access())
+    }
+}
+
+impl fmt::Display for VariableAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.var)?;
+        for element in &self.path {
+            write!(f, "{element}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A set of variable accesses, indexed by variable name.
+#[derive(Clone, Debug, Default)]
+pub struct AccessSet {
+    accesses: HashMap<VariableName, HashSet<VariableAccess>>,
+}
+
+impl AccessSet {
+    pub fn new() -> AccessSet {
+        AccessSet::default()
+    }
+
+    pub fn insert(&mut self, access: VariableAccess) -> bool {
+        self.accesses.entry(access.name().clone()).or_default().insert(access)
+    }
+
+    /// Returns the accesses in the set which may alias the given access.
+    pub fn aliases<'a>(
+        &'a self,
+        access: &'a VariableAccess,
+    ) -> impl Iterator<Item = &'a VariableAccess> + 'a {
+        self.accesses
+            .get(access.name())
+            .into_iter()
+            .flatten()
+            .filter(move |other| other.may_alias(access))
+    }
+
+    /// Returns true if the set contains an access which may alias the given
+    /// access.
+    pub fn may_contain(&self, access: &VariableAccess) -> bool {
+        self.aliases(access).next().is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &VariableAccess> {
+        self.accesses.values().flatten()
+    }
+}
+
+impl Extend<VariableAccess> for AccessSet {
+    fn extend<T: IntoIterator<Item = VariableAccess>>(&mut self, iter: T) {
+        for access in iter {
+            self.insert(access);
+        }
+    }
+}
+
+impl FromIterator<VariableAccess> for AccessSet {
+    fn from_iter<T: IntoIterator<Item = VariableAccess>>(iter: T) -> AccessSet {
+        let mut result = AccessSet::new();
+        result.extend(iter);
+        result
+    }
+}
+
+/// Returns the variables written by the statement, together with the
+/// corresponding variable uses. Since local variables are versioned, each
+/// assignment to a local variable defines the entire variable. Signals and
+/// components are not versioned, so an assignment to an element (like
+/// `c[0].in <== x`) only defines that element. (Component signal accesses are
+/// tracked per component, see `VariableAccess::component`.)
+pub fn definitions(stmt: &Statement) -> Vec<(VariableAccess, &VariableUse)> {
+    let locals_written =
+        stmt.locals_written().iter().map(|var| (VariableAccess::from(var.name()), var));
+    let signals_written = stmt.signals_written().iter().map(|var| (VariableAccess::from(var), var));
+    let components_written =
+        stmt.components_written().iter().map(|var| (VariableAccess::component(var), var));
+    locals_written.chain(signals_written).chain(components_written).collect()
+}
+
+/// Returns the variables written by the statement. (See `definitions`.)
+pub fn variables_written(stmt: &Statement) -> Vec<VariableAccess> {
+    definitions(stmt).into_iter().map(|(access, _)| access).collect()
+}
+
+/// Returns the variables read by the statement. An assignment to an element of
+/// a signal or component array reads the array itself. This read is ignored
+/// here, since it would otherwise relate the assigned element to all other
+/// elements of the array.
+pub fn variables_read(stmt: &Statement) -> Vec<VariableAccess> {
+    let updated = match stmt {
+        Statement::Substitution { var, rhe: Expression::Update { .. }, .. }
+            if stmt.locals_written().is_empty() =>
+        {
+            Some(var)
+        }
+        _ => None,
+    };
+    let is_self_read = |var: &VariableUse| Some(var.name()) == updated && var.access().is_empty();
+    let locals_read = stmt.locals_read().iter().map(VariableAccess::from);
+    let signals_read =
+        stmt.signals_read().iter().filter(|var| !is_self_read(var)).map(VariableAccess::from);
+    let components_read = stmt
+        .components_read()
+        .iter()
+        .filter(|var| !is_self_read(var))
+        .map(VariableAccess::component);
+    locals_read.chain(signals_read).chain(components_read).collect()
+}
+
+/// Returns the variables used (that is, read or written) by the statement.
+pub fn variables_used(stmt: &Statement) -> Vec<VariableAccess> {
+    let mut result = variables_read(stmt);
+    result.extend(variables_written(stmt));
+    result
+}
+
+/// Returns the dimensions of the given variable, if the variable is declared
+/// by the CFG and all dimensions are constant.
+pub fn declared_dimensions(cfg: &Cfg, var: &VariableName) -> Option<Vec<usize>> {
+    cfg.iter().flat_map(|basic_block| basic_block.iter()).find_map(|stmt| match stmt {
+        Statement::Declaration { names, dimensions, .. }
+            if names.iter().any(|name| name == var) =>
+        {
+            dimensions
+                .iter()
+                .map(|size| match size.value() {
+                    Some(ValueReduction::FieldElement { value }) => value.to_usize(),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_may_alias() {
+        use AccessElement::*;
+        let access =
+            |path: Vec<AccessElement>| VariableAccess { var: VariableName::from_string("c"), path };
+        let signal = |name: &str| Signal(name.to_string());
+
+        // `c[0].in` and `c[1].in` do not alias.
+        assert!(
+            !access(vec![Index(0), signal("in")]).may_alias(&access(vec![Index(1), signal("in")]))
+        );
+        // `c[0].in` and `c[0].out` do not alias.
+        assert!(
+            !access(vec![Index(0), signal("in")]).may_alias(&access(vec![Index(0), signal("out")]))
+        );
+        // `c[i].in` aliases both `c[0].in` and `c[1].in`.
+        assert!(
+            access(vec![Unknown, signal("in")]).may_alias(&access(vec![Index(0), signal("in")]))
+        );
+        assert!(
+            access(vec![Unknown, signal("in")]).may_alias(&access(vec![Index(1), signal("in")]))
+        );
+        // `c` and `c[0]` alias `c[0].in[1]`.
+        let element = access(vec![Index(0), signal("in"), Index(1)]);
+        assert!(access(vec![]).may_alias(&element));
+        assert!(access(vec![Index(0)]).may_alias(&element));
+        assert!(element.may_alias(&access(vec![Index(0)])));
+        // Accesses to different variables never alias.
+        let other = VariableAccess::from(&VariableName::from_string("d"));
+        assert!(!access(vec![]).may_alias(&other));
+    }
+
+    #[test]
+    fn test_elements() {
+        let access = VariableAccess::from(&VariableName::from_string("b"));
+        let elements = access.elements(&[2, 3]).unwrap();
+        assert_eq!(elements.len(), 6);
+        assert_eq!(elements[0].to_string(), "b[0][0]");
+        assert_eq!(elements[5].to_string(), "b[1][2]");
+        assert_eq!(access.elements(&[]).unwrap(), vec![access.clone()]);
+        assert!(access.elements(&[MAX_ELEMENTS + 1]).is_none());
+    }
+}
@@ -168,6 +168,13 @@ impl Definition {
             Self::Function { name, .. } => name.clone(),
         }
     }
+
+    pub fn meta(&self) -> &Meta {
+        match self {
+            Self::Template { meta, .. } => meta,
+            Self::Function { meta, .. } => meta,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -313,6 +320,7 @@ pub enum Expression {
 }
 
 #[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum Access {
     ComponentAccess(String),
     ArrayAccess(Expression),
@@ -363,6 +371,7 @@ pub enum ExpressionPrefixOpcode {
 }
 
 #[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum LogArgument {
     LogStr(String),
     LogExp(Expression),
@@ -4,6 +4,7 @@ use std::fmt;
 
 use crate::ir::declarations::Declarations;
 use crate::ir::degree_meta::DegreeEnvironment;
+use crate::ir::range_meta::RangeEnvironment;
 use crate::ir::value_meta::ValueEnvironment;
 use crate::ssa::traits::DirectedGraphNode;
 
@@ -138,6 +139,15 @@ impl BasicBlock {
         result
     }
 
+    pub fn propagate_ranges(&mut self, env: &mut RangeEnvironment) -> bool {
+        trace!("propagating value ranges for basic block {}", self.index());
+        let mut result = false;
+        for stmt in self.iter_mut() {
+            result |= stmt.propagate_ranges(env);
+        }
+        result
+    }
+
     pub fn propagate_values(&mut self, env: &mut ValueEnvironment) -> bool {
         trace!("propagating values for basic block {}", self.index());
         let mut result = false;
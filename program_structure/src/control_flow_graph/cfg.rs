@@ -1,15 +1,18 @@
 use log::debug;
+use num_traits::ToPrimitive;
 use std::collections::HashSet;
 use std::fmt;
+use std::sync::Arc;
 use std::time::{Instant, Duration};
 
 use crate::constants::UsefulConstants;
 use crate::file_definition::FileID;
 use crate::ir::declarations::{Declaration, Declarations};
 use crate::ir::degree_meta::{DegreeEnvironment, Degree, DegreeRange};
-use crate::ir::value_meta::ValueEnvironment;
+use crate::ir::range_meta::{RangeEnvironment, ValueRange};
+use crate::ir::value_meta::{FunctionEvaluator, ValueEnvironment, ValueMeta, ValueReduction};
 use crate::ir::variable_meta::VariableMeta;
-use crate::ir::{VariableName, VariableType, SignalType};
+use crate::ir::{Expression, Statement, VariableName, VariableType, SignalType};
 use crate::ssa::dominator_tree::DominatorTree;
 use crate::ssa::errors::SSAResult;
 use crate::ssa::{insert_phi_statements, insert_ssa_variables};
@@ -45,6 +48,11 @@ pub struct Cfg {
     name: String,
     constants: UsefulConstants,
     parameters: Parameters,
+    /// Constant values bound to the parameters (if the CFG represents a
+    /// specific template instantiation).
+    arguments: Vec<ValueReduction>,
+    /// Used to evaluate function calls with constant arguments (if any).
+    functions: Option<Arc<dyn FunctionEvaluator>>,
     declarations: Declarations,
     basic_blocks: Vec<BasicBlock>,
     definition_type: DefinitionType,
@@ -65,12 +73,39 @@ impl Cfg {
             name,
             constants,
             parameters,
+            arguments: Vec::new(),
+            functions: None,
             declarations,
             basic_blocks,
             definition_type,
             dominator_tree,
         }
     }
+
+    /// Binds the parameters to the given constant values. This is used to
+    /// analyze a specific instantiation of a template, and must be called
+    /// before the CFG is converted to SSA.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the number of arguments does not match the
+    /// number of parameters.
+    #[must_use]
+    pub fn with_arguments(mut self, arguments: &[ValueReduction]) -> Cfg {
+        assert_eq!(arguments.len(), self.parameters.len());
+        self.arguments = arguments.to_vec();
+        self
+    }
+
+    /// Use the given evaluator to evaluate function calls with constant
+    /// arguments during value propagation. This must be called before the CFG
+    /// is converted to SSA.
+    #[must_use]
+    pub fn with_functions(mut self, functions: Arc<dyn FunctionEvaluator>) -> Cfg {
+        self.functions = Some(functions);
+        self
+    }
+
     /// Returns the entry (first) block of the CFG.
     #[must_use]
     pub fn entry_block(&self) -> &BasicBlock {
@@ -118,6 +153,7 @@ impl Cfg {
         self.propagate_types();
         self.propagate_values();
         self.propagate_degrees();
+        self.propagate_ranges();
         self.cache_variable_use();
 
         // 5. Print trace output of CFG.
@@ -163,6 +199,13 @@ impl Cfg {
         &self.parameters
     }
 
+    /// Returns the constant values bound to the parameters. This is empty
+    /// unless the CFG represents a specific template instantiation.
+    #[must_use]
+    pub fn arguments(&self) -> &[ValueReduction] {
+        &self.arguments
+    }
+
     /// Returns the variable declaration for the CFG.
     #[must_use]
     pub fn declarations(&self) -> &Declarations {
@@ -476,10 +519,83 @@ impl Cfg {
         }
     }
 
+    /// Propagate value ranges along the CFG. Since ranges are computed using
+    /// constant values, this must run after value propagation.
+    pub(crate) fn propagate_ranges(&mut self) {
+        debug!("propagating value ranges for `{}`", self.name());
+        let mut env = RangeEnvironment::new(&self.constants, &self.declarations);
+        for (index, param) in self.parameters().iter().enumerate() {
+            let range = match self.arguments.get(index) {
+                Some(value) => ValueRange::from_value(value, self.constants.prime()),
+                None => ValueRange::unbounded(),
+            };
+            env.set_range(param, &range);
+        }
+        env.add_constraints(self.iter().flat_map(|basic_block| basic_block.iter()));
+
+        // A loop header is a basic block with a predecessor which occurs
+        // after the block itself. The loop condition is given by the final
+        // if-statement of the header.
+        let loop_headers = self
+            .iter()
+            .filter(|basic_block| {
+                basic_block.predecessors().iter().any(|index| *index >= basic_block.index())
+            })
+            .map(|basic_block| basic_block.index())
+            .collect::<Vec<_>>();
+        for cond in self.loop_conditions(&loop_headers) {
+            env.add_loop_condition(cond);
+        }
+        let mut rerun = true;
+        let start = Instant::now();
+        while rerun {
+            // Rerun range propagation if a single child node was updated.
+            rerun = false;
+            for basic_block in self.iter_mut() {
+                rerun = basic_block.propagate_ranges(&mut env) || rerun;
+            }
+            // Loop bounds are updated from the ranges of the loop conditions.
+            for cond in self.loop_conditions(&loop_headers) {
+                rerun = env.update_loop_bound(cond) || rerun;
+            }
+            // Bail out if analysis takes more than 10 seconds.
+            if start.elapsed() > MAX_ANALYSIS_DURATION {
+                debug!("failed to propagate ranges within allotted time");
+                rerun = false;
+            }
+        }
+    }
+
+    fn loop_conditions<'a>(
+        &'a self,
+        loop_headers: &'a [Index],
+    ) -> impl Iterator<Item = &'a Expression> + 'a {
+        loop_headers.iter().filter_map(|index| {
+            match self.get_basic_block(*index)?.statements().last() {
+                Some(Statement::IfThenElse { cond, .. }) => Some(cond),
+                _ => None,
+            }
+        })
+    }
+
     /// Propagate constant values along the CFG.
     pub(crate) fn propagate_values(&mut self) {
         debug!("propagating constant values for `{}`", self.name());
         let mut env = ValueEnvironment::new(&self.constants);
+        if let Some(functions) = &self.functions {
+            env = env.with_functions(functions.clone());
+        }
+        for (param, value) in self.parameters.iter().zip(self.arguments.iter()) {
+            env.add_variable(param, value);
+        }
+        let assigned = self
+            .iter()
+            .flat_map(|basic_block| basic_block.iter())
+            .filter_map(|stmt| match stmt {
+                Statement::Substitution { var, .. } => Some(var.clone()),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
         let mut rerun = true;
         let start = Instant::now();
         while rerun {
@@ -488,6 +604,7 @@ impl Cfg {
             for basic_block in self.iter_mut() {
                 rerun = rerun || basic_block.propagate_values(&mut env);
             }
+            rerun = self.initialize_arrays(&assigned, &mut env) || rerun;
             // Bail out if analysis takes more than 10 seconds.
             if start.elapsed() > MAX_ANALYSIS_DURATION {
                 debug!("failed to propagate values within allotted time");
@@ -496,6 +613,44 @@ impl Cfg {
         }
     }
 
+    /// Local arrays are zero initialized. This method sets the value of each
+    /// unassigned (i.e. initial) version of a local array with constant
+    /// dimensions. Returns true if a new value was added to the environment.
+    fn initialize_arrays(
+        &self,
+        assigned: &HashSet<VariableName>,
+        env: &mut ValueEnvironment,
+    ) -> bool {
+        let mut result = false;
+        for stmt in self.iter().flat_map(|basic_block| basic_block.iter()) {
+            let Statement::Declaration { names, var_type: VariableType::Local, dimensions, .. } =
+                stmt
+            else {
+                continue;
+            };
+            if dimensions.is_empty() {
+                continue;
+            }
+            let dimensions = dimensions
+                .iter()
+                .map(|size| match size.value()? {
+                    ValueReduction::FieldElement { value } => value.to_usize(),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>();
+            let Some(zero) = dimensions.and_then(|dimensions| ValueReduction::zero(&dimensions))
+            else {
+                continue;
+            };
+            for name in names.iter() {
+                if !assigned.contains(name) && env.get_variable(name).is_none() {
+                    result = env.add_variable(name, &zero) || result;
+                }
+            }
+        }
+        result
+    }
+
     /// Propagate variable types along the CFG.
     pub(crate) fn propagate_types(&mut self) {
         debug!("propagating variable types for `{}`", self.name());
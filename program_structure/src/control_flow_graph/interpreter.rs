@@ -0,0 +1,247 @@
+use log::trace;
+use num_traits::ToPrimitive;
+use std::collections::HashMap;
+
+use crate::ir::value_meta::{ValueEnvironment, ValueReduction};
+use crate::ir::{AccessType, Expression, Statement, VariableName, VariableType};
+
+use super::{Cfg, DefinitionType, Index};
+
+/// The maximum number of statements executed (and array elements allocated)
+/// when evaluating a single call, including nested calls.
+const MAX_STEPS: usize = 10_000;
+
+/// The maximum depth of nested function calls.
+const MAX_CALL_DEPTH: usize = 64;
+
+/// An interpreter for function CFGs, used to evaluate calls to functions with
+/// constant arguments. Evaluation is bounded both by a step budget and by a
+/// limit on the depth of nested calls.
+pub struct Interpreter<'a> {
+    functions: &'a dyn Fn(&str) -> Option<&'a Cfg>,
+    steps: usize,
+    depth: usize,
+}
+
+impl<'a> Interpreter<'a> {
+    /// Creates a new interpreter. The given closure is used to look up the
+    /// CFG of each called function.
+    pub fn new(functions: &'a dyn Fn(&str) -> Option<&'a Cfg>) -> Interpreter<'a> {
+        Interpreter { functions, steps: 0, depth: 0 }
+    }
+
+    /// Evaluates the function call `name(args...)`. Returns `None` if the
+    /// function is unknown, if the evaluation fails (e.g. on division by zero
+    /// or a failing assert), or if the evaluation exceeds the step budget or
+    /// recursion limit.
+    pub fn call(&mut self, name: &str, args: &[ValueReduction]) -> Option<ValueReduction> {
+        let result = self.call_value(name, args.to_vec());
+        if result.is_none() {
+            trace!("failed to evaluate `{name}`");
+        }
+        result
+    }
+
+    fn call_value(&mut self, name: &str, args: Vec<ValueReduction>) -> Option<ValueReduction> {
+        let cfg = (self.functions)(name)?;
+        if !matches!(cfg.definition_type(), DefinitionType::Function)
+            || cfg.parameters().len() != args.len()
+            || self.depth >= MAX_CALL_DEPTH
+        {
+            return None;
+        }
+        let mut frame =
+            Frame { env: ValueEnvironment::new(cfg.constants()), variables: HashMap::new() };
+        for (param, arg) in cfg.parameters().iter().zip(args) {
+            frame.variables.insert(param.without_version(), arg);
+        }
+        self.depth += 1;
+        let result = self.run(cfg, &mut frame);
+        self.depth -= 1;
+        result
+    }
+
+    /// Executes the CFG from the entry block until a return statement is
+    /// reached. Since phi statements are ignored, and all versions of a
+    /// variable are stored together, this reverses the SSA transformation.
+    fn run(&mut self, cfg: &Cfg, frame: &mut Frame) -> Option<ValueReduction> {
+        use Statement::*;
+        let mut index = Index::default();
+        loop {
+            let basic_block = cfg.get_basic_block(index)?;
+            let mut next = None;
+            for stmt in basic_block.iter() {
+                self.step(1)?;
+                match stmt {
+                    Declaration { names, var_type: VariableType::Local, dimensions, .. } => {
+                        let dimensions = dimensions
+                            .iter()
+                            .map(|size| self.evaluate_index(size, frame))
+                            .collect::<Option<Vec<_>>>()?;
+                        self.step(
+                            dimensions.iter().try_fold(1_usize, |size, n| size.checked_mul(*n))?,
+                        )?;
+                        let zero = ValueReduction::zero(&dimensions)?;
+                        for name in names.iter() {
+                            frame.variables.insert(name.without_version(), zero.clone());
+                        }
+                    }
+                    Substitution { rhe: Expression::Phi { .. }, .. } => {}
+                    Substitution { var, rhe, .. } => {
+                        let value = self.evaluate(rhe, frame)?;
+                        frame.variables.insert(var.without_version(), value);
+                    }
+                    IfThenElse { cond, true_index, false_index, .. } => {
+                        next = if self.evaluate_bool(cond, frame)? {
+                            Some(*true_index)
+                        } else {
+                            Some((*false_index)?)
+                        };
+                    }
+                    Return { value, .. } => return self.evaluate(value, frame),
+                    Assert { arg, .. } => {
+                        if !self.evaluate_bool(arg, frame)? {
+                            return None;
+                        }
+                    }
+                    LogCall { .. } => {}
+                    // Functions cannot declare signals or components, or
+                    // generate constraints.
+                    Declaration { .. } | ConstraintEquality { .. } => return None,
+                }
+            }
+            index = match next {
+                Some(index) => index,
+                None => {
+                    let mut successors = basic_block.successors().iter();
+                    match (successors.next(), successors.next()) {
+                        (Some(index), None) => *index,
+                        _ => return None,
+                    }
+                }
+            };
+        }
+    }
+
+    fn step(&mut self, steps: usize) -> Option<()> {
+        self.steps = self.steps.checked_add(steps)?;
+        (self.steps <= MAX_STEPS).then_some(())
+    }
+
+    fn evaluate(&mut self, expr: &Expression, frame: &Frame) -> Option<ValueReduction> {
+        use Expression::*;
+        match expr {
+            Number(_, value) => Some(ValueReduction::FieldElement { value: value.clone() }),
+            Variable { name, .. } => frame.variables.get(&name.without_version()).cloned(),
+            InfixOp { lhe, infix_op, rhe, .. } => {
+                let lhv = self.evaluate_scalar(lhe, frame)?;
+                let rhv = self.evaluate_scalar(rhe, frame)?;
+                // Booleans and field elements may be mixed in Circom.
+                infix_op
+                    .propagate_values(Some(&lhv), Some(&rhv), &frame.env)
+                    .or_else(|| {
+                        let (lhv, rhv) = (as_field_element(&lhv), as_field_element(&rhv));
+                        infix_op.propagate_values(Some(&lhv), Some(&rhv), &frame.env)
+                    })
+                    .or_else(|| {
+                        let (lhv, rhv) = (as_boolean(&lhv), as_boolean(&rhv));
+                        infix_op.propagate_values(Some(&lhv), Some(&rhv), &frame.env)
+                    })
+            }
+            PrefixOp { prefix_op, rhe, .. } => {
+                let rhv = self.evaluate_scalar(rhe, frame)?;
+                prefix_op
+                    .propagate_values(Some(&rhv), &frame.env)
+                    .or_else(|| {
+                        prefix_op.propagate_values(Some(&as_field_element(&rhv)), &frame.env)
+                    })
+                    .or_else(|| prefix_op.propagate_values(Some(&as_boolean(&rhv)), &frame.env))
+            }
+            SwitchOp { cond, if_true, if_false, .. } => {
+                if self.evaluate_bool(cond, frame)? {
+                    self.evaluate(if_true, frame)
+                } else {
+                    self.evaluate(if_false, frame)
+                }
+            }
+            Call { name, args, .. } => {
+                let args =
+                    args.iter().map(|arg| self.evaluate(arg, frame)).collect::<Option<Vec<_>>>()?;
+                self.call_value(name, args)
+            }
+            InlineArray { values, .. } => {
+                let values = values
+                    .iter()
+                    .map(|value| self.evaluate(value, frame))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(ValueReduction::Array { values })
+            }
+            Access { var, access, .. } => {
+                let indices = self.evaluate_access(access, frame)?;
+                frame.variables.get(&var.without_version())?.get(&indices).cloned()
+            }
+            Update { var, access, rhe, .. } => {
+                let indices = self.evaluate_access(access, frame)?;
+                let value = self.evaluate(rhe, frame)?;
+                frame.variables.get(&var.without_version())?.update(&indices, &value)
+            }
+            Phi { .. } => None,
+        }
+    }
+
+    fn evaluate_scalar(&mut self, expr: &Expression, frame: &Frame) -> Option<ValueReduction> {
+        match self.evaluate(expr, frame)? {
+            ValueReduction::Array { .. } => None,
+            value => Some(value),
+        }
+    }
+
+    fn evaluate_bool(&mut self, expr: &Expression, frame: &Frame) -> Option<bool> {
+        match as_boolean(&self.evaluate_scalar(expr, frame)?) {
+            ValueReduction::Boolean { value } => Some(value),
+            _ => None,
+        }
+    }
+
+    fn evaluate_index(&mut self, expr: &Expression, frame: &Frame) -> Option<usize> {
+        match self.evaluate_scalar(expr, frame)? {
+            ValueReduction::FieldElement { value } => value.to_usize(),
+            _ => None,
+        }
+    }
+
+    fn evaluate_access(&mut self, access: &[AccessType], frame: &Frame) -> Option<Vec<usize>> {
+        access
+            .iter()
+            .map(|access| match access {
+                AccessType::ArrayAccess(index) => self.evaluate_index(index, frame),
+                AccessType::ComponentAccess(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// The variables of a single function call.
+struct Frame {
+    env: ValueEnvironment,
+    variables: HashMap<VariableName, ValueReduction>,
+}
+
+fn as_field_element(value: &ValueReduction) -> ValueReduction {
+    match value {
+        ValueReduction::Boolean { value } => {
+            ValueReduction::FieldElement { value: u8::from(*value).into() }
+        }
+        value => value.clone(),
+    }
+}
+
+fn as_boolean(value: &ValueReduction) -> ValueReduction {
+    use num_traits::Zero;
+    match value {
+        ValueReduction::FieldElement { value } => {
+            ValueReduction::Boolean { value: !value.is_zero() }
+        }
+        value => value.clone(),
+    }
+}
@@ -3,10 +3,14 @@ pub mod errors;
 pub mod parameters;
 
 mod cfg;
+mod interpreter;
 mod lifting;
+mod node_query;
 mod ssa_impl;
 mod unique_vars;
 
 pub use basic_block::BasicBlock;
 pub use cfg::{Cfg, DefinitionType, Index};
+pub use interpreter::Interpreter;
 pub use lifting::IntoCfg;
+pub use node_query::IrNode;
@@ -0,0 +1,189 @@
+use std::fmt;
+
+use crate::file_definition::FileID;
+use crate::ir::degree_meta::DegreeRange;
+use crate::ir::range_meta::ValueRange;
+use crate::ir::value_meta::ValueReduction;
+use crate::ir::{AccessType, Expression, LogArgument, Meta, Statement, VariableName, VariableType};
+
+use super::Cfg;
+
+/// A reference to an IR node (a statement or an expression) in a CFG.
+#[derive(Clone, Copy)]
+pub enum IrNode<'a> {
+    Statement(&'a Statement),
+    Expression(&'a Expression),
+}
+
+impl<'a> IrNode<'a> {
+    #[must_use]
+    pub fn meta(&self) -> &'a Meta {
+        match self {
+            IrNode::Statement(stmt) => stmt.meta(),
+            IrNode::Expression(expr) => expr.meta(),
+        }
+    }
+
+    /// Returns the (SSA) variable name for nodes representing a variable (or a
+    /// variable update), and `None` otherwise.
+    #[must_use]
+    pub fn variable_name(&self) -> Option<&'a VariableName> {
+        use Expression::*;
+        use Statement::*;
+        match self {
+            IrNode::Statement(Substitution { var, .. }) => Some(var),
+            IrNode::Statement(Declaration { names, .. }) => Some(names.first()),
+            IrNode::Expression(Variable { name, .. }) => Some(name),
+            IrNode::Expression(Access { var, .. } | Update { var, .. }) => Some(var),
+            _ => None,
+        }
+    }
+
+    /// Returns the inferred type of the corresponding variable (if any).
+    #[must_use]
+    pub fn variable_type(&self) -> Option<&'a VariableType> {
+        self.meta().type_knowledge().variable_type()
+    }
+
+    /// Returns the value of the node if the node reduces to a constant.
+    #[must_use]
+    pub fn value(&self) -> Option<&'a ValueReduction> {
+        self.meta().value_knowledge().get_reduces_to()
+    }
+
+    /// Returns the inferred degree range of the node (if known).
+    #[must_use]
+    pub fn degree(&self) -> Option<&'a DegreeRange> {
+        self.meta().degree_knowledge().degree()
+    }
+
+    /// Returns the inferred value range of the node (if known).
+    #[must_use]
+    pub fn range(&self) -> Option<&'a ValueRange> {
+        self.meta().range_knowledge().range()
+    }
+
+    /// Returns the direct children of the node.
+    #[must_use]
+    pub fn children(&self) -> Vec<IrNode<'a>> {
+        match self {
+            IrNode::Statement(stmt) => statement_children(stmt),
+            IrNode::Expression(expr) => expression_children(expr),
+        }
+    }
+
+    /// Returns true if the node corresponds to the given file and byte offset.
+    fn contains(&self, file_id: FileID, offset: usize) -> bool {
+        let meta = self.meta();
+        meta.file_id() == Some(file_id) && meta.start() <= offset && offset < meta.end()
+    }
+}
+
+impl fmt::Display for IrNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IrNode::Statement(stmt) => write!(f, "{stmt}"),
+            IrNode::Expression(expr) => write!(f, "{expr}"),
+        }
+    }
+}
+
+impl fmt::Debug for IrNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IrNode::Statement(stmt) => write!(f, "{stmt:?}"),
+            IrNode::Expression(expr) => write!(f, "{expr:?}"),
+        }
+    }
+}
+
+fn statement_children(stmt: &Statement) -> Vec<IrNode<'_>> {
+    use Statement::*;
+    match stmt {
+        Declaration { dimensions, .. } => dimensions.iter().map(IrNode::Expression).collect(),
+        IfThenElse { cond, .. } => vec![IrNode::Expression(cond)],
+        Return { value, .. } => vec![IrNode::Expression(value)],
+        Substitution { rhe, .. } => vec![IrNode::Expression(rhe)],
+        ConstraintEquality { lhe, rhe, .. } => {
+            vec![IrNode::Expression(lhe), IrNode::Expression(rhe)]
+        }
+        LogCall { args, .. } => args
+            .iter()
+            .filter_map(|arg| match arg {
+                LogArgument::Expr(expr) => Some(IrNode::Expression(expr.as_ref())),
+                LogArgument::String(_) => None,
+            })
+            .collect(),
+        Assert { arg, .. } => vec![IrNode::Expression(arg)],
+    }
+}
+
+fn access_children(access: &[AccessType]) -> Vec<IrNode<'_>> {
+    access
+        .iter()
+        .filter_map(|access| match access {
+            AccessType::ArrayAccess(index) => Some(IrNode::Expression(index.as_ref())),
+            AccessType::ComponentAccess(_) => None,
+        })
+        .collect()
+}
+
+fn expression_children(expr: &Expression) -> Vec<IrNode<'_>> {
+    use Expression::*;
+    match expr {
+        InfixOp { lhe, rhe, .. } => vec![IrNode::Expression(lhe), IrNode::Expression(rhe)],
+        PrefixOp { rhe, .. } => vec![IrNode::Expression(rhe)],
+        SwitchOp { cond, if_true, if_false, .. } => vec![
+            IrNode::Expression(cond),
+            IrNode::Expression(if_true),
+            IrNode::Expression(if_false),
+        ],
+        Call { args, .. } => args.iter().map(IrNode::Expression).collect(),
+        InlineArray { values, .. } => values.iter().map(IrNode::Expression).collect(),
+        Access { access, .. } => access_children(access),
+        Update { access, rhe, .. } => {
+            let mut children = access_children(access);
+            children.push(IrNode::Expression(rhe));
+            children
+        }
+        Variable { .. } | Number(..) | Phi { .. } => Vec::new(),
+    }
+}
+
+impl Cfg {
+    /// Returns the innermost IR node (statement or expression) containing the
+    /// given byte offset in the given file. If several nodes share the same
+    /// smallest location, the first node is returned.
+    #[must_use]
+    pub fn find_node(&self, file_id: FileID, offset: usize) -> Option<IrNode<'_>> {
+        let mut result: Option<IrNode> = None;
+        let mut worklist = self
+            .iter()
+            .flat_map(|basic_block| basic_block.iter())
+            .map(IrNode::Statement)
+            .filter(|node| node.contains(file_id, offset))
+            .collect::<Vec<_>>();
+        // Visit nodes in source order to ensure that the result is stable.
+        worklist.reverse();
+        while let Some(node) = worklist.pop() {
+            let is_smaller = match &result {
+                Some(current) => {
+                    let (current, node) = (current.meta(), node.meta());
+                    node.end() - node.start() < current.end() - current.start()
+                }
+                None => true,
+            };
+            if is_smaller {
+                result = Some(node);
+            }
+            let mut children = node
+                .children()
+                .into_iter()
+                .filter(|child| child.contains(file_id, offset))
+                .collect::<Vec<_>>();
+            children.reverse();
+            worklist.extend(children);
+        }
+        result
+    }
+}
@@ -1,5 +1,5 @@
 use log::trace;
-use num_traits::Zero;
+use num_traits::{ToPrimitive, Zero};
 use std::collections::HashSet;
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -9,6 +9,7 @@ use circom_algebra::modular_arithmetic;
 use super::declarations::Declarations;
 use super::degree_meta::{Degree, DegreeEnvironment, DegreeMeta, DegreeRange};
 use super::ir::*;
+use super::range_meta::{RangeEnvironment, RangeMeta, ValueRange};
 use super::type_meta::TypeMeta;
 use super::value_meta::{ValueEnvironment, ValueMeta, ValueReduction};
 use super::variable_meta::{VariableMeta, VariableUse, VariableUses};
@@ -597,40 +598,63 @@ impl ValueMeta for Expression {
                 let value = FieldElement { value: value.clone() };
                 meta.value_knowledge_mut().set_reduces_to(value)
             }
-            Call { args, .. } => {
-                // TODO: Handle function calls.
+            Call { meta, name, args } => {
                 let mut result = false;
-                for arg in args {
+                for arg in args.iter_mut() {
                     result = result || arg.propagate_values(env);
                 }
+                // Function calls with constant arguments are evaluated once
+                // the values of all arguments are known.
+                if !result && !meta.value_knowledge().is_constant() {
+                    let args =
+                        args.iter().map(|arg| arg.value().cloned()).collect::<Option<Vec<_>>>();
+                    if let Some(value) = args.and_then(|args| env.evaluate_call(name, &args)) {
+                        result = meta.value_knowledge_mut().set_reduces_to(value);
+                    }
+                }
                 result
             }
-            InlineArray { values, .. } => {
-                // TODO: Handle inline arrays.
+            InlineArray { meta, values } => {
                 let mut result = false;
-                for value in values {
+                for value in values.iter_mut() {
                     result = result || value.propagate_values(env);
                 }
+                let values =
+                    values.iter().map(|value| value.value().cloned()).collect::<Option<Vec<_>>>();
+                if let Some(values) = values {
+                    result = result || meta.value_knowledge_mut().set_reduces_to(Array { values });
+                }
                 result
             }
-            Access { access, .. } => {
-                // TODO: Handle array values.
+            Access { meta, var, access } => {
                 let mut result = false;
                 for access in access.iter_mut() {
                     if let AccessType::ArrayAccess(index) = access {
                         result = result || index.propagate_values(env);
                     }
                 }
+                // Indexing a constant array with constant indices yields a constant.
+                let value = constant_indices(access)
+                    .and_then(|indices| env.get_variable(var)?.get(&indices).cloned());
+                if let Some(value) = value {
+                    result = result || meta.value_knowledge_mut().set_reduces_to(value);
+                }
                 result
             }
-            Update { access, rhe, .. } => {
-                // TODO: Handle array values.
+            Update { meta, var, access, rhe } => {
                 let mut result = rhe.propagate_values(env);
                 for access in access.iter_mut() {
                     if let AccessType::ArrayAccess(index) = access {
                         result = result || index.propagate_values(env);
                     }
                 }
+                // Updating a constant array with a constant value at constant
+                // indices yields a constant.
+                let value = constant_indices(access)
+                    .and_then(|indices| env.get_variable(var)?.update(&indices, rhe.value()?));
+                if let Some(value) = value {
+                    result = result || meta.value_knowledge_mut().set_reduces_to(value);
+                }
                 result
             }
             Phi { meta, args, .. } => {
@@ -666,6 +690,134 @@ impl ValueMeta for Expression {
     }
 }
 
+/// Returns the array indices of the given access if they are all constant.
+fn constant_indices(access: &[AccessType]) -> Option<Vec<usize>> {
+    access
+        .iter()
+        .map(|access| match access {
+            AccessType::ArrayAccess(index) => match index.value()? {
+                ValueReduction::FieldElement { value } => value.to_usize(),
+                _ => None,
+            },
+            AccessType::ComponentAccess(_) => None,
+        })
+        .collect()
+}
+
+impl RangeMeta for Expression {
+    fn propagate_ranges(&mut self, env: &mut RangeEnvironment) -> bool {
+        let mut result = false;
+
+        use Expression::*;
+        let range = match self {
+            InfixOp { lhe, infix_op, rhe, .. } => {
+                result |= lhe.propagate_ranges(env);
+                result |= rhe.propagate_ranges(env);
+                match (lhe.range(), rhe.range()) {
+                    (Some(lhr), Some(rhr)) => Some(lhr.infix_op(infix_op, rhr).reduce(env.prime())),
+                    _ => None,
+                }
+            }
+            PrefixOp { prefix_op, rhe, .. } => {
+                result |= rhe.propagate_ranges(env);
+                rhe.range().map(|range| range.prefix_op(prefix_op).reduce(env.prime()))
+            }
+            SwitchOp { cond, if_true, if_false, .. } => {
+                result |= cond.propagate_ranges(env);
+                result |= if_true.propagate_ranges(env);
+                result |= if_false.propagate_ranges(env);
+                match (if_true.range(), if_false.range()) {
+                    (Some(true_range), Some(false_range)) => Some(true_range.join(false_range)),
+                    _ => None,
+                }
+            }
+            Variable { name, .. } => env.range(name).cloned(),
+            Number(_, value) => Some(ValueRange::from_field_element(value, env.prime())),
+            Call { args, .. } => {
+                // Function calls with constant arguments are evaluated during
+                // value propagation. Other calls are unbounded.
+                for arg in args.iter_mut() {
+                    result |= arg.propagate_ranges(env);
+                }
+                Some(ValueRange::unbounded())
+            }
+            InlineArray { values, .. } => {
+                // The range of an array is the join of the ranges of all elements.
+                for value in values.iter_mut() {
+                    result |= value.propagate_ranges(env);
+                }
+                values
+                    .iter()
+                    .map(|value| value.range().cloned())
+                    .collect::<Option<Vec<_>>>()
+                    .and_then(|ranges| ranges.into_iter().reduce(|lhs, rhs| lhs.join(&rhs)))
+            }
+            Access { var, access, .. } => {
+                // Array accesses are ignored when determining the range of a
+                // variable.
+                for access in access.iter_mut() {
+                    if let AccessType::ArrayAccess(index) = access {
+                        result |= index.propagate_ranges(env);
+                    }
+                }
+                let component_access = access
+                    .iter()
+                    .position(|access| matches!(access, AccessType::ComponentAccess(_)));
+                match component_access {
+                    Some(index) if env.is_component(var) => {
+                        let AccessType::ComponentAccess(signal) = &access[index] else {
+                            unreachable!();
+                        };
+                        env.component_signal_range(var, &access[..index], signal)
+                            .or(Some(ValueRange::unbounded()))
+                    }
+                    // This is either a component signal or signal tag access.
+                    Some(_) => Some(ValueRange::unbounded()),
+                    None => env.range(var).cloned(),
+                }
+            }
+            Update { var, access, rhe, .. } => {
+                result |= rhe.propagate_ranges(env);
+                for access in access.iter_mut() {
+                    if let AccessType::ArrayAccess(index) = access {
+                        result |= index.propagate_ranges(env);
+                    }
+                }
+                if env.is_local(var) {
+                    // The range of a local array is the join of the ranges of
+                    // all elements. Uninitialized elements are zero.
+                    let previous =
+                        env.range(var).cloned().unwrap_or_else(|| ValueRange::constant(0.into()));
+                    rhe.range().map(|range| range.join(&previous))
+                } else {
+                    // Signal arrays are handled at the statement level.
+                    rhe.range().cloned()
+                }
+            }
+            Phi { args, .. } => {
+                // Loop variables are handled at the statement level.
+                args.iter()
+                    .filter_map(|arg| env.range(arg))
+                    .cloned()
+                    .reduce(|lhs, rhs| lhs.join(&rhs))
+            }
+        };
+        // If the value of the node is known, the range contains a single value.
+        let range = match self.value() {
+            Some(value) => Some(ValueRange::from_value(value, env.prime())),
+            None => range,
+        };
+        if let Some(range) = range {
+            result |= self.meta_mut().range_knowledge_mut().set_range(&range);
+        }
+        result
+    }
+
+    fn range(&self) -> Option<&ValueRange> {
+        self.meta().range_knowledge().range()
+    }
+}
+
 impl ExpressionInfixOpcode {
     fn propagate_degrees(
         &self,
@@ -701,7 +853,7 @@ impl ExpressionInfixOpcode {
         }
     }
 
-    fn propagate_values(
+    pub(crate) fn propagate_values(
         &self,
         lhv: Option<&ValueReduction>,
         rhv: Option<&ValueReduction>,
@@ -817,7 +969,7 @@ impl ExpressionPrefixOpcode {
         }
     }
 
-    fn propagate_values(
+    pub(crate) fn propagate_values(
         &self,
         rhe: Option<&ValueReduction>,
         env: &ValueEnvironment,
@@ -853,7 +1005,8 @@ impl ExpressionPrefixOpcode {
                     _ => None,
                 }
             }
-            None => None,
+            // Prefix operations are not defined on arrays.
+            Some(Array { .. }) | None => None,
         }
     }
 }
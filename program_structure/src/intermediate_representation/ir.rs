@@ -5,6 +5,7 @@ use crate::file_definition::{FileID, FileLocation};
 use crate::nonempty_vec::NonEmptyVec;
 
 use super::degree_meta::DegreeKnowledge;
+use super::range_meta::RangeKnowledge;
 use super::type_meta::TypeKnowledge;
 use super::value_meta::ValueKnowledge;
 use super::variable_meta::VariableKnowledge;
@@ -17,6 +18,7 @@ pub struct Meta {
     pub location: FileLocation,
     pub file_id: Option<FileID>,
     degree_knowledge: DegreeKnowledge,
+    range_knowledge: RangeKnowledge,
     type_knowledge: TypeKnowledge,
     value_knowledge: ValueKnowledge,
     variable_knowledge: VariableKnowledge,
@@ -29,6 +31,7 @@ impl Meta {
             location: location.clone(),
             file_id: *file_id,
             degree_knowledge: DegreeKnowledge::default(),
+            range_knowledge: RangeKnowledge::default(),
             type_knowledge: TypeKnowledge::default(),
             value_knowledge: ValueKnowledge::default(),
             variable_knowledge: VariableKnowledge::default(),
@@ -60,6 +63,11 @@ impl Meta {
         &self.degree_knowledge
     }
 
+    #[must_use]
+    pub fn range_knowledge(&self) -> &RangeKnowledge {
+        &self.range_knowledge
+    }
+
     #[must_use]
     pub fn type_knowledge(&self) -> &TypeKnowledge {
         &self.type_knowledge
@@ -80,6 +88,11 @@ impl Meta {
         &mut self.degree_knowledge
     }
 
+    #[must_use]
+    pub fn range_knowledge_mut(&mut self) -> &mut RangeKnowledge {
+        &mut self.range_knowledge
+    }
+
     #[must_use]
     pub fn type_knowledge_mut(&mut self) -> &mut TypeKnowledge {
         &mut self.type_knowledge
@@ -103,7 +116,6 @@ impl std::hash::Hash for Meta {
     {
         self.location.hash(state);
         self.file_id.hash(state);
-        state.finish();
     }
 }
 
@@ -1,6 +1,7 @@
 pub mod declarations;
 pub mod degree_meta;
 pub mod errors;
+pub mod range_meta;
 pub mod type_meta;
 pub mod value_meta;
 pub mod variable_meta;
@@ -0,0 +1,984 @@
+use log::trace;
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::constants::UsefulConstants;
+
+use super::declarations::Declarations;
+use super::value_meta::{ValueMeta, ValueReduction};
+use super::{
+    AccessType, AssignOp, Expression, ExpressionInfixOpcode, ExpressionPrefixOpcode, Statement,
+    VariableName, VariableType,
+};
+
+/// The maximum size (in bits) of bounds computed by the analysis. Bounds
+/// larger than this are always outside the field, so there is no point in
+/// computing them exactly.
+const MAX_BOUND_SIZE: usize = 1024;
+
+/// The number of times the range of a variable may grow before the range is
+/// widened to the full field. This ensures that the analysis terminates on
+/// loops.
+const MAX_RANGE_UPDATES: usize = 8;
+
+/// Templates from Circomlib whose output signal `out` is constrained to be
+/// boolean.
+const BOOLEAN_OUTPUTS: [&str; 8] = [
+    "Num2Bits",
+    "Num2Bits_strict",
+    "LessThan",
+    "LessEqThan",
+    "GreaterThan",
+    "GreaterEqThan",
+    "IsZero",
+    "IsEqual",
+];
+
+/// An inclusive range of integers. Field elements are identified with integers
+/// in the range (-p/2, p/2], which is the representation Circom uses when
+/// comparing field elements.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum ValueRange {
+    Bounded { lower: BigInt, upper: BigInt },
+    Unbounded,
+}
+
+impl ValueRange {
+    /// Returns the range `[lower, upper]`.
+    #[must_use]
+    pub fn new(lower: BigInt, upper: BigInt) -> ValueRange {
+        debug_assert!(lower <= upper);
+        ValueRange::Bounded { lower, upper }
+    }
+
+    #[must_use]
+    pub fn unbounded() -> ValueRange {
+        ValueRange::Unbounded
+    }
+
+    /// Returns the range containing the single value `value`.
+    #[must_use]
+    pub fn constant(value: BigInt) -> ValueRange {
+        ValueRange::new(value.clone(), value)
+    }
+
+    /// Returns the range `[0, 1]`.
+    #[must_use]
+    pub fn boolean() -> ValueRange {
+        ValueRange::new(BigInt::zero(), BigInt::one())
+    }
+
+    /// Returns the range of values representable using `size` bits.
+    #[must_use]
+    pub fn bits(size: usize) -> ValueRange {
+        if size > MAX_BOUND_SIZE {
+            return ValueRange::Unbounded;
+        }
+        ValueRange::new(BigInt::zero(), (BigInt::one() << size) - 1)
+    }
+
+    /// Returns the range containing the given field element.
+    #[must_use]
+    pub fn from_field_element(value: &BigInt, prime: &BigInt) -> ValueRange {
+        let mut value = value % prime;
+        if value.is_negative() {
+            value += prime;
+        }
+        if value > half(prime) {
+            value -= prime;
+        }
+        ValueRange::constant(value)
+    }
+
+    /// Returns the range containing the given value. (For arrays, this is the
+    /// range containing all elements.)
+    #[must_use]
+    pub fn from_value(value: &ValueReduction, prime: &BigInt) -> ValueRange {
+        match value {
+            ValueReduction::Boolean { value } => ValueRange::constant(u8::from(*value).into()),
+            ValueReduction::FieldElement { value } => ValueRange::from_field_element(value, prime),
+            // The range of an array is the join of the ranges of all elements.
+            ValueReduction::Array { values } => values
+                .iter()
+                .map(|value| ValueRange::from_value(value, prime))
+                .reduce(|lhs, rhs| lhs.join(&rhs))
+                .unwrap_or_else(ValueRange::unbounded),
+        }
+    }
+
+    #[must_use]
+    pub fn lower(&self) -> Option<&BigInt> {
+        match self {
+            ValueRange::Bounded { lower, .. } => Some(lower),
+            ValueRange::Unbounded => None,
+        }
+    }
+
+    #[must_use]
+    pub fn upper(&self) -> Option<&BigInt> {
+        match self {
+            ValueRange::Bounded { upper, .. } => Some(upper),
+            ValueRange::Unbounded => None,
+        }
+    }
+
+    #[must_use]
+    pub fn is_bounded(&self) -> bool {
+        matches!(self, ValueRange::Bounded { .. })
+    }
+
+    /// Returns true if the range only contains non-negative values.
+    #[must_use]
+    pub fn is_non_negative(&self) -> bool {
+        matches!(self, ValueRange::Bounded { lower, .. } if !lower.is_negative())
+    }
+
+    /// Returns true if the range only contains the values 0 and 1.
+    #[must_use]
+    pub fn is_boolean(&self) -> bool {
+        self.is_non_negative() && self.upper().map_or(false, |upper| upper <= &BigInt::one())
+    }
+
+    /// Returns the number of bits required to represent the values in the
+    /// range, or `None` if the range is unbounded or contains negative values.
+    #[must_use]
+    pub fn bit_width(&self) -> Option<usize> {
+        match self {
+            ValueRange::Bounded { lower, upper } if !lower.is_negative() => Some(upper.bits()),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn contains(&self, value: &BigInt) -> bool {
+        match self {
+            ValueRange::Bounded { lower, upper } => lower <= value && value <= upper,
+            ValueRange::Unbounded => true,
+        }
+    }
+
+    /// Returns the smallest range containing both ranges.
+    #[must_use]
+    pub fn join(&self, other: &ValueRange) -> ValueRange {
+        use ValueRange::*;
+        match (self, other) {
+            (Bounded { lower: lhl, upper: lhu }, Bounded { lower: rhl, upper: rhu }) => {
+                ValueRange::new(min(lhl, rhl).clone(), max(lhu, rhu).clone())
+            }
+            _ => Unbounded,
+        }
+    }
+
+    /// Returns the intersection of the two ranges, or `None` if the
+    /// intersection is empty.
+    #[must_use]
+    pub fn meet(&self, other: &ValueRange) -> Option<ValueRange> {
+        use ValueRange::*;
+        match (self, other) {
+            (Bounded { lower: lhl, upper: lhu }, Bounded { lower: rhl, upper: rhu }) => {
+                let (lower, upper) = (max(lhl, rhl), min(lhu, rhu));
+                (lower <= upper).then(|| ValueRange::new(lower.clone(), upper.clone()))
+            }
+            (Bounded { .. }, Unbounded) => Some(self.clone()),
+            (Unbounded, _) => Some(other.clone()),
+        }
+    }
+
+    /// Returns true if the range is contained in (-p/2, p/2]. If this is the
+    /// case, the integer values in the range are not reduced modulo `p`.
+    #[must_use]
+    pub fn fits_in_field(&self, prime: &BigInt) -> bool {
+        match self {
+            ValueRange::Bounded { lower, upper } => {
+                let half = half(prime);
+                -&half <= *lower && upper <= &half
+            }
+            ValueRange::Unbounded => false,
+        }
+    }
+
+    /// Returns the range of the result when the values in the range are
+    /// reduced modulo `p`. This is unbounded if any value in the range
+    /// overflows.
+    #[must_use]
+    pub fn reduce(&self, prime: &BigInt) -> ValueRange {
+        if self.fits_in_field(prime) {
+            self.clone()
+        } else {
+            ValueRange::Unbounded
+        }
+    }
+
+    /// Returns the range of `self op other` using integer arithmetic. (That
+    /// is, the result is not reduced modulo `p`.) Operations which cannot be
+    /// evaluated using integer arithmetic (like field division) are unbounded.
+    #[must_use]
+    pub fn infix_op(&self, op: &ExpressionInfixOpcode, other: &ValueRange) -> ValueRange {
+        use ExpressionInfixOpcode::*;
+        match op {
+            Add => self.add(other),
+            Sub => self.infix_sub(other),
+            Mul => self.mul(other),
+            Pow => self.pow(other),
+            Div => ValueRange::Unbounded,
+            IntDiv => self.int_div(other),
+            Mod => self.modulo(other),
+            ShiftL => self.shift_left(other),
+            ShiftR => self.shift_right(other),
+            BitAnd => self.bit_and(other),
+            BitOr | BitXor => self.bit_or(other),
+            Lesser | Greater | LesserEq | GreaterEq | Eq | NotEq | BoolOr | BoolAnd => {
+                ValueRange::boolean()
+            }
+        }
+    }
+
+    /// Returns the range of `op self` using integer arithmetic.
+    #[must_use]
+    pub fn prefix_op(&self, op: &ExpressionPrefixOpcode) -> ValueRange {
+        use ExpressionPrefixOpcode::*;
+        match op {
+            Sub => self.prefix_sub(),
+            BoolNot => ValueRange::boolean(),
+            Complement => ValueRange::Unbounded,
+        }
+    }
+
+    #[must_use]
+    pub fn add(&self, other: &ValueRange) -> ValueRange {
+        match (self.bounds(), other.bounds()) {
+            (Some((lhl, lhu)), Some((rhl, rhu))) => ValueRange::new(lhl + rhl, lhu + rhu),
+            _ => ValueRange::Unbounded,
+        }
+    }
+
+    #[must_use]
+    pub fn infix_sub(&self, other: &ValueRange) -> ValueRange {
+        self.add(&other.prefix_sub())
+    }
+
+    #[must_use]
+    pub fn prefix_sub(&self) -> ValueRange {
+        match self.bounds() {
+            Some((lower, upper)) => ValueRange::new(-upper, -lower),
+            None => ValueRange::Unbounded,
+        }
+    }
+
+    #[must_use]
+    pub fn mul(&self, other: &ValueRange) -> ValueRange {
+        match (self.bounds(), other.bounds()) {
+            (Some((lhl, lhu)), Some((rhl, rhu))) => {
+                let products = [lhl * rhl, lhl * rhu, lhu * rhl, lhu * rhu];
+                // These unwraps are safe since the array is non-empty.
+                let lower = products.iter().min().unwrap().clone();
+                let upper = products.iter().max().unwrap().clone();
+                ValueRange::new(lower, upper)
+            }
+            _ => ValueRange::Unbounded,
+        }
+    }
+
+    #[must_use]
+    pub fn pow(&self, other: &ValueRange) -> ValueRange {
+        if !self.is_non_negative() || !other.is_non_negative() {
+            return ValueRange::Unbounded;
+        }
+        let (Some((lhl, lhu)), Some((rhl, rhu))) = (self.bounds(), other.bounds()) else {
+            return ValueRange::Unbounded;
+        };
+        let Some(exp) = rhu.to_usize() else {
+            return ValueRange::Unbounded;
+        };
+        if lhu.bits().saturating_mul(exp) > MAX_BOUND_SIZE {
+            return ValueRange::Unbounded;
+        }
+        // Since 0^0 = 1 we use 0 as the lower bound if the base may be 0.
+        let lower = match rhl.to_usize() {
+            Some(exp) if !lhl.is_zero() => num_traits::pow(lhl.clone(), exp),
+            _ => BigInt::zero(),
+        };
+        ValueRange::new(lower, num_traits::pow(lhu.clone(), exp))
+    }
+
+    #[must_use]
+    pub fn int_div(&self, other: &ValueRange) -> ValueRange {
+        if !self.is_non_negative() || !other.is_non_negative() {
+            return ValueRange::Unbounded;
+        }
+        let (Some((lhl, lhu)), Some((rhl, rhu))) = (self.bounds(), other.bounds()) else {
+            return ValueRange::Unbounded;
+        };
+        if rhl.is_zero() {
+            // Division by zero is an error, so the result is at most `lhu`.
+            ValueRange::new(BigInt::zero(), lhu.clone())
+        } else {
+            ValueRange::new(lhl / rhu, lhu / rhl)
+        }
+    }
+
+    #[must_use]
+    pub fn modulo(&self, other: &ValueRange) -> ValueRange {
+        if !self.is_non_negative() || !other.is_non_negative() {
+            return ValueRange::Unbounded;
+        }
+        let (Some((_, lhu)), Some((rhl, rhu))) = (self.bounds(), other.bounds()) else {
+            return ValueRange::Unbounded;
+        };
+        if rhl.is_zero() {
+            ValueRange::Unbounded
+        } else if lhu < rhl {
+            self.clone()
+        } else {
+            ValueRange::new(BigInt::zero(), min(lhu.clone(), rhu - 1))
+        }
+    }
+
+    #[must_use]
+    pub fn shift_left(&self, other: &ValueRange) -> ValueRange {
+        if !self.is_non_negative() || !other.is_non_negative() {
+            return ValueRange::Unbounded;
+        }
+        let (Some((lhl, lhu)), Some((rhl, rhu))) = (self.bounds(), other.bounds()) else {
+            return ValueRange::Unbounded;
+        };
+        match (rhl.to_usize(), rhu.to_usize()) {
+            (Some(rhl), Some(rhu)) if lhu.bits() + rhu <= MAX_BOUND_SIZE => {
+                ValueRange::new(lhl << rhl, lhu << rhu)
+            }
+            _ => ValueRange::Unbounded,
+        }
+    }
+
+    #[must_use]
+    pub fn shift_right(&self, other: &ValueRange) -> ValueRange {
+        if !self.is_non_negative() || !other.is_non_negative() {
+            return ValueRange::Unbounded;
+        }
+        let (Some((lhl, lhu)), Some((rhl, rhu))) = (self.bounds(), other.bounds()) else {
+            return ValueRange::Unbounded;
+        };
+        // Shifting by more than the size of the value always produces 0.
+        let shift = |value: &BigInt, size: &BigInt| match size.to_usize() {
+            Some(size) if size <= value.bits() => value >> size,
+            _ => BigInt::zero(),
+        };
+        ValueRange::new(shift(lhl, rhu), shift(lhu, rhl))
+    }
+
+    #[must_use]
+    pub fn bit_and(&self, other: &ValueRange) -> ValueRange {
+        if !self.is_non_negative() || !other.is_non_negative() {
+            return ValueRange::Unbounded;
+        }
+        match (self.upper(), other.upper()) {
+            (Some(lhu), Some(rhu)) => ValueRange::new(BigInt::zero(), min(lhu, rhu).clone()),
+            _ => ValueRange::Unbounded,
+        }
+    }
+
+    /// Returns the range of both `self | other` and `self ^ other`.
+    #[must_use]
+    pub fn bit_or(&self, other: &ValueRange) -> ValueRange {
+        match (self.bit_width(), other.bit_width()) {
+            (Some(lhs), Some(rhs)) => ValueRange::bits(max(lhs, rhs)),
+            _ => ValueRange::Unbounded,
+        }
+    }
+
+    fn bounds(&self) -> Option<(&BigInt, &BigInt)> {
+        match self {
+            ValueRange::Bounded { lower, upper } => Some((lower, upper)),
+            ValueRange::Unbounded => None,
+        }
+    }
+}
+
+/// Returns (p - 1)/2, the largest value in (-p/2, p/2].
+fn half(prime: &BigInt) -> BigInt {
+    prime / 2
+}
+
+/// Formats large powers of two (and powers of two minus one) as `2^n` (and
+/// `2^n - 1`) to keep bit size constraints readable.
+fn fmt_bound(value: &BigInt, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    let size = value.abs().bits();
+    if size <= 16 {
+        write!(f, "{value}")
+    } else if value.abs() == BigInt::one() << (size - 1) {
+        write!(f, "{}2^{}", if value.is_negative() { "-" } else { "" }, size - 1)
+    } else if value == &((BigInt::one() << size) - 1) {
+        write!(f, "2^{size} - 1")
+    } else {
+        write!(f, "{value}")
+    }
+}
+
+impl fmt::Display for ValueRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ValueRange::Bounded { lower, upper } => {
+                write!(f, "[")?;
+                fmt_bound(lower, f)?;
+                write!(f, ", ")?;
+                fmt_bound(upper, f)?;
+                write!(f, "]")
+            }
+            ValueRange::Unbounded => write!(f, "(-p/2, p/2]"),
+        }
+    }
+}
+
+impl fmt::Debug for ValueRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{self}")
+    }
+}
+
+/// Tracks a component instantiation `var[i]...[k] = T(...)`.
+#[derive(Clone)]
+struct Component {
+    access: Vec<AccessType>,
+    name: String,
+    args: Vec<Option<ValueReduction>>,
+}
+
+/// This type is used to track value ranges of individual variables and
+/// signals during range propagation.
+///
+/// Before ranges are propagated, the environment is seeded with range
+/// constraints obtained from the template (see `add_constraints`). Signals
+/// assigned using `<==` take the intersection of the assigned range and the
+/// seeded range, while signals assigned using `<--` only take the seeded range
+/// (since the assigned value is not constrained).
+#[derive(Clone)]
+pub struct RangeEnvironment {
+    prime: BigInt,
+    prime_size: usize,
+    var_types: HashMap<VariableName, VariableType>,
+    ranges: HashMap<VariableName, ValueRange>,
+    updates: HashMap<VariableName, usize>,
+    // Range constraints on signals obtained from the template.
+    seeds: HashMap<VariableName, ValueRange>,
+    // Signals assigned in the template.
+    assigned_signals: HashSet<VariableName>,
+    // Component instantiations, keyed by component name.
+    components: HashMap<VariableName, Vec<Component>>,
+    // Assignments on the form `x = y + c`, where `c` is a positive constant.
+    increments: HashMap<VariableName, (VariableName, BigInt)>,
+    // Loop variables `v` from loop conditions `v < e` and `v <= e`, mapped to
+    // the largest value of `v` for which the loop body is executed (if known).
+    loop_bounds: HashMap<VariableName, Option<Option<BigInt>>>,
+}
+
+impl RangeEnvironment {
+    #[must_use]
+    pub fn new(constants: &UsefulConstants, declarations: &Declarations) -> RangeEnvironment {
+        RangeEnvironment {
+            prime: constants.prime().clone(),
+            prime_size: constants.prime_size(),
+            var_types: declarations
+                .iter()
+                .map(|(name, declaration)| (name.clone(), declaration.variable_type().clone()))
+                .collect(),
+            ranges: HashMap::new(),
+            updates: HashMap::new(),
+            seeds: HashMap::new(),
+            assigned_signals: HashSet::new(),
+            components: HashMap::new(),
+            increments: HashMap::new(),
+            loop_bounds: HashMap::new(),
+        }
+    }
+
+    /// Returns the prime used.
+    #[must_use]
+    pub fn prime(&self) -> &BigInt {
+        &self.prime
+    }
+
+    /// Extends the range of the given variable to include the given range.
+    /// Returns true if the range of the variable was updated.
+    pub fn set_range(&mut self, var: &VariableName, range: &ValueRange) -> bool {
+        let new_range = match self.ranges.get(var) {
+            Some(old_range) => old_range.join(range),
+            None => range.clone(),
+        };
+        if self.ranges.get(var) == Some(&new_range) {
+            return false;
+        }
+        let updates = self.updates.entry(var.clone()).or_default();
+        *updates += 1;
+        let new_range =
+            if *updates > MAX_RANGE_UPDATES { ValueRange::Unbounded } else { new_range };
+        trace!("setting range of `{var:?}` to {new_range}");
+        self.ranges.insert(var.clone(), new_range);
+        true
+    }
+
+    /// Gets the range of the given variable. Returns `None` if no range has
+    /// been computed for the variable yet.
+    #[must_use]
+    pub fn range(&self, var: &VariableName) -> Option<&ValueRange> {
+        self.ranges.get(var)
+    }
+
+    /// Returns the range constraint on the given signal (if any).
+    #[must_use]
+    pub fn seed(&self, var: &VariableName) -> Option<&ValueRange> {
+        self.seeds.get(&var.without_version())
+    }
+
+    #[must_use]
+    pub fn is_local(&self, var: &VariableName) -> bool {
+        matches!(self.var_types.get(var), Some(VariableType::Local))
+    }
+
+    #[must_use]
+    pub fn is_signal(&self, var: &VariableName) -> bool {
+        matches!(self.var_types.get(var), Some(VariableType::Signal(_, _)))
+    }
+
+    #[must_use]
+    pub fn is_component(&self, var: &VariableName) -> bool {
+        matches!(
+            self.var_types.get(var),
+            Some(VariableType::Component | VariableType::AnonymousComponent)
+        )
+    }
+
+    /// Returns true if the signal is assigned somewhere in the template.
+    #[must_use]
+    pub fn is_assigned(&self, var: &VariableName) -> bool {
+        self.assigned_signals.contains(&var.without_version())
+    }
+
+    /// Returns the range of the component signal `var[i]...[k].signal` (if
+    /// known).
+    #[must_use]
+    pub fn component_signal_range(
+        &self,
+        var: &VariableName,
+        access: &[AccessType],
+        signal: &str,
+    ) -> Option<ValueRange> {
+        let component = self.component(var, access)?;
+        (signal == "out" && BOOLEAN_OUTPUTS.contains(&component.name.as_str()))
+            .then(ValueRange::boolean)
+    }
+
+    /// Returns the range of the phi expression `var = φ(args...)`.
+    ///
+    /// If `var` is the loop variable of a loop with condition `var < e`, and
+    /// the arguments assigned in the loop body are on the form `var + c`, the
+    /// range of `var` is bounded by the upper bound of `e` (plus `c`).
+    /// Otherwise, the range is the join of the argument ranges.
+    #[must_use]
+    pub fn phi_range(&self, var: &VariableName, args: &[VariableName]) -> Option<ValueRange> {
+        let step = |arg: &VariableName| match self.increments.get(arg) {
+            Some((base, step)) if base == var => Some(step),
+            _ => None,
+        };
+        let join = |args: &mut dyn Iterator<Item = &VariableName>| {
+            args.filter_map(|arg| self.range(arg)).cloned().reduce(|lhs, rhs| lhs.join(&rhs))
+        };
+        match self.loop_bounds.get(var) {
+            // The loop bound is known. Since the increments are only executed
+            // if `var <= bound`, the result is bounded by `bound + c`.
+            Some(Some(Some(bound))) => {
+                let initial = join(&mut args.iter().filter(|arg| step(arg).is_none()))?;
+                let (Some(lower), Some(upper)) = (initial.lower(), initial.upper()) else {
+                    return Some(ValueRange::Unbounded);
+                };
+                let upper =
+                    args.iter().filter_map(step).map(|step| bound + step).fold(upper.clone(), max);
+                Some(ValueRange::new(lower.clone(), upper).reduce(&self.prime))
+            }
+            // The loop bound has not been computed yet, so we ignore increments.
+            Some(None) => join(&mut args.iter().filter(|arg| step(arg).is_none())),
+            // This is not a loop variable, or the loop bound is unbounded.
+            Some(Some(None)) | None => join(&mut args.iter()),
+        }
+    }
+
+    /// Records the loop condition of a loop header. This must be called before
+    /// ranges are propagated.
+    pub fn add_loop_condition(&mut self, cond: &Expression) {
+        if let Some((var, _, _)) = loop_condition(cond) {
+            self.loop_bounds.insert(var.clone(), None);
+        }
+    }
+
+    /// Updates the loop bound from the given loop condition, using the range
+    /// computed for the condition. Returns true if the bound was updated.
+    pub fn update_loop_bound(&mut self, cond: &Expression) -> bool {
+        let Some((var, op, bound)) = loop_condition(cond) else {
+            return false;
+        };
+        let Some(range) = bound.range() else {
+            return false;
+        };
+        let bound = range.upper().map(|upper| match op {
+            ExpressionInfixOpcode::Lesser => upper - 1,
+            _ => upper.clone(),
+        });
+        match self.loop_bounds.get(var) {
+            Some(Some(old_bound)) if old_bound == &bound => false,
+            _ => {
+                trace!("setting loop bound of `{var:?}` to {bound:?}");
+                self.loop_bounds.insert(var.clone(), Some(bound));
+                true
+            }
+        }
+    }
+
+    /// Collects range constraints on signals from the given statements. The
+    /// following constraints are supported:
+    ///
+    ///   1. `x` is assigned to the input of `Num2Bits(n)`, which constrains
+    ///      `x` to `n` bits.
+    ///   2. `x` is constrained to be boolean using a constraint of the form
+    ///      `x * (x - 1) === 0`.
+    ///   3. `x` is declared with the tag `binary`.
+    ///   4. `x` is declared with the tag `maxbit`, and `x.maxbit` is assigned
+    ///      a constant value `n`.
+    ///
+    /// (Note that tags are not enforced by constraints. They are assumed to be
+    /// upheld by the template assigning the signal.)
+    ///
+    /// This must be called after values are propagated, but before ranges are
+    /// propagated.
+    pub fn add_constraints<'a, I>(&mut self, stmts: I)
+    where
+        I: IntoIterator<Item = &'a Statement>,
+    {
+        let stmts = stmts.into_iter().collect::<Vec<_>>();
+        for stmt in &stmts {
+            self.add_component(stmt);
+        }
+        for stmt in &stmts {
+            self.add_constraint(stmt);
+        }
+    }
+
+    fn add_component(&mut self, stmt: &Statement) {
+        use Expression::*;
+        if let Statement::Substitution { var, op: AssignOp::AssignLocalOrComponent, rhe, .. } = stmt
+        {
+            if !self.is_component(var) {
+                return;
+            }
+            let (access, rhe) = match rhe {
+                Update { access, rhe, .. } => (access.clone(), rhe.as_ref()),
+                _ => (Vec::new(), rhe),
+            };
+            if let Call { name, args, .. } = rhe {
+                let args = args.iter().map(|arg| arg.value().cloned()).collect();
+                let component = Component { access, name: name.clone(), args };
+                self.components.entry(var.without_version()).or_default().push(component);
+            }
+        }
+    }
+
+    fn add_constraint(&mut self, stmt: &Statement) {
+        use AccessType::*;
+        use Expression::*;
+        use Statement::*;
+        match stmt {
+            Declaration { names, var_type: VariableType::Signal(_, tags), .. }
+                if tags.iter().any(|tag| tag == "binary") =>
+            {
+                for name in names.iter() {
+                    self.add_seed(name, &ValueRange::boolean());
+                }
+            }
+            Substitution { var, rhe, .. } if self.is_local(var) => {
+                self.add_increment(var, rhe);
+            }
+            Substitution { var, op, rhe, .. } if self.is_signal(var) => {
+                if let Update { access, rhe, .. } = rhe {
+                    if let Some(ComponentAccess(tag)) = access.last() {
+                        // This is a tag assignment `var.tag = value`.
+                        if let (true, Some(ValueReduction::FieldElement { value })) =
+                            (tag == "maxbit", rhe.value())
+                        {
+                            if let Some(size) = value.to_usize() {
+                                self.add_seed(var, &ValueRange::bits(size));
+                            }
+                        }
+                        return;
+                    }
+                }
+                if matches!(op, AssignOp::AssignSignal | AssignOp::AssignConstraintSignal) {
+                    self.assigned_signals.insert(var.without_version());
+                }
+            }
+            Substitution {
+                var,
+                op: AssignOp::AssignConstraintSignal,
+                rhe: Update { access, rhe, .. },
+                ..
+            } if self.is_component(var) => {
+                if let Variable { name, .. } = rhe.as_ref() {
+                    self.add_component_input(var, access, name);
+                }
+            }
+            ConstraintEquality { lhe, rhe, .. } => {
+                for (lhe, rhe) in [(lhe, rhe), (rhe, lhe)] {
+                    if let (Access { var, access, .. }, Variable { name, .. }) = (lhe, rhe) {
+                        if self.is_component(var) {
+                            self.add_component_input(var, access, name);
+                        }
+                    }
+                    if let Some(name) = boolean_constraint(lhe, rhe) {
+                        if self.is_signal(name) {
+                            self.add_seed(name, &ValueRange::boolean());
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles component input assignments `var[i]...[k].in <== name`, where
+    /// `var` is a `Num2Bits` component.
+    fn add_component_input(
+        &mut self,
+        var: &VariableName,
+        access: &[AccessType],
+        name: &VariableName,
+    ) {
+        let Some((AccessType::ComponentAccess(signal), access)) = access.split_last() else {
+            return;
+        };
+        if signal != "in" || !self.is_signal(name) {
+            return;
+        }
+        let Some(component) = self.component(var, access) else {
+            return;
+        };
+        if component.name != "Num2Bits" || component.args.len() != 1 {
+            return;
+        }
+        if let Some(ValueReduction::FieldElement { value }) = &component.args[0] {
+            // If the size is at least the prime size, the input is not
+            // constrained.
+            let range = match value.to_usize() {
+                Some(size) if size < self.prime_size => ValueRange::bits(size),
+                _ => return,
+            };
+            self.add_seed(name, &range.reduce(&self.prime));
+        }
+    }
+
+    fn add_seed(&mut self, var: &VariableName, range: &ValueRange) {
+        if !range.is_bounded() {
+            return;
+        }
+        let var = var.without_version();
+        let seed = match self.seeds.get(&var) {
+            // If the constraints are inconsistent, the template is not
+            // satisfiable and we simply keep the first constraint.
+            Some(seed) => seed.meet(range).unwrap_or_else(|| seed.clone()),
+            None => range.clone(),
+        };
+        trace!("adding range constraint {seed} on `{var:?}`");
+        self.seeds.insert(var, seed);
+    }
+
+    /// Returns the component instantiation corresponding to `var[i]...[k]`.
+    /// If the access does not match an instantiation syntactically, and all
+    /// instantiations of `var` are identical, that instantiation is returned.
+    fn component(&self, var: &VariableName, access: &[AccessType]) -> Option<&Component> {
+        let components = self.components.get(&var.without_version())?;
+        if let Some(component) = components.iter().find(|component| component.access == access) {
+            return Some(component);
+        }
+        let first = components.first()?;
+        components
+            .iter()
+            .all(|component| component.name == first.name && component.args == first.args)
+            .then_some(first)
+    }
+
+    /// Records assignments on the form `var = x + c`, where `c` is a positive
+    /// constant.
+    fn add_increment(&mut self, var: &VariableName, rhe: &Expression) {
+        use Expression::*;
+        use ExpressionInfixOpcode::*;
+        if let InfixOp { lhe, infix_op: Add, rhe, .. } = rhe {
+            for (lhe, rhe) in [(lhe, rhe), (rhe, lhe)] {
+                if let (Variable { name, .. }, Some(ValueReduction::FieldElement { value })) =
+                    (lhe.as_ref(), rhe.value())
+                {
+                    if value.is_positive() && value <= &half(&self.prime) {
+                        self.increments.insert(var.clone(), (name.clone(), value.clone()));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns `(v, op, e)` if the condition is on the form `v < e` or `v <= e`.
+fn loop_condition(
+    cond: &Expression,
+) -> Option<(&VariableName, &ExpressionInfixOpcode, &Expression)> {
+    use Expression::*;
+    use ExpressionInfixOpcode::*;
+    match cond {
+        InfixOp { lhe, infix_op: infix_op @ (Lesser | LesserEq), rhe, .. } => match lhe.as_ref() {
+            Variable { name, .. } => Some((name, infix_op, rhe)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns `x` if the constraint `lhe === rhe` is on one of the forms
+/// `x * (x - 1) === 0`, `x * (1 - x) === 0`, or `x * x === x` (with the
+/// factors in any order).
+fn boolean_constraint<'a>(lhe: &'a Expression, rhe: &Expression) -> Option<&'a VariableName> {
+    use Expression::*;
+    use ExpressionInfixOpcode::*;
+    let InfixOp { lhe: lhf, infix_op: Mul, rhe: rhf, .. } = lhe else {
+        return None;
+    };
+    let is_constant = |expr: &Expression, value: usize| matches!(expr.value(), Some(ValueReduction::FieldElement { value: v }) if v == &BigInt::from(value));
+    for (x, factor) in [(lhf, rhf), (rhf, lhf)] {
+        let Variable { name, .. } = x.as_ref() else {
+            continue;
+        };
+        let is_x =
+            |expr: &Expression| matches!(expr, Variable { name: other, .. } if other == name);
+        let is_boolean = match factor.as_ref() {
+            // x * (x - 1) === 0 or x * (1 - x) === 0
+            InfixOp { lhe: lhs, infix_op: Sub, rhe: rhs, .. } => {
+                is_constant(rhe, 0)
+                    && ((is_x(lhs) && is_constant(rhs, 1)) || (is_constant(lhs, 1) && is_x(rhs)))
+            }
+            // x * x === x
+            other => is_x(other) && is_x(rhe),
+        };
+        if is_boolean {
+            return Some(name);
+        }
+    }
+    None
+}
+
+pub trait RangeMeta {
+    /// Compute value ranges for this node and child nodes. Returns true if the
+    /// node (or a child node) is updated.
+    fn propagate_ranges(&mut self, env: &mut RangeEnvironment) -> bool;
+
+    /// Returns an inclusive range containing the values the node may take, or
+    /// `None` if the range has not been computed.
+    #[must_use]
+    fn range(&self) -> Option<&ValueRange>;
+}
+
+#[derive(Default, Clone)]
+pub struct RangeKnowledge {
+    // The inclusive range of values the node may take.
+    range: Option<ValueRange>,
+}
+
+impl RangeKnowledge {
+    #[must_use]
+    pub fn new() -> RangeKnowledge {
+        RangeKnowledge::default()
+    }
+
+    /// Sets the range of the node. Returns true if the range was updated.
+    pub fn set_range(&mut self, range: &ValueRange) -> bool {
+        if self.range.as_ref() == Some(range) {
+            false
+        } else {
+            self.range = Some(range.clone());
+            true
+        }
+    }
+
+    #[must_use]
+    pub fn range(&self) -> Option<&ValueRange> {
+        self.range.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::constants::{Curve, UsefulConstants};
+    use crate::ir::ExpressionInfixOpcode::*;
+
+    use super::{RangeKnowledge, ValueRange};
+
+    fn range(lower: i64, upper: i64) -> ValueRange {
+        ValueRange::new(BigInt::from(lower), BigInt::from(upper))
+    }
+
+    #[test]
+    fn test_range_arithmetic() {
+        assert_eq!(range(0, 3).infix_op(&Add, &range(-1, 2)), range(-1, 5));
+        assert_eq!(range(0, 3).infix_op(&Sub, &range(-1, 2)), range(-2, 4));
+        assert_eq!(range(-2, 3).infix_op(&Mul, &range(-1, 2)), range(-4, 6));
+        assert_eq!(range(0, 3).infix_op(&Pow, &range(0, 2)), range(0, 9));
+        assert_eq!(range(4, 9).infix_op(&IntDiv, &range(2, 4)), range(1, 4));
+        assert_eq!(range(0, 100).infix_op(&Mod, &range(1, 8)), range(0, 7));
+        assert_eq!(range(1, 3).infix_op(&ShiftL, &range(1, 2)), range(2, 12));
+        assert_eq!(range(8, 16).infix_op(&ShiftR, &range(1, 2)), range(2, 8));
+        assert_eq!(range(0, 5).infix_op(&BitOr, &range(0, 8)), range(0, 15));
+        assert_eq!(range(0, 5).infix_op(&BitAnd, &range(0, 8)), range(0, 5));
+        assert_eq!(range(-5, 5).infix_op(&Lesser, &range(0, 8)), ValueRange::boolean());
+        assert_eq!(range(-5, 5).infix_op(&Div, &range(1, 1)), ValueRange::unbounded());
+        assert_eq!(range(-5, 5).infix_op(&BitAnd, &range(0, 8)), ValueRange::unbounded());
+        assert_eq!(ValueRange::unbounded().infix_op(&Add, &range(0, 1)), ValueRange::unbounded());
+    }
+
+    #[test]
+    fn test_range_reduction() {
+        let constants = UsefulConstants::new(&Curve::default());
+        let prime = constants.prime();
+        let half: BigInt = prime / 2;
+
+        let value = ValueRange::from_field_element(&(prime - 1), prime);
+        assert_eq!(value, range(-1, -1));
+        assert_eq!(ValueRange::bits(8).reduce(prime), range(0, 255));
+        assert!(ValueRange::bits(252).fits_in_field(prime));
+        assert!(!ValueRange::bits(253).fits_in_field(prime));
+        assert_eq!(ValueRange::bits(253).reduce(prime), ValueRange::unbounded());
+        assert!(ValueRange::new(-&half, half.clone()).fits_in_field(prime));
+        assert!(!ValueRange::new(-&half - 1, half).fits_in_field(prime));
+    }
+
+    #[test]
+    fn test_range_lattice() {
+        assert_eq!(range(0, 3).join(&range(5, 8)), range(0, 8));
+        assert_eq!(range(0, 3).join(&ValueRange::unbounded()), ValueRange::unbounded());
+        assert_eq!(range(0, 5).meet(&range(3, 8)), Some(range(3, 5)));
+        assert_eq!(range(0, 3).meet(&range(5, 8)), None);
+        assert_eq!(ValueRange::unbounded().meet(&range(5, 8)), Some(range(5, 8)));
+
+        assert!(ValueRange::boolean().is_boolean());
+        assert_eq!(ValueRange::bits(64).bit_width(), Some(64));
+        assert_eq!(range(-1, 1).bit_width(), None);
+        assert_eq!(ValueRange::bits(64).to_string(), "[0, 2^64 - 1]");
+        assert_eq!(range(-256, 1000).to_string(), "[-256, 1000]");
+    }
+
+    #[test]
+    fn test_range_knowledge() {
+        let mut knowledge = RangeKnowledge::new();
+        assert!(knowledge.range().is_none());
+        assert!(knowledge.set_range(&range(0, 1)));
+        assert!(!knowledge.set_range(&range(0, 1)));
+        assert!(knowledge.set_range(&range(0, 2)));
+        assert_eq!(knowledge.range(), Some(&range(0, 2)));
+    }
+}
@@ -4,6 +4,7 @@ use std::fmt;
 use super::declarations::Declarations;
 use super::ir::*;
 use super::degree_meta::{Degree, DegreeEnvironment, DegreeMeta};
+use super::range_meta::{RangeEnvironment, RangeMeta, ValueRange};
 use super::type_meta::TypeMeta;
 use super::value_meta::{ValueEnvironment, ValueMeta};
 use super::variable_meta::{VariableMeta, VariableUse, VariableUses};
@@ -83,10 +84,96 @@ impl Statement {
         }
     }
 
+    pub fn propagate_ranges(&mut self, env: &mut RangeEnvironment) -> bool {
+        let mut result = false;
+
+        use AssignOp::*;
+        use Expression::*;
+        use Statement::*;
+        match self {
+            Declaration { names, var_type, dimensions, .. } => {
+                for size in dimensions {
+                    result |= size.propagate_ranges(env);
+                }
+                if matches!(var_type, VariableType::Signal(_, _)) {
+                    // Signals which are not assigned in the template (like
+                    // input signals) are only constrained by the seeded range.
+                    let names = names
+                        .iter()
+                        .filter(|name| !env.is_assigned(name))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    for name in &names {
+                        let range = env.seed(name).cloned().unwrap_or_else(ValueRange::unbounded);
+                        result |= env.set_range(name, &range);
+                    }
+                }
+                result
+            }
+            Substitution { meta, var, op, rhe } => {
+                let range = if let Phi { meta: phi_meta, args } = rhe {
+                    let range = env.phi_range(var, args);
+                    if let Some(range) = &range {
+                        result |= phi_meta.range_knowledge_mut().set_range(range);
+                    }
+                    range
+                } else {
+                    result |= rhe.propagate_ranges(env);
+                    rhe.range().cloned()
+                };
+                let range = if env.is_signal(var) {
+                    if let Update { access, .. } = rhe {
+                        if matches!(access.last(), Some(AccessType::ComponentAccess(_))) {
+                            // This is a tag assignment.
+                            return result;
+                        }
+                    }
+                    // Values assigned using `<--` are not constrained, so the
+                    // signal is only constrained by the seeded range.
+                    let seed = env.seed(var).cloned().unwrap_or_else(ValueRange::unbounded);
+                    match op {
+                        AssignConstraintSignal => {
+                            range.map(|range| range.meet(&seed).unwrap_or(seed))
+                        }
+                        _ => Some(seed),
+                    }
+                } else if env.is_local(var) {
+                    range
+                } else {
+                    // Components do not have a value.
+                    return result;
+                };
+                if let Some(range) = range {
+                    result |= env.set_range(var, &range);
+                }
+                if let Some(range) = env.range(var) {
+                    result |= meta.range_knowledge_mut().set_range(range);
+                }
+                result
+            }
+            LogCall { args, .. } => {
+                use LogArgument::*;
+                for arg in args {
+                    if let Expr(value) = arg {
+                        result |= value.propagate_ranges(env);
+                    }
+                }
+                result
+            }
+            IfThenElse { cond, .. } => cond.propagate_ranges(env),
+            Return { value, .. } => value.propagate_ranges(env),
+            Assert { arg, .. } => arg.propagate_ranges(env),
+            ConstraintEquality { lhe, rhe, .. } => {
+                result |= lhe.propagate_ranges(env);
+                result |= rhe.propagate_ranges(env);
+                result
+            }
+        }
+    }
+
     #[must_use]
     pub fn propagate_values(&mut self, env: &mut ValueEnvironment) -> bool {
         use Statement::*;
-        use Expression::*;
         match self {
             Declaration { dimensions, .. } => {
                 let mut result = false;
@@ -98,12 +185,9 @@ impl Statement {
             Substitution { meta, var, rhe, .. } => {
                 let mut result = rhe.propagate_values(env);
 
-                // TODO: Handle array values.
-                if !matches!(rhe, Update { .. }) {
-                    if let Some(value) = rhe.value() {
-                        env.add_variable(var, value);
-                        result = result || meta.value_knowledge_mut().set_reduces_to(value.clone());
-                    }
+                if let Some(value) = rhe.value() {
+                    env.add_variable(var, value);
+                    result = result || meta.value_knowledge_mut().set_reduces_to(value.clone());
                 }
                 trace!("Substitution returned {result}");
                 result
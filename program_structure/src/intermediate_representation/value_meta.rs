@@ -1,20 +1,56 @@
 use num_bigint::BigInt;
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 
+use crate::ast;
 use crate::constants::UsefulConstants;
+use crate::report::ReportCollection;
 
-use super::ir::VariableName;
+use super::ir::{Expression, VariableName};
+use super::lifting::TryLift;
+
+/// The maximum number of elements of a constant array value. Larger arrays
+/// are not tracked by value propagation.
+pub const MAX_ARRAY_SIZE: usize = 1 << 16;
+
+/// Evaluates calls to functions with constant arguments during value
+/// propagation.
+pub trait FunctionEvaluator: Send + Sync {
+    /// Returns the value of `name(args...)`, or `None` if the call cannot be
+    /// evaluated.
+    fn evaluate(&self, name: &str, args: &[ValueReduction]) -> Option<ValueReduction>;
+}
 
 #[derive(Clone)]
 pub struct ValueEnvironment {
     constants: UsefulConstants,
     reduces_to: HashMap<VariableName, ValueReduction>,
+    functions: Option<Arc<dyn FunctionEvaluator>>,
 }
 
 impl ValueEnvironment {
     pub fn new(constants: &UsefulConstants) -> ValueEnvironment {
-        ValueEnvironment { constants: constants.clone(), reduces_to: HashMap::new() }
+        ValueEnvironment {
+            constants: constants.clone(),
+            reduces_to: HashMap::new(),
+            functions: None,
+        }
+    }
+
+    /// Use the given evaluator to evaluate function calls with constant
+    /// arguments.
+    #[must_use]
+    pub fn with_functions(mut self, functions: Arc<dyn FunctionEvaluator>) -> ValueEnvironment {
+        self.functions = Some(functions);
+        self
+    }
+
+    /// Returns the value of the function call `name(args...)`, or `None` if
+    /// the call cannot be evaluated.
+    #[must_use]
+    pub fn evaluate_call(&self, name: &str, args: &[ValueReduction]) -> Option<ValueReduction> {
+        self.functions.as_ref()?.evaluate(name, args)
     }
 
     /// Set the value of the given variable. Returns `true` on first update.
@@ -69,6 +105,74 @@ pub trait ValueMeta {
 pub enum ValueReduction {
     Boolean { value: bool },
     FieldElement { value: BigInt },
+    Array { values: Vec<ValueReduction> },
+}
+
+impl ValueReduction {
+    /// Returns a zero initialized value with the given dimensions, or `None`
+    /// if the total number of elements exceeds `MAX_ARRAY_SIZE`.
+    #[must_use]
+    pub fn zero(dimensions: &[usize]) -> Option<ValueReduction> {
+        let size = dimensions.iter().try_fold(1_usize, |size, n| size.checked_mul(*n))?;
+        if size > MAX_ARRAY_SIZE {
+            return None;
+        }
+        Some(Self::zero_unchecked(dimensions))
+    }
+
+    fn zero_unchecked(dimensions: &[usize]) -> ValueReduction {
+        match dimensions.split_first() {
+            Some((size, dimensions)) => {
+                ValueReduction::Array { values: vec![Self::zero_unchecked(dimensions); *size] }
+            }
+            None => ValueReduction::FieldElement { value: BigInt::from(0) },
+        }
+    }
+
+    /// Returns the element at the given (possibly partial) array index.
+    /// Returns `None` if the index is out of bounds.
+    #[must_use]
+    pub fn get(&self, indices: &[usize]) -> Option<&ValueReduction> {
+        match (self, indices.split_first()) {
+            (_, None) => Some(self),
+            (ValueReduction::Array { values }, Some((index, indices))) => {
+                values.get(*index)?.get(indices)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of the array where the element at the given (possibly
+    /// partial) index is replaced by the given value. Returns `None` if the
+    /// index is out of bounds.
+    #[must_use]
+    pub fn update(&self, indices: &[usize], value: &ValueReduction) -> Option<ValueReduction> {
+        match (self, indices.split_first()) {
+            (_, None) => Some(value.clone()),
+            (ValueReduction::Array { values }, Some((index, indices))) => {
+                let mut values = values.clone();
+                let element = values.get_mut(*index)?;
+                *element = element.update(indices, value)?;
+                Some(ValueReduction::Array { values })
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluates the given AST expression (e.g. an argument to the main
+    /// component). Returns `None` if the expression does not reduce to a
+    /// constant.
+    #[must_use]
+    pub fn from_ast(expr: &ast::Expression, constants: &UsefulConstants) -> Option<ValueReduction> {
+        if expr.is_tuple() || expr.is_anonymous_component() {
+            return None;
+        }
+        let mut expr: Expression = expr.try_lift((), &mut ReportCollection::new()).ok()?;
+        let mut env = ValueEnvironment::new(constants);
+        // Each pass may only update a single level of the expression tree.
+        while expr.propagate_values(&mut env) {}
+        expr.value().cloned()
+    }
 }
 
 impl fmt::Display for ValueReduction {
@@ -77,6 +181,10 @@ impl fmt::Display for ValueReduction {
         match self {
             Boolean { value } => write!(f, "{value}"),
             FieldElement { value } => write!(f, "{value}"),
+            Array { values } => {
+                let values = values.iter().map(ToString::to_string).collect::<Vec<_>>();
+                write!(f, "[{}]", values.join(", "))
+            }
         }
     }
 }
@@ -138,7 +246,7 @@ mod tests {
     #[test]
     fn test_value_knowledge() {
         let mut value = ValueKnowledge::new();
-        assert!(matches!(value.get_reduces_to(), None));
+        assert!(value.get_reduces_to().is_none());
 
         let number = ValueReduction::FieldElement { value: BigInt::from(1) };
         assert!(value.set_reduces_to(number));
@@ -152,4 +260,26 @@ mod tests {
         assert!(!value.is_field_element());
         assert!(value.is_boolean());
     }
+
+    #[test]
+    fn test_array_values() {
+        let number = |value: usize| ValueReduction::FieldElement { value: BigInt::from(value) };
+        let array = ValueReduction::zero(&[2, 3]).unwrap();
+        assert_eq!(array.to_string(), "[[0, 0, 0], [0, 0, 0]]");
+
+        let array = array.update(&[1, 2], &number(5)).unwrap();
+        assert_eq!(array.get(&[1, 2]), Some(&number(5)));
+        assert_eq!(array.get(&[0, 2]), Some(&number(0)));
+        assert_eq!(array.get(&[1]).unwrap().to_string(), "[0, 0, 5]");
+        assert_eq!(array.get(&[2]), None);
+        assert_eq!(array.get(&[1, 2, 0]), None);
+
+        let row = ValueReduction::Array { values: vec![number(1), number(2), number(3)] };
+        let array = array.update(&[0], &row).unwrap();
+        assert_eq!(array.to_string(), "[[1, 2, 3], [0, 0, 5]]");
+        assert_eq!(array.update(&[0, 3], &number(1)), None);
+
+        assert_eq!(ValueReduction::zero(&[]), Some(number(0)));
+        assert_eq!(ValueReduction::zero(&[1 << 10, 1 << 10]), None);
+    }
 }
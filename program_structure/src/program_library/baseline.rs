@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use serde_derive::{Deserialize, Serialize};
+
+use super::file_definition::FileLibrary;
+use super::report::Report;
+
+/// The current version of the baseline file format.
+const BASELINE_VERSION: u32 = 1;
+
+/// A stable identifier for a report. Fingerprints do not depend on byte offsets
+/// or line numbers, which means that they are not affected by unrelated
+/// changes to the analyzed source code.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// The report ID (e.g. `CS0005`).
+    pub code: String,
+    /// The name of the function or template containing the issue (if any).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definition: Option<String>,
+    /// The source code corresponding to the primary locations of the report,
+    /// with all whitespace normalized.
+    pub snippet: String,
+}
+
+impl Fingerprint {
+    pub fn new(report: &Report, file_library: &FileLibrary) -> Fingerprint {
+        let files = file_library.to_storage();
+        let snippets = report
+            .primary()
+            .iter()
+            .filter_map(|label| {
+                let source = files.get(label.file_id).ok()?.source();
+                source.get(label.range.clone()).map(normalize_whitespace)
+            })
+            .collect::<Vec<_>>();
+        // Fall back to the report message if there is no primary location.
+        let snippet = if snippets.is_empty() {
+            normalize_whitespace(report.message())
+        } else {
+            snippets.join(" | ")
+        };
+        Fingerprint {
+            code: report.id(),
+            definition: report.definition_name().map(ToString::to_string),
+            snippet,
+        }
+    }
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A single baseline entry, corresponding to one or more identical reports.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    #[serde(flatten)]
+    pub fingerprint: Fingerprint,
+    /// The report message (for information only).
+    #[serde(default)]
+    pub message: String,
+    /// The number of reports with this fingerprint.
+    pub count: usize,
+}
+
+/// A set of previously accepted reports.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    pub fn new(reports: &[Report], file_library: &FileLibrary) -> Baseline {
+        let mut entries: HashMap<Fingerprint, BaselineEntry> = HashMap::new();
+        for report in reports {
+            let fingerprint = Fingerprint::new(report, file_library);
+            entries
+                .entry(fingerprint.clone())
+                .or_insert_with(|| BaselineEntry {
+                    fingerprint,
+                    message: report.message().clone(),
+                    count: 0,
+                })
+                .count += 1;
+        }
+        // Sort entries to ensure that the output is deterministic.
+        let mut entries = entries.into_values().collect::<Vec<_>>();
+        entries.sort_by(|lhs, rhs| lhs.fingerprint.cmp(&rhs.fingerprint));
+        Baseline { version: BASELINE_VERSION, entries }
+    }
+
+    pub fn entries(&self) -> &[BaselineEntry] {
+        &self.entries
+    }
+
+    /// Returns the number of reports contained in the baseline.
+    pub fn len(&self) -> usize {
+        self.entries.iter().map(|entry| entry.count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn read(path: &Path) -> Result<Baseline> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read baseline `{}`", path.display()))?;
+        let baseline: Baseline = serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse baseline `{}`", path.display()))?;
+        if baseline.version != BASELINE_VERSION {
+            return Err(anyhow!(
+                "unsupported baseline version {} in `{}`",
+                baseline.version,
+                path.display()
+            ));
+        }
+        Ok(baseline)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json + "\n")
+            .with_context(|| format!("failed to write baseline `{}`", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    fn report(
+        file_library: &FileLibrary,
+        file_id: usize,
+        pattern: &str,
+        definition: &str,
+    ) -> Report {
+        let source = file_library.to_storage().get(file_id).unwrap().source();
+        let start = source.find(pattern).unwrap();
+        let mut report =
+            Report::warning("message".to_string(), ReportCode::SignalAssignmentStatement);
+        report.add_primary(start..start + pattern.len(), file_id, "label".to_string());
+        report.set_definition_name(definition);
+        report
+    }
+
+    #[test]
+    fn test_fingerprints_are_stable() {
+        let mut file_library = FileLibrary::new();
+        let old_file = file_library.add_file(
+            "old.circom".to_string(),
+            "template T() {\n  out <-- in   * in;\n}".to_string(),
+            true,
+        );
+        let new_file = file_library.add_file(
+            "new.circom".to_string(),
+            "// A new comment.\n\ntemplate T() {\n    out <-- in * in;\n}".to_string(),
+            true,
+        );
+        let old_report = report(&file_library, old_file, "out <-- in   * in", "T");
+        let new_report = report(&file_library, new_file, "out <-- in * in", "T");
+        assert_eq!(
+            Fingerprint::new(&old_report, &file_library),
+            Fingerprint::new(&new_report, &file_library)
+        );
+
+        let other_report = report(&file_library, new_file, "out <-- in * in", "S");
+        assert_ne!(
+            Fingerprint::new(&old_report, &file_library),
+            Fingerprint::new(&other_report, &file_library)
+        );
+    }
+
+    #[test]
+    fn test_baseline_counts() {
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file(
+            "test.circom".to_string(),
+            "template T() {\n  a <-- b;\n  a <-- b;\n  c <-- d;\n}".to_string(),
+            true,
+        );
+        let reports = [
+            report(&file_library, file_id, "a <-- b", "T"),
+            report(&file_library, file_id, "a <-- b", "T"),
+            report(&file_library, file_id, "c <-- d", "T"),
+        ];
+        let baseline = Baseline::new(&reports, &file_library);
+        assert_eq!(baseline.entries().len(), 2);
+        assert_eq!(baseline.entries()[0].fingerprint.snippet, "a <-- b");
+        assert_eq!(baseline.entries()[0].count, 2);
+        assert_eq!(baseline.entries()[1].fingerprint.snippet, "c <-- d");
+        assert_eq!(baseline.entries()[1].count, 1);
+
+        let json = serde_json::to_string(&baseline).unwrap();
+        assert_eq!(serde_json::from_str::<Baseline>(&json).unwrap(), baseline);
+    }
+}
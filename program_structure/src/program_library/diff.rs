@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+/// The lines added or modified by a unified diff, keyed by the path of the
+/// new version of each file. Line numbers are one-based.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diff {
+    changed_lines: HashMap<PathBuf, Vec<Range<usize>>>,
+}
+
+impl Diff {
+    /// Reads a unified diff from the given file, or from `stdin` if the path
+    /// is `-`.
+    pub fn read(diff_file: &Path) -> Result<Diff> {
+        let contents = if diff_file == Path::new("-") {
+            let mut contents = String::new();
+            io::stdin().read_to_string(&mut contents).context("failed to read diff from stdin")?;
+            contents
+        } else {
+            fs::read_to_string(diff_file)
+                .with_context(|| format!("failed to read `{}`", diff_file.display()))?
+        };
+        Diff::parse(&contents).with_context(|| format!("failed to parse `{}`", diff_file.display()))
+    }
+
+    /// Parses a unified diff (e.g. the output of `git diff`). Only added
+    /// lines are recorded, since removed lines do not exist in the analyzed
+    /// files. (Modified lines are represented as removed and added lines.)
+    pub fn parse(contents: &str) -> Result<Diff> {
+        let mut diff = Diff::default();
+        let mut current_file: Option<PathBuf> = None;
+        let mut lines = contents.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                current_file = parse_path(path);
+                continue;
+            }
+            let Some(header) = line.strip_prefix("@@ ") else {
+                continue;
+            };
+            let (mut old_count, mut new_line, mut new_count) = parse_hunk_header(header)
+                .ok_or_else(|| anyhow!("invalid hunk header on line {}", index + 1))?;
+            while old_count > 0 || new_count > 0 {
+                let Some((index, line)) = lines.next() else {
+                    bail!("unexpected end of diff in hunk");
+                };
+                match line.chars().next() {
+                    Some('+') => {
+                        if let Some(path) = &current_file {
+                            diff.add_line(path, new_line);
+                        }
+                        new_line += 1;
+                        new_count = new_count.saturating_sub(1);
+                    }
+                    Some('-') => old_count = old_count.saturating_sub(1),
+                    // Context lines (the leading space may be stripped from
+                    // empty lines).
+                    Some(' ') | None => {
+                        new_line += 1;
+                        old_count = old_count.saturating_sub(1);
+                        new_count = new_count.saturating_sub(1);
+                    }
+                    // `\ No newline at end of file`
+                    Some('\\') => {}
+                    Some(_) => bail!("invalid line in hunk on line {}", index + 1),
+                }
+            }
+        }
+        Ok(diff)
+    }
+
+    fn add_line(&mut self, path: &Path, line: usize) {
+        let ranges = self.changed_lines.entry(path.to_path_buf()).or_default();
+        match ranges.last_mut() {
+            Some(range) if range.end == line => range.end += 1,
+            _ => ranges.push(line..line + 1),
+        }
+    }
+
+    /// Returns the paths of all files with added or modified lines.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.changed_lines.keys().map(PathBuf::as_path)
+    }
+
+    /// Returns the (one-based) ranges of lines added or modified in the given
+    /// file. Paths in the diff are typically relative to the repository root,
+    /// so a file matches a path in the diff if the path is a suffix of the
+    /// file path.
+    pub fn changed_lines(&self, file_path: &Path) -> &[Range<usize>] {
+        self.changed_lines
+            .iter()
+            .find(|(path, _)| file_path.ends_with(path))
+            .map(|(_, ranges)| ranges.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Parses the path from a `+++` line, dropping the `b/` prefix used by `git`.
+/// Returns `None` if the file was deleted.
+fn parse_path(path: &str) -> Option<PathBuf> {
+    // Some tools append a timestamp separated by a tab.
+    let path = path.split('\t').next().unwrap_or_default().trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path.strip_prefix("b/").unwrap_or(path);
+    Some(PathBuf::from(path))
+}
+
+/// Parses a hunk header on the form `-l,s +l,s @@`, returning the number of
+/// lines from the old file, and the first line and number of lines from the
+/// new file. (The line count is omitted if it is 1.)
+fn parse_hunk_header(header: &str) -> Option<(usize, usize, usize)> {
+    let mut parts = header.split_whitespace();
+    let parse_range = |range: Option<&str>, prefix: char| -> Option<(usize, usize)> {
+        let range = range?.strip_prefix(prefix)?;
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (_, old_count) = parse_range(parts.next(), '-')?;
+    let (new_start, new_count) = parse_range(parts.next(), '+')?;
+    Some((old_count, new_start, new_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diff() {
+        let diff = Diff::parse(
+            "\
+diff --git a/circuits/main.circom b/circuits/main.circom
+index 1234567..89abcde 100644
+--- a/circuits/main.circom
++++ b/circuits/main.circom
+@@ -2,4 +2,5 @@ template Main() {
+     signal input in;
+-    signal output out;
++    signal output out[2];
++    // -- comment
+     out <== in;
+
+@@ -10 +11,0 @@
+-    in === 1;
+diff --git a/old.circom b/old.circom
+deleted file mode 100644
+--- a/old.circom
++++ /dev/null
+@@ -1 +0,0 @@
+-template Old() {}
+--- lib.circom\t2024-01-01 00:00:00
++++ lib.circom\t2024-01-02 00:00:00
+@@ -1,0 +1,2 @@
++template Lib() {}
++
+\\ No newline at end of file
+",
+        )
+        .unwrap();
+
+        let changed_lines = |path: &str| {
+            diff.changed_lines(Path::new(path)).iter().cloned().flatten().collect::<Vec<_>>()
+        };
+        assert_eq!(changed_lines("/project/circuits/main.circom"), vec![3, 4]);
+        assert_eq!(changed_lines("lib.circom"), vec![1, 2]);
+        assert!(changed_lines("old.circom").is_empty());
+        assert!(changed_lines("/project/main.circom").is_empty());
+        assert_eq!(diff.files().count(), 2);
+    }
+
+    #[test]
+    fn test_invalid_diff() {
+        assert!(Diff::parse("+++ b/a.circom\n@@ -1 +1 @@\n").is_err());
+        assert!(Diff::parse("+++ b/a.circom\n@@ invalid @@\n").is_err());
+        assert!(Diff::parse("+++ b/a.circom\n@@ -1 +1 @@\n?\n").is_err());
+        assert_eq!(Diff::parse("").unwrap(), Diff::default());
+    }
+}
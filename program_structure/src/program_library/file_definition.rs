@@ -1,5 +1,10 @@
 use codespan_reporting::files::{Files, SimpleFiles};
-use std::{ops::Range, collections::HashSet};
+use std::{
+    ops::Range,
+    collections::{HashMap, HashSet},
+};
+
+use super::suppression::Suppression;
 
 pub type FileSource = String;
 pub type FilePath = String;
@@ -7,15 +12,45 @@ pub type FileID = usize;
 pub type FileLocation = Range<usize>;
 type FileStorage = SimpleFiles<FilePath, FileSource>;
 
+/// A source code comment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comment {
+    /// The comment text (excluding the comment delimiters).
+    text: String,
+    /// The location of the comment (including the comment delimiters).
+    location: FileLocation,
+}
+
+impl Comment {
+    pub fn new(text: String, location: FileLocation) -> Comment {
+        Comment { text, location }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn location(&self) -> &FileLocation {
+        &self.location
+    }
+}
+
 #[derive(Clone)]
 pub struct FileLibrary {
     files: FileStorage,
     user_inputs: HashSet<FileID>,
+    comments: HashMap<FileID, Vec<Comment>>,
+    suppressions: Vec<Suppression>,
 }
 
 impl Default for FileLibrary {
     fn default() -> Self {
-        FileLibrary { files: FileStorage::new(), user_inputs: HashSet::new() }
+        FileLibrary {
+            files: FileStorage::new(),
+            user_inputs: HashSet::new(),
+            comments: HashMap::new(),
+            suppressions: Vec::new(),
+        }
     }
 }
 
@@ -40,6 +75,26 @@ impl FileLibrary {
         self.files.line_index(file_id, start).map(|lines| lines + 1).ok()
     }
 
+    /// Adds the comments from the file with the given file ID.
+    pub fn add_comments(&mut self, file_id: FileID, comments: Vec<Comment>) {
+        self.comments.entry(file_id).or_default().extend(comments);
+    }
+
+    /// Returns the comments from the file with the given file ID.
+    pub fn comments(&self, file_id: FileID) -> &[Comment] {
+        self.comments.get(&file_id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Adds inline suppressions parsed from source code comments.
+    pub fn add_suppressions(&mut self, suppressions: Vec<Suppression>) {
+        self.suppressions.extend(suppressions);
+    }
+
+    /// Returns all inline suppressions.
+    pub fn suppressions(&self) -> &[Suppression] {
+        &self.suppressions
+    }
+
     pub fn to_storage(&self) -> &FileStorage {
         self.get_files()
     }
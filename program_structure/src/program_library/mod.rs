@@ -1,6 +1,10 @@
 use super::ast;
+pub mod baseline;
+pub mod diff;
 pub mod report_code;
+pub mod report_code_explanation;
 pub mod report;
+pub mod suppression;
 pub mod file_definition;
 pub mod function_data;
 pub mod program_archive;
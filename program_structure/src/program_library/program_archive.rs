@@ -23,6 +23,7 @@ pub struct ProgramArchive {
     pub custom_gates: bool,
 }
 impl ProgramArchive {
+    #[allow(clippy::result_large_err)]
     pub fn new(
         file_library: FileLibrary,
         file_id_main: FileID,
@@ -82,6 +82,52 @@ impl MessageCategory {
     }
 }
 
+/// A suggested fix for a report, given by a replacement for the source code at
+/// the given location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    message: String,
+    file_id: FileID,
+    location: FileLocation,
+    replacement: String,
+}
+
+impl Suggestion {
+    pub fn new(
+        location: FileLocation,
+        file_id: FileID,
+        replacement: String,
+        message: String,
+    ) -> Suggestion {
+        Suggestion { message, file_id, location, replacement }
+    }
+
+    /// A short description of the fix.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn file_id(&self) -> FileID {
+        self.file_id
+    }
+
+    /// The location of the source code replaced by the fix.
+    pub fn location(&self) -> &FileLocation {
+        &self.location
+    }
+
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+
+    /// Returns true if the two suggestions replace overlapping source code.
+    pub fn overlaps(&self, other: &Suggestion) -> bool {
+        self.file_id == other.file_id
+            && self.location.start < other.location.end
+            && other.location.start < self.location.end
+    }
+}
+
 #[derive(Clone)]
 pub struct Report {
     category: MessageCategory,
@@ -90,7 +136,11 @@ pub struct Report {
     primary: Vec<ReportLabel>,
     secondary: Vec<ReportLabel>,
     notes: Vec<ReportNote>,
+    suggestions: Vec<Suggestion>,
     code: ReportCode,
+    /// The name of the function or template analyzed when the report was
+    /// generated (if any).
+    definition_name: Option<String>,
 }
 
 impl Report {
@@ -102,7 +152,9 @@ impl Report {
             primary: Vec::new(),
             secondary: Vec::new(),
             notes: Vec::new(),
+            suggestions: Vec::new(),
             code,
+            definition_name: None,
         }
     }
 
@@ -149,6 +201,17 @@ impl Report {
         self
     }
 
+    pub fn add_suggestion(
+        &mut self,
+        location: FileLocation,
+        file_id: FileID,
+        replacement: String,
+        message: String,
+    ) -> &mut Self {
+        self.suggestions.push(Suggestion::new(location, file_id, replacement, message));
+        self
+    }
+
     pub fn to_diagnostic(&self, verbose: bool) -> Diagnostic<FileID> {
         let mut labels = self.primary().clone();
         let mut secondary = self.secondary().clone();
@@ -163,6 +226,9 @@ impl Report {
         .with_labels(labels);
 
         let mut notes = self.notes().clone();
+        for suggestion in self.suggestions() {
+            notes.push(format!("Suggested fix: {}", suggestion.message()));
+        }
         if let Some(url) = self.code().url() {
             // Add URL to documentation if available.
             notes.push(format!("For more details, see {url}."));
@@ -188,6 +254,11 @@ impl Report {
         &self.category
     }
 
+    pub fn set_category(&mut self, category: MessageCategory) -> &mut Self {
+        self.category = category;
+        self
+    }
+
     pub fn message(&self) -> &String {
         &self.message
     }
@@ -216,6 +287,10 @@ impl Report {
         &mut self.notes
     }
 
+    pub fn suggestions(&self) -> &Vec<Suggestion> {
+        &self.suggestions
+    }
+
     pub fn code(&self) -> &ReportCode {
         &self.code
     }
@@ -224,7 +299,35 @@ impl Report {
         self.code.id()
     }
 
+    pub fn definition_name(&self) -> Option<&str> {
+        self.definition_name.as_deref()
+    }
+
+    pub fn set_definition_name(&mut self, name: &str) -> &mut Self {
+        self.definition_name = Some(name.to_string());
+        self
+    }
+
     pub fn name(&self) -> String {
         self.code.name()
     }
+
+    /// Updates the file ID and location of each label and suggestion of the
+    /// report using the given function. This is used to move reports when the
+    /// underlying source code is moved (e.g. when a previous definition is
+    /// edited).
+    pub fn relocate<F>(&mut self, mut relocate: F) -> &mut Self
+    where
+        F: FnMut(FileID, FileLocation) -> (FileID, FileLocation),
+    {
+        for label in self.primary.iter_mut().chain(self.secondary.iter_mut()) {
+            (label.file_id, label.range) = relocate(label.file_id, label.range.clone());
+        }
+        for suggestion in &mut self.suggestions {
+            (suggestion.file_id, suggestion.location) =
+                relocate(suggestion.file_id, suggestion.location.clone());
+        }
+        self.primary_file_ids = self.primary.iter().map(|label| label.file_id).collect();
+        self
+    }
 }
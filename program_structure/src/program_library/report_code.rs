@@ -1,6 +1,8 @@
+use serde_derive::{Deserialize, Serialize};
+
 const DOC_URL: &str = "https://github.com/trailofbits/circomspect/blob/main/doc/analysis_passes.md";
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub enum ReportCode {
     AssertWrongType,
     ParseFail,
@@ -74,6 +76,7 @@ pub enum ReportCode {
     ParameterNameCollision,
     FieldElementComparison,
     FieldElementArithmetic,
+    FieldElementOverflow,
     SignalAssignmentStatement,
     UnnecessarySignalAssignment,
     UnusedVariableValue,
@@ -88,9 +91,125 @@ pub enum ReportCode {
     Bn254SpecificCircuit,
     UnderConstrainedSignal,
     UnusedOutputSignal,
+    UnusedSuppression,
+    // Codes emitted by custom analysis passes defined outside this crate.
+    // (Custom codes cannot be serialized.)
+    #[serde(skip)]
+    Custom {
+        #[serde(skip)]
+        id: &'static str,
+        #[serde(skip)]
+        name: &'static str,
+    },
 }
 
+/// All built-in report codes (i.e. all codes except custom codes).
+const BUILTIN_CODES: [ReportCode; 86] = {
+    use ReportCode::*;
+    [
+        AssertWrongType,
+        ParseFail,
+        CompilerVersionError,
+        WrongTypesInAssignOperation,
+        WrongNumberOfArguments(0, 0),
+        UndefinedFunction,
+        UndefinedTemplate,
+        UninitializedSymbolInExpression,
+        UnableToTypeFunction,
+        UnreachableConstraints,
+        UnknownIndex,
+        UnknownDimension,
+        SameFunctionDeclaredTwice,
+        SameTemplateDeclaredTwice,
+        SameSymbolDeclaredTwice,
+        StaticInfoWasOverwritten,
+        SignalInLineInitialization,
+        SignalOutsideOriginalScope,
+        FunctionWrongNumberOfArguments,
+        FunctionInconsistentTyping,
+        FunctionPathWithoutReturn,
+        FunctionReturnError,
+        ForbiddenDeclarationInFunction,
+        NonHomogeneousArray,
+        NonBooleanCondition,
+        NonCompatibleBranchTypes,
+        NonEqualTypesInExpression,
+        NonExistentSymbol,
+        NoMainFoundInProject,
+        NoCompilerVersionWarning,
+        MultipleMainInComponent,
+        TemplateCallAsArgument,
+        TemplateWrongNumberOfArguments,
+        TemplateWithReturnStatement,
+        TypeCantBeUseAsCondition,
+        EmptyArrayInlineDeclaration,
+        PrefixOperatorWithWrongTypes,
+        InfixOperatorWithWrongTypes,
+        InvalidArgumentInCall,
+        InconsistentReturnTypesInBlock,
+        InconsistentStaticInformation,
+        InvalidArrayAccess,
+        InvalidSignalAccess,
+        InvalidArraySize,
+        InvalidArrayType,
+        ForStatementIllConstructed,
+        BadArrayAccess,
+        AssigningAComponentTwice,
+        AssigningASignalTwice,
+        NotAllowedOperation,
+        ConstraintGeneratorInFunction,
+        WrongSignalTags,
+        InvalidPartialArray,
+        MustBeSingleArithmetic,
+        ExpectedDimDiffGotDim(0, 0),
+        RuntimeError,
+        UnknownTemplate,
+        NonQuadratic,
+        NonConstantArrayLength,
+        NonComputableExpression,
+        AnonymousComponentError,
+        TupleError,
+        UnconstrainedSignal,
+        OneConstraintIntermediate,
+        NoOutputInInstance,
+        ErrorWat2Wasm,
+        ShadowingVariable,
+        ParameterNameCollision,
+        FieldElementComparison,
+        FieldElementArithmetic,
+        FieldElementOverflow,
+        SignalAssignmentStatement,
+        UnnecessarySignalAssignment,
+        UnusedVariableValue,
+        UnusedParameterValue,
+        VariableWithoutSideEffect,
+        ConstantBranchCondition,
+        NonStrictBinaryConversion,
+        CyclomaticComplexity,
+        TooManyArguments,
+        UnconstrainedLessThan,
+        UnconstrainedDivision,
+        Bn254SpecificCircuit,
+        UnderConstrainedSignal,
+        UnusedOutputSignal,
+        UnusedSuppression,
+    ]
+};
+
 impl ReportCode {
+    /// Returns all built-in report codes. Codes taking arguments are
+    /// represented by a single instance.
+    pub fn builtin_codes() -> &'static [ReportCode] {
+        &BUILTIN_CODES
+    }
+
+    /// Returns true if the code is emitted by the parser or type checker
+    /// (i.e. if the ID starts with `P` or `T`). These codes are also used for
+    /// errors preventing a template or function from being analyzed.
+    pub fn is_compiler_code(&self) -> bool {
+        !matches!(self, ReportCode::Custom { .. }) && self.id().starts_with(['P', 'T'])
+    }
+
     pub fn id(&self) -> String {
         use self::ReportCode::*;
         match self {
@@ -180,6 +299,9 @@ impl ReportCode {
             Bn254SpecificCircuit => "CS0016",
             UnderConstrainedSignal => "CS0017",
             UnusedOutputSignal => "CS0018",
+            UnusedSuppression => "CS0019",
+            FieldElementOverflow => "CS0020",
+            Custom { id, .. } => id,
         }
         .to_string()
     }
@@ -257,6 +379,7 @@ impl ReportCode {
             ParameterNameCollision => "parameter-name-collision",
             FieldElementComparison => "field-element-comparison",
             FieldElementArithmetic => "field-element-arithmetic",
+            FieldElementOverflow => "field-element-overflow",
             SignalAssignmentStatement => "signal-assignment-statement",
             UnnecessarySignalAssignment => "unnecessary-signal-assignment",
             UnusedVariableValue => "unused-variable-value",
@@ -271,6 +394,8 @@ impl ReportCode {
             Bn254SpecificCircuit => "bn254-specific-circuit",
             UnderConstrainedSignal => "under-constrained-signal",
             UnusedOutputSignal => "unused-output-signal",
+            UnusedSuppression => "unused-suppression",
+            Custom { name, .. } => name,
         }
         .to_string()
     }
@@ -281,6 +406,7 @@ impl ReportCode {
             ShadowingVariable => Some("shadowing-variable"),
             FieldElementComparison => Some("field-element-comparison"),
             FieldElementArithmetic => Some("field-element-arithmetic"),
+            FieldElementOverflow => Some("field-element-arithmetic"),
             SignalAssignmentStatement => Some("signal-assignment"),
             UnusedVariableValue => Some("unused-variable-or-parameter"),
             UnusedParameterValue => Some("unused-variable-or-parameter"),
@@ -295,6 +421,7 @@ impl ReportCode {
             Bn254SpecificCircuit => Some("bn254-specific-circuit"),
             UnderConstrainedSignal => Some("under-constrained-signal"),
             UnusedOutputSignal => Some("unused-output-signal"),
+            UnusedSuppression => Some("unused-suppression"),
             // We only provide a URL for Circomspect specific issues.
             _ => None,
         }
@@ -0,0 +1,1392 @@
+//! Long-form explanations of each built-in report code, used by `circomspect
+//! explain` and for Sarif rule descriptions.
+use crate::report_code::ReportCode;
+
+/// A long-form explanation of a report code, together with an example
+/// triggering the report and the same example rewritten to avoid it.
+#[derive(Clone, Copy)]
+pub struct Explanation {
+    description: &'static str,
+    example: &'static str,
+    fixed_example: &'static str,
+}
+
+impl Explanation {
+    const fn new(
+        description: &'static str,
+        example: &'static str,
+        fixed_example: &'static str,
+    ) -> Explanation {
+        Explanation { description, example, fixed_example }
+    }
+
+    /// Returns the description of the issue, as paragraphs separated by empty
+    /// lines.
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// Returns an example which triggers the report.
+    pub fn example(&self) -> String {
+        dedent(self.example)
+    }
+
+    /// Returns the example rewritten to avoid the report.
+    pub fn fixed_example(&self) -> String {
+        dedent(self.fixed_example)
+    }
+
+    /// Returns the full explanation as plain text.
+    pub fn to_text(&self) -> String {
+        let indent = |code: String| {
+            code.lines()
+                .map(|line| if line.is_empty() { String::new() } else { format!("    {line}") })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        format!(
+            "{}\n\nExample:\n\n{}\n\nFixed example:\n\n{}",
+            self.description,
+            indent(self.example()),
+            indent(self.fixed_example())
+        )
+    }
+
+    /// Returns the full explanation as Markdown.
+    pub fn to_markdown(&self) -> String {
+        format!(
+            "{}\n\nExample:\n\n```circom\n{}\n```\n\nFixed example:\n\n```circom\n{}\n```",
+            self.description,
+            self.example(),
+            self.fixed_example()
+        )
+    }
+}
+
+/// Removes the leading empty line, trailing whitespace, and the common
+/// indentation from the given example.
+fn dedent(example: &str) -> String {
+    let example = example.strip_prefix('\n').unwrap_or(example).trim_end();
+    let indent = example
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or_default();
+    example
+        .lines()
+        .map(|line| line.get(indent..).unwrap_or_default().trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl ReportCode {
+    /// Returns a long-form explanation of the report code, or `None` for
+    /// custom report codes.
+    pub fn explanation(&self) -> Option<Explanation> {
+        use ReportCode::*;
+        let explanation = match self {
+            // Parser codes
+            ParseFail => Explanation::new(
+                "The file could not be parsed. This is typically caused by a syntax error, like a \
+                 missing semicolon or an unbalanced brace, or by an include statement referring \
+                 to a file that does not exist. Circomspect cannot analyze files that do not \
+                 parse.",
+                r#"
+                template Example() {
+                    signal input in;
+                    signal output out
+                    out <== in;
+                }
+                "#,
+                r#"
+                template Example() {
+                    signal input in;
+                    signal output out;
+                    out <== in;
+                }
+                "#,
+            ),
+            NoMainFoundInProject => Explanation::new(
+                "No main component was found. A Circom program must declare exactly one main \
+                 component, which is the entry point of the circuit. (Circomspect does not \
+                 require a main component when analyzing libraries.)",
+                r#"
+                template Example() {
+                    signal input in;
+                }
+                "#,
+                r#"
+                template Example() {
+                    signal input in;
+                }
+
+                component main = Example();
+                "#,
+            ),
+            MultipleMainInComponent => Explanation::new(
+                "More than one main component was declared. A Circom program must declare exactly \
+                 one main component, and included files must not declare a main component of \
+                 their own.",
+                r#"
+                component main = Example();
+                component main = Other();
+                "#,
+                r#"
+                component main = Example();
+                "#,
+            ),
+            CompilerVersionError => Explanation::new(
+                "The compiler version given by the `pragma circom` directive is not supported. \
+                 Circomspect supports programs written for Circom 2. Use a version pragma which \
+                 matches the compiler version used to build the circuit.",
+                r#"
+                pragma circom 1.0.0;
+                "#,
+                r#"
+                pragma circom 2.1.6;
+                "#,
+            ),
+            NoCompilerVersionWarning => Explanation::new(
+                "The file does not declare a compiler version. Without a `pragma circom` \
+                 directive it is unclear which version of the language the code was written for, \
+                 and different compiler versions may produce different circuits.",
+                r#"
+                include "circomlib/circuits/comparators.circom";
+
+                template Example() {}
+                "#,
+                r#"
+                pragma circom 2.1.6;
+
+                include "circomlib/circuits/comparators.circom";
+
+                template Example() {}
+                "#,
+            ),
+            // Type checker codes
+            WrongTypesInAssignOperation => Explanation::new(
+                "The type of the assigned expression does not match the type of the assigned \
+                 variable. For example, a template instantiation can only be assigned to a \
+                 component, and signals can only be assigned using `<--` or `<==`.",
+                r#"
+                signal output out;
+                out = 1;
+                "#,
+                r#"
+                signal output out;
+                out <== 1;
+                "#,
+            ),
+            WrongNumberOfArguments(..) => Explanation::new(
+                "A function or template is called with the wrong number of arguments. The number \
+                 of arguments must match the number of parameters of the called function or \
+                 template.",
+                r#"
+                component lt = LessThan(8, 1);
+                "#,
+                r#"
+                component lt = LessThan(8);
+                "#,
+            ),
+            UndefinedFunction => Explanation::new(
+                "A function is called, but no function with the given name is defined. Ensure \
+                 that the name is spelled correctly, and that the file defining the function is \
+                 included.",
+                r#"
+                var n = nbits(255);
+                "#,
+                r#"
+                include "circomlib/circuits/binsum.circom";
+
+                var n = nbits(255);
+                "#,
+            ),
+            UndefinedTemplate => Explanation::new(
+                "A template is instantiated, but no template with the given name is defined. \
+                 Ensure that the name is spelled correctly, and that the file defining the \
+                 template is included.",
+                r#"
+                component n2b = Num2Bits(8);
+                "#,
+                r#"
+                include "circomlib/circuits/bitify.circom";
+
+                component n2b = Num2Bits(8);
+                "#,
+            ),
+            UninitializedSymbolInExpression => Explanation::new(
+                "A variable is used before it is assigned a value. The value of an uninitialized \
+                 variable is undefined, and relying on it is almost certainly a mistake.",
+                r#"
+                var sum;
+                for (var i = 0; i < n; i++) {
+                    sum += in[i];
+                }
+                "#,
+                r#"
+                var sum = 0;
+                for (var i = 0; i < n; i++) {
+                    sum += in[i];
+                }
+                "#,
+            ),
+            UnableToTypeFunction => Explanation::new(
+                "The type of the return value of a function could not be determined. This \
+                 typically happens when a recursive function has no base case returning a \
+                 value.",
+                r#"
+                function f(n) {
+                    return f(n - 1);
+                }
+                "#,
+                r#"
+                function f(n) {
+                    if (n == 0) {
+                        return 0;
+                    }
+                    return f(n - 1);
+                }
+                "#,
+            ),
+            UnreachableConstraints => Explanation::new(
+                "Constraints are generated in a branch with a condition that depends on the value \
+                 of a signal. Since the constraint system is fixed when the circuit is compiled, \
+                 constraints may only depend on values known at compile time.",
+                r#"
+                if (in == 0) {
+                    out === 1;
+                }
+                "#,
+                r#"
+                component isZero = IsZero();
+                isZero.in <== in;
+                isZero.out * (out - 1) === 0;
+                "#,
+            ),
+            UnknownIndex => Explanation::new(
+                "An array index could not be evaluated when the circuit is compiled. Signals and \
+                 components must be accessed using indices which are known at compile time.",
+                r#"
+                out <== in[index];
+                "#,
+                r#"
+                component mux = Multiplexer(1, n);
+                for (var i = 0; i < n; i++) {
+                    mux.inp[i][0] <== in[i];
+                }
+                mux.sel <== index;
+                out <== mux.out[0];
+                "#,
+            ),
+            UnknownDimension => Explanation::new(
+                "The size of an array could not be evaluated when the circuit is compiled. The \
+                 dimensions of signal and component arrays must be known at compile time.",
+                r#"
+                signal input n;
+                signal bits[n];
+                "#,
+                r#"
+                template Example(n) {
+                    signal bits[n];
+                }
+                "#,
+            ),
+            SameFunctionDeclaredTwice => Explanation::new(
+                "Two functions with the same name are declared. Function names must be unique \
+                 across all included files.",
+                r#"
+                function square(x) { return x * x; }
+                function square(x) { return x ** 2; }
+                "#,
+                r#"
+                function square(x) { return x * x; }
+                "#,
+            ),
+            SameTemplateDeclaredTwice => Explanation::new(
+                "Two templates with the same name are declared. Template names must be unique \
+                 across all included files. This often happens when two versions of the same \
+                 library are included.",
+                r#"
+                include "lib/v1/comparators.circom";
+                include "lib/v2/comparators.circom";
+                "#,
+                r#"
+                include "lib/v2/comparators.circom";
+                "#,
+            ),
+            SameSymbolDeclaredTwice => Explanation::new(
+                "A symbol is declared twice in the same scope. Each variable, signal, and \
+                 component name may only be declared once per scope.",
+                r#"
+                signal input in;
+                signal input in;
+                "#,
+                r#"
+                signal input a;
+                signal input b;
+                "#,
+            ),
+            StaticInfoWasOverwritten => Explanation::new(
+                "Information which must be known at compile time (like the dimensions of a \
+                 component array) is assigned different values on different paths. This is \
+                 not supported by the compiler.",
+                r#"
+                component c[2];
+                c[0] = Num2Bits(8);
+                c[1] = Num2Bits(16);
+                "#,
+                r#"
+                component c[2];
+                c[0] = Num2Bits(8);
+                c[1] = Num2Bits(8);
+                "#,
+            ),
+            SignalInLineInitialization => Explanation::new(
+                "An input signal is initialized where it is declared. Input signals are assigned \
+                 by the caller (or the prover), and cannot be assigned by the template declaring \
+                 them.",
+                r#"
+                signal input in <== 0;
+                "#,
+                r#"
+                signal input in;
+                "#,
+            ),
+            SignalOutsideOriginalScope => Explanation::new(
+                "A signal is declared inside a nested block, like a loop or a branch. Signals \
+                 must be declared in the top-level scope of the template, so that the set of \
+                 signals is known when the circuit is compiled.",
+                r#"
+                for (var i = 0; i < n; i++) {
+                    signal square;
+                    square <== in[i] * in[i];
+                }
+                "#,
+                r#"
+                signal square[n];
+                for (var i = 0; i < n; i++) {
+                    square[i] <== in[i] * in[i];
+                }
+                "#,
+            ),
+            FunctionWrongNumberOfArguments => Explanation::new(
+                "A function is called with the wrong number of arguments. The number of \
+                 arguments must match the number of parameters of the function.",
+                r#"
+                function square(x) { return x * x; }
+
+                var y = square(x, 2);
+                "#,
+                r#"
+                function square(x) { return x * x; }
+
+                var y = square(x);
+                "#,
+            ),
+            FunctionInconsistentTyping => Explanation::new(
+                "A function returns values of different types (for example, a number on one \
+                 path and an array on another). All return statements in a function must return \
+                 values of the same type and dimension.",
+                r#"
+                function f(n) {
+                    if (n == 0) {
+                        return 0;
+                    }
+                    return [n, n];
+                }
+                "#,
+                r#"
+                function f(n) {
+                    if (n == 0) {
+                        return [0, 0];
+                    }
+                    return [n, n];
+                }
+                "#,
+            ),
+            FunctionPathWithoutReturn => Explanation::new(
+                "There is a path through the function which does not end in a return \
+                 statement. Every path through a function must return a value.",
+                r#"
+                function sign(x) {
+                    if (x > 0) {
+                        return 1;
+                    }
+                }
+                "#,
+                r#"
+                function sign(x) {
+                    if (x > 0) {
+                        return 1;
+                    }
+                    return 0;
+                }
+                "#,
+            ),
+            FunctionReturnError => Explanation::new(
+                "A return statement is used incorrectly. Functions must return a value, and \
+                 return statements are not allowed outside of functions.",
+                r#"
+                function f(x) {
+                    return;
+                }
+                "#,
+                r#"
+                function f(x) {
+                    return x;
+                }
+                "#,
+            ),
+            ForbiddenDeclarationInFunction => Explanation::new(
+                "A signal or component is declared inside a function. Functions can only \
+                 compute values, and cannot declare signals or components (and therefore \
+                 cannot generate constraints).",
+                r#"
+                function f(x) {
+                    signal y;
+                    return x;
+                }
+                "#,
+                r#"
+                function f(x) {
+                    var y = x;
+                    return y;
+                }
+                "#,
+            ),
+            NonHomogeneousArray => Explanation::new(
+                "An inline array contains elements of different types or dimensions. All \
+                 elements of an array must have the same type and dimension.",
+                r#"
+                var a[2] = [1, [2, 3]];
+                "#,
+                r#"
+                var a[2][2] = [[1, 0], [2, 3]];
+                "#,
+            ),
+            NonBooleanCondition => Explanation::new(
+                "A condition is not a boolean expression. Conditions of branches, loops, and \
+                 inline switches must be comparisons or boolean expressions.",
+                r#"
+                if (x) {
+                    y = 1;
+                }
+                "#,
+                r#"
+                if (x != 0) {
+                    y = 1;
+                }
+                "#,
+            ),
+            NonCompatibleBranchTypes => Explanation::new(
+                "The two branches of an inline switch (`cond ? a : b`) have different types or \
+                 dimensions. Both branches must evaluate to values of the same type.",
+                r#"
+                var a[2] = x > 0 ? [1, 1] : 0;
+                "#,
+                r#"
+                var a[2] = x > 0 ? [1, 1] : [0, 0];
+                "#,
+            ),
+            NonEqualTypesInExpression => Explanation::new(
+                "The operands of an expression have different types or dimensions. For example, \
+                 an array cannot be added to a field element.",
+                r#"
+                var a[2] = [1, 2];
+                var b = a + 1;
+                "#,
+                r#"
+                var a[2] = [1, 2];
+                var b = a[0] + 1;
+                "#,
+            ),
+            NonExistentSymbol => Explanation::new(
+                "A symbol is used, but no variable, signal, or component with the given name is \
+                 declared in scope. Ensure that the name is spelled correctly, and that it is \
+                 declared before it is used.",
+                r#"
+                signal input in;
+                signal output out;
+                out <== inp;
+                "#,
+                r#"
+                signal input in;
+                signal output out;
+                out <== in;
+                "#,
+            ),
+            TemplateCallAsArgument => Explanation::new(
+                "A template instantiation is passed as an argument to a function or template. \
+                 Templates can only be instantiated by assigning them to a component.",
+                r#"
+                var n = f(Num2Bits(8));
+                "#,
+                r#"
+                component n2b = Num2Bits(8);
+                "#,
+            ),
+            TemplateWrongNumberOfArguments => Explanation::new(
+                "A template is instantiated with the wrong number of arguments. The number of \
+                 arguments must match the number of parameters of the template.",
+                r#"
+                component n2b = Num2Bits();
+                "#,
+                r#"
+                component n2b = Num2Bits(8);
+                "#,
+            ),
+            TemplateWithReturnStatement => Explanation::new(
+                "A template contains a return statement. Templates define signals and \
+                 constraints, and cannot return values. Use output signals to pass values to \
+                 the caller.",
+                r#"
+                template Square() {
+                    signal input in;
+                    return in * in;
+                }
+                "#,
+                r#"
+                template Square() {
+                    signal input in;
+                    signal output out;
+                    out <== in * in;
+                }
+                "#,
+            ),
+            TypeCantBeUseAsCondition => Explanation::new(
+                "An expression which cannot be used as a condition (like an array or a \
+                 component) is used as the condition of a branch, loop, or inline switch.",
+                r#"
+                var a[2] = [0, 1];
+                if (a) {
+                    x = 1;
+                }
+                "#,
+                r#"
+                var a[2] = [0, 1];
+                if (a[1] == 1) {
+                    x = 1;
+                }
+                "#,
+            ),
+            EmptyArrayInlineDeclaration => Explanation::new(
+                "An empty inline array is declared. Inline arrays must contain at least one \
+                 element, since the type of the array is inferred from its elements.",
+                r#"
+                var a[0] = [];
+                "#,
+                r#"
+                var a[1] = [0];
+                "#,
+            ),
+            PrefixOperatorWithWrongTypes => Explanation::new(
+                "A prefix operator (`-`, `!`, or `~`) is applied to an operand of the wrong \
+                 type, like an array or a component.",
+                r#"
+                var a[2] = [1, 2];
+                var b = -a;
+                "#,
+                r#"
+                var a[2] = [1, 2];
+                var b = -a[0];
+                "#,
+            ),
+            InfixOperatorWithWrongTypes => Explanation::new(
+                "An infix operator is applied to operands of the wrong type. Arithmetic, \
+                 comparison, and boolean operators can only be applied to field elements.",
+                r#"
+                var a[2] = [1, 2];
+                var b[2] = [3, 4];
+                var c = a * b;
+                "#,
+                r#"
+                var a[2] = [1, 2];
+                var b[2] = [3, 4];
+                var c = a[0] * b[0] + a[1] * b[1];
+                "#,
+            ),
+            InvalidArgumentInCall => Explanation::new(
+                "An argument of the wrong type is passed to a function or template. For \
+                 example, components cannot be passed as arguments.",
+                r#"
+                component c = Num2Bits(8);
+                var n = f(c);
+                "#,
+                r#"
+                component c = Num2Bits(8);
+                var n = f(8);
+                "#,
+            ),
+            InconsistentReturnTypesInBlock => Explanation::new(
+                "Return statements in the same block return values of different types or \
+                 dimensions. All return statements must return values of the same type.",
+                r#"
+                if (n == 0) {
+                    return 0;
+                } else {
+                    return [1, 1];
+                }
+                "#,
+                r#"
+                if (n == 0) {
+                    return [0, 0];
+                } else {
+                    return [1, 1];
+                }
+                "#,
+            ),
+            InconsistentStaticInformation => Explanation::new(
+                "Information which must be known at compile time (like array dimensions or \
+                 signal tags) is inconsistent between different uses of the same symbol.",
+                r#"
+                component c[2];
+                c[0] = Num2Bits(8);
+                c[1] = LessThan(8);
+                "#,
+                r#"
+                component c[2];
+                c[0] = Num2Bits(8);
+                c[1] = Num2Bits(8);
+                "#,
+            ),
+            InvalidArrayAccess => Explanation::new(
+                "An array is accessed with too many indices, or a value which is not an array \
+                 is indexed.",
+                r#"
+                signal input in[2];
+                out <== in[0][1];
+                "#,
+                r#"
+                signal input in[2];
+                out <== in[1];
+                "#,
+            ),
+            InvalidSignalAccess => Explanation::new(
+                "A signal which does not exist is accessed on a component, or a signal of a \
+                 component is accessed in a way which is not allowed (for example, reading an \
+                 input signal of a sub-component before it is assigned).",
+                r#"
+                component n2b = Num2Bits(8);
+                n2b.input <== in;
+                "#,
+                r#"
+                component n2b = Num2Bits(8);
+                n2b.in <== in;
+                "#,
+            ),
+            InvalidArraySize => Explanation::new(
+                "The size of an array is not a valid array size. Array sizes must be \
+                 non-negative integers, and the number of elements in an inline array must \
+                 match the declared size.",
+                r#"
+                var a[2] = [1, 2, 3];
+                "#,
+                r#"
+                var a[3] = [1, 2, 3];
+                "#,
+            ),
+            InvalidArrayType => Explanation::new(
+                "An array has an invalid type. For example, arrays of mixed signals and \
+                 variables are not supported.",
+                r#"
+                signal input in;
+                var a[2] = [in, 1];
+                signal s[2] <== a;
+                "#,
+                r#"
+                signal input in;
+                signal s[2];
+                s[0] <== in;
+                s[1] <== 1;
+                "#,
+            ),
+            ForStatementIllConstructed => Explanation::new(
+                "A for-loop is not constructed correctly. A for-loop must have an \
+                 initialization, a condition, and a step.",
+                r#"
+                for (var i = 0; i < n) {
+                    sum += in[i];
+                }
+                "#,
+                r#"
+                for (var i = 0; i < n; i++) {
+                    sum += in[i];
+                }
+                "#,
+            ),
+            BadArrayAccess => Explanation::new(
+                "An array is accessed in a way which is not allowed, like indexing a signal \
+                 array using an index which is not a field element.",
+                r#"
+                var index[2] = [0, 1];
+                out <== in[index];
+                "#,
+                r#"
+                var index[2] = [0, 1];
+                out <== in[index[0]];
+                "#,
+            ),
+            AssigningAComponentTwice => Explanation::new(
+                "A component is instantiated twice. Each component can only be assigned a \
+                 template instantiation once.",
+                r#"
+                component n2b = Num2Bits(8);
+                n2b = Num2Bits(16);
+                "#,
+                r#"
+                component n2b = Num2Bits(16);
+                "#,
+            ),
+            AssigningASignalTwice => Explanation::new(
+                "A signal is assigned twice. Each signal can only be assigned once, since the \
+                 witness can only contain a single value for each signal.",
+                r#"
+                out <== a;
+                out <== b;
+                "#,
+                r#"
+                out <== a;
+                out === b;
+                "#,
+            ),
+            NotAllowedOperation => Explanation::new(
+                "An operation which is not allowed in this context is used. For example, \
+                 constraints can only be generated in templates, and sub-component inputs \
+                 cannot be assigned using `=`.",
+                r#"
+                component n2b = Num2Bits(8);
+                n2b.in = in;
+                "#,
+                r#"
+                component n2b = Num2Bits(8);
+                n2b.in <== in;
+                "#,
+            ),
+            ConstraintGeneratorInFunction => Explanation::new(
+                "A function contains a statement which generates constraints (like `===` or \
+                 `<==`). Functions cannot generate constraints. Use a template instead.",
+                r#"
+                function square(x) {
+                    x * x === 1;
+                    return x * x;
+                }
+                "#,
+                r#"
+                template Square() {
+                    signal input in;
+                    signal output out;
+                    out <== in * in;
+                }
+                "#,
+            ),
+            WrongSignalTags => Explanation::new(
+                "A signal tag is used incorrectly. For example, a signal with a tag is assigned \
+                 a value from a signal which does not have the tag, or the tags of a \
+                 sub-component input are not satisfied.",
+                r#"
+                signal input {binary} in;
+                signal {binary} out;
+                out <== in + 1;
+                "#,
+                r#"
+                signal input {binary} in;
+                signal {binary} out;
+                out <== 1 - in;
+                "#,
+            ),
+            AssertWrongType => Explanation::new(
+                "The argument of an assert statement is not a boolean expression. Assert \
+                 statements take a single comparison or boolean expression.",
+                r#"
+                assert(n);
+                "#,
+                r#"
+                assert(n > 0);
+                "#,
+            ),
+            InvalidPartialArray => Explanation::new(
+                "Part of an array is assigned or used in a way which is not supported, like \
+                 assigning a sub-array of a signal array using an expression of the wrong \
+                 dimension.",
+                r#"
+                signal output out[2][2];
+                out[0] <== [1, 2, 3];
+                "#,
+                r#"
+                signal output out[2][2];
+                out[0] <== [1, 2];
+                "#,
+            ),
+            MustBeSingleArithmetic => Explanation::new(
+                "An expression which must evaluate to a single field element (like an array \
+                 index or a template argument) evaluates to an array.",
+                r#"
+                var n[2] = [8, 8];
+                component n2b = Num2Bits(n);
+                "#,
+                r#"
+                var n[2] = [8, 8];
+                component n2b = Num2Bits(n[0]);
+                "#,
+            ),
+            ExpectedDimDiffGotDim(..) => Explanation::new(
+                "An expression has the wrong number of dimensions. For example, an array of \
+                 dimension two is assigned to a variable of dimension one.",
+                r#"
+                var a[2] = [[1, 2], [3, 4]];
+                "#,
+                r#"
+                var a[2][2] = [[1, 2], [3, 4]];
+                "#,
+            ),
+            RuntimeError => Explanation::new(
+                "An error occurred while evaluating the program. This could for example be an \
+                 out-of-bounds array access or a division by zero in an expression evaluated \
+                 at compile time.",
+                r#"
+                var a[2] = [1, 2];
+                var b = a[2];
+                "#,
+                r#"
+                var a[2] = [1, 2];
+                var b = a[1];
+                "#,
+            ),
+            UnknownTemplate => Explanation::new(
+                "The template instantiated by a component could not be determined at compile \
+                 time. Each component must be assigned a single, statically known template.",
+                r#"
+                component c;
+                if (in == 0) {
+                    c = Num2Bits(8);
+                } else {
+                    c = Num2Bits(16);
+                }
+                "#,
+                r#"
+                component c;
+                if (n == 0) {
+                    c = Num2Bits(8);
+                } else {
+                    c = Num2Bits(16);
+                }
+                "#,
+            ),
+            NonQuadratic => Explanation::new(
+                "A constraint is not quadratic. Circom can only express constraints of the form \
+                 `A * B + C = 0`, where `A`, `B`, and `C` are linear combinations of signals. \
+                 Introduce intermediate signals to split the constraint into quadratic \
+                 constraints.",
+                r#"
+                out <== a * b * c;
+                "#,
+                r#"
+                signal ab;
+                ab <== a * b;
+                out <== ab * c;
+                "#,
+            ),
+            NonConstantArrayLength => Explanation::new(
+                "The length of an array is not known at compile time. Signal and component \
+                 arrays must have constant dimensions.",
+                r#"
+                signal input n;
+                signal bits[n];
+                "#,
+                r#"
+                template Example(n) {
+                    signal bits[n];
+                }
+                "#,
+            ),
+            NonComputableExpression => Explanation::new(
+                "An expression which must be evaluated at compile time (like a template \
+                 argument or an array dimension) depends on the value of a signal.",
+                r#"
+                signal input n;
+                component n2b = Num2Bits(n);
+                "#,
+                r#"
+                template Example(n) {
+                    component n2b = Num2Bits(n);
+                }
+                "#,
+            ),
+            AnonymousComponentError => Explanation::new(
+                "An anonymous component is used incorrectly. Anonymous components must be \
+                 given the correct number of input signals, and their outputs must be assigned \
+                 using `<==` or `==>`.",
+                r#"
+                signal out;
+                out = Num2Bits(8)(in);
+                "#,
+                r#"
+                signal out[8];
+                out <== Num2Bits(8)(in);
+                "#,
+            ),
+            TupleError => Explanation::new(
+                "A tuple is used incorrectly. Tuples can only be used on the left-hand side of \
+                 an assignment from an anonymous component, and must contain one element for \
+                 each output signal of the component.",
+                r#"
+                signal a;
+                (a, _) <== Num2Bits(1)(in);
+                "#,
+                r#"
+                signal a;
+                (a) <== Num2Bits(1)(in);
+                "#,
+            ),
+            // Constraint analysis codes
+            UnconstrainedSignal => Explanation::new(
+                "A signal does not occur in any constraint. The value of an unconstrained \
+                 signal can be chosen freely by a malicious prover, which typically means that \
+                 the circuit is under-constrained.",
+                r#"
+                signal input in;
+                signal output out;
+                out <-- in * in;
+                "#,
+                r#"
+                signal input in;
+                signal output out;
+                out <== in * in;
+                "#,
+            ),
+            OneConstraintIntermediate => Explanation::new(
+                "An intermediate signal only occurs in a single constraint. Since intermediate \
+                 signals are not visible outside the template, a signal constrained by a single \
+                 constraint can typically be chosen freely by a malicious prover.",
+                r#"
+                signal inv;
+                inv <-- 1 / in;
+                "#,
+                r#"
+                signal inv;
+                inv <-- 1 / in;
+                inv * in === 1;
+                "#,
+            ),
+            NoOutputInInstance => Explanation::new(
+                "The instantiated template does not declare any output signals. Templates \
+                 without outputs can still constrain their inputs, but this may indicate that \
+                 the template is incomplete.",
+                r#"
+                template Square() {
+                    signal input in;
+                    signal sq <== in * in;
+                }
+                "#,
+                r#"
+                template Square() {
+                    signal input in;
+                    signal output out <== in * in;
+                }
+                "#,
+            ),
+            ErrorWat2Wasm => Explanation::new(
+                "The generated WebAssembly text could not be converted to WebAssembly. This \
+                 indicates an issue with the compiler toolchain rather than the analyzed code. \
+                 Ensure that `wat2wasm` is installed and up to date.",
+                r#"
+                component main = Example();
+                "#,
+                r#"
+                component main = Example();
+                "#,
+            ),
+            // Circomspect specific codes
+            ShadowingVariable => Explanation::new(
+                "A variable declaration shadows a previous declaration of a variable with the \
+                 same name. This does not have to be a problem, but if a variable declared in an \
+                 outer scope is shadowed by mistake, updates intended for the outer variable are \
+                 silently lost.",
+                r#"
+                function numberOfBits(a) {
+                    var n = 1;
+                    var r = 0;
+                    while (n - 1 < a) {
+                        var r = r + 1;
+                        n *= 2;
+                    }
+                    return r;
+                }
+                "#,
+                r#"
+                function numberOfBits(a) {
+                    var n = 1;
+                    var r = 0;
+                    while (n - 1 < a) {
+                        r = r + 1;
+                        n *= 2;
+                    }
+                    return r;
+                }
+                "#,
+            ),
+            ParameterNameCollision => Explanation::new(
+                "A function or template declares two parameters with the same name. Only one of \
+                 the corresponding arguments can be accessed in the body, which is almost \
+                 certainly a mistake.",
+                r#"
+                template Range(n, n) {
+                    signal input in;
+                }
+                "#,
+                r#"
+                template Range(n, m) {
+                    signal input in;
+                }
+                "#,
+            ),
+            FieldElementComparison => Explanation::new(
+                "Field elements are normalized to the interval `(-p/2, p/2]` before they are \
+                 compared. In particular, `p/2 + 1 < 0 < p/2 - 1`. This can be surprising if \
+                 you are used to thinking of field elements as unsigned integers, and may lead \
+                 to comparisons with unexpected results for large values.",
+                r#"
+                var x = -1;
+                if (x > 0) {
+                    // Never taken, since `-1 = p - 1` is normalized to `-1`.
+                }
+                "#,
+                r#"
+                var x = -1;
+                // Compare values known to be in the range [0, p/2].
+                if (x != 0) {
+                }
+                "#,
+            ),
+            FieldElementArithmetic => Explanation::new(
+                "Arithmetic on field elements is performed modulo the prime `p`, and may \
+                 overflow or underflow. Ensure that the operands are constrained to a range \
+                 where the result is the expected one.",
+                r#"
+                signal input a;
+                signal input b;
+                signal output diff;
+                diff <== a - b;
+                "#,
+                r#"
+                signal input a;
+                signal input b;
+                signal output diff;
+                component ge = GreaterEqThan(64);
+                ge.in <== [a, b];
+                ge.out === 1;
+                diff <== a - b;
+                "#,
+            ),
+            FieldElementOverflow => Explanation::new(
+                "Arithmetic on field elements is performed modulo the prime `p`. Circomspect \
+                 has inferred ranges for the operands of the expression, and the result may \
+                 overflow past `p` (or `p/2`, when interpreted as a signed value). Ensure that \
+                 the result is the expected one, or constrain the operands to a smaller range.",
+                r#"
+                var x = 2**200;
+                var y = x * x;
+                "#,
+                r#"
+                var x = 2**100;
+                var y = x * x;
+                "#,
+            ),
+            SignalAssignmentStatement => Explanation::new(
+                "A signal is assigned using the signal assignment operator `<--`, which does not \
+                 generate a constraint. It is up to the developer to ensure that the signal is \
+                 properly constrained elsewhere. If the signal is not constrained, a malicious \
+                 prover can assign it any value.",
+                r#"
+                signal input in;
+                signal output isZero;
+                signal inv;
+                inv <-- in != 0 ? 1 / in : 0;
+                isZero <-- -in * inv + 1;
+                "#,
+                r#"
+                signal input in;
+                signal output isZero;
+                signal inv;
+                inv <-- in != 0 ? 1 / in : 0;
+                isZero <== -in * inv + 1;
+                in * isZero === 0;
+                "#,
+            ),
+            UnnecessarySignalAssignment => Explanation::new(
+                "A signal is assigned a quadratic expression using the signal assignment \
+                 operator `<--`. Since the expression is quadratic, the assignment can be \
+                 rewritten using the constraint assignment operator `<==`, which also \
+                 generates the corresponding constraint.",
+                r#"
+                signal output out;
+                out <-- a * b;
+                out === a * b;
+                "#,
+                r#"
+                signal output out;
+                out <== a * b;
+                "#,
+            ),
+            UnusedVariableValue => Explanation::new(
+                "A value assigned to a variable is never read. This typically indicates a \
+                 mistake, like updating the wrong variable, or an incomplete implementation.",
+                r#"
+                var sum = 0;
+                var total = 0;
+                for (var i = 0; i < n; i++) {
+                    sum += in[i];
+                }
+                out <== total;
+                "#,
+                r#"
+                var sum = 0;
+                for (var i = 0; i < n; i++) {
+                    sum += in[i];
+                }
+                out <== sum;
+                "#,
+            ),
+            UnusedParameterValue => Explanation::new(
+                "A parameter of a function or template is never read. This typically indicates \
+                 a mistake, like using a hardcoded value instead of the parameter.",
+                r#"
+                template Bits(n) {
+                    signal input in;
+                    component n2b = Num2Bits(8);
+                    n2b.in <== in;
+                }
+                "#,
+                r#"
+                template Bits(n) {
+                    signal input in;
+                    component n2b = Num2Bits(n);
+                    n2b.in <== in;
+                }
+                "#,
+            ),
+            VariableWithoutSideEffect => Explanation::new(
+                "An assigned value does not contribute (directly or indirectly) to a constraint, \
+                 a signal, or a function return value. Since the value does not influence the \
+                 generated circuit, this typically indicates a mistake in the implementation.",
+                r#"
+                var lout = 0;
+                for (var k = 0; k < n; k++) {
+                    lout += out[k] * 2 ** k;
+                }
+                lin === n;
+                "#,
+                r#"
+                var lout = 0;
+                for (var k = 0; k < n; k++) {
+                    lout += out[k] * 2 ** k;
+                }
+                lin === lout;
+                "#,
+            ),
+            ConstantBranchCondition => Explanation::new(
+                "The condition of a branch always evaluates to either true or false. This means \
+                 that the branch is either always taken, or never taken, which typically \
+                 indicates a mistake in the code.",
+                r#"
+                var n = 8;
+                if (n > 16) {
+                    out <== in * in;
+                } else {
+                    out <== in;
+                }
+                "#,
+                r#"
+                if (m > 16) {
+                    out <== in * in;
+                } else {
+                    out <== in;
+                }
+                "#,
+            ),
+            NonStrictBinaryConversion => Explanation::new(
+                "`Num2Bits` or `Bits2Num` is used with a bit size which is not smaller than the \
+                 size of the prime. In this case there are multiple valid binary \
+                 representations of some field elements (e.g. both `x` and `x + p`), which \
+                 means that the output of the circuit is not uniquely determined by the input. \
+                 Use `Num2Bits_strict` or `Bits2Num_strict` instead.",
+                r#"
+                component n2b = Num2Bits(254);
+                n2b.in <== in;
+                "#,
+                r#"
+                component n2b = Num2Bits_strict();
+                n2b.in <== in;
+                "#,
+            ),
+            CyclomaticComplexity => Explanation::new(
+                "The function or template has a high cyclomatic complexity, which makes it \
+                 difficult to review and maintain. Consider splitting it into smaller, more \
+                 easily understandable functions or templates.",
+                r#"
+                function f(x) {
+                    if (x == 0) { return 1; }
+                    if (x == 1) { return 2; }
+                    // Many more branches...
+                    return 0;
+                }
+                "#,
+                r#"
+                function lookup(x) {
+                    var table[2] = [1, 2];
+                    return x < 2 ? table[x] : 0;
+                }
+                "#,
+            ),
+            TooManyArguments => Explanation::new(
+                "The function or template takes a large number of parameters, which makes it \
+                 difficult to use correctly and to review. Consider grouping related parameters \
+                 into arrays, or splitting the function or template.",
+                r#"
+                template Example(a, b, c, d, e, f, g, h) {}
+                "#,
+                r#"
+                template Example(params) {}
+                "#,
+            ),
+            UnconstrainedLessThan => Explanation::new(
+                "The inputs to the Circomlib `LessThan` template are not constrained to be \
+                 non-negative. If the inputs may be large (e.g. `p - 254`), `LessThan` can \
+                 evaluate to true even when the first input is greater than the second, when \
+                 viewed as unsigned integers. Constrain each input to the given bit size using \
+                 `Num2Bits`.",
+                r#"
+                component lt = LessThan(8);
+                lt.in[0] <== in;
+                lt.in[1] <== 2;
+                lt.out === 1;
+                "#,
+                r#"
+                component n2b = Num2Bits(8);
+                n2b.in <== in;
+
+                component lt = LessThan(8);
+                lt.in[0] <== in;
+                lt.in[1] <== 2;
+                lt.out === 1;
+                "#,
+            ),
+            UnconstrainedDivision => Explanation::new(
+                "A signal is assigned the result of a division, but the divisor is not \
+                 constrained to be non-zero. The constraint `c * b === a` is satisfied for any \
+                 `c` when `a` and `b` are both zero, which allows a malicious prover to choose \
+                 the value of `c`. Constrain the divisor to be non-zero using the Circomlib \
+                 `IsZero` template.",
+                r#"
+                c <-- a / b;
+                c * b === a;
+                "#,
+                r#"
+                component isZero = IsZero();
+                isZero.in <== b;
+                isZero.out === 0;
+
+                c <-- a / b;
+                c * b === a;
+                "#,
+            ),
+            Bn254SpecificCircuit => Explanation::new(
+                "A Circomlib template which hardcodes constants specific to the BN254 scalar \
+                 field is used together with a different curve. Templates like `Sign`, \
+                 `AliasCheck`, and the strict versions of `Num2Bits` and `Bits2Num` may give \
+                 unexpected results when used with other primes.",
+                r#"
+                // Compiled using `--prime goldilocks`.
+                component n2b = Num2Bits_strict();
+                n2b.in <== in;
+                "#,
+                r#"
+                // Compiled using `--prime goldilocks`.
+                component n2b = Num2Bits(63);
+                n2b.in <== in;
+                "#,
+            ),
+            UnderConstrainedSignal => Explanation::new(
+                "An intermediate signal occurs in fewer constraints than expected (by default, \
+                 a single constraint). Since intermediate signals are not available outside the \
+                 template, this typically means that the signal can be chosen freely by a \
+                 malicious prover.",
+                r#"
+                signal input in;
+                signal output out;
+                signal inv;
+                inv <-- 1 / in;
+                out <== in;
+                "#,
+                r#"
+                signal input in;
+                signal output out;
+                signal inv;
+                inv <-- 1 / in;
+                inv * in === 1;
+                out <== in;
+                "#,
+            ),
+            UnusedOutputSignal => Explanation::new(
+                "An output signal of an instantiated template is never used. Failing to \
+                 constrain the output of a sub-component typically indicates a mistake, like \
+                 forgetting to check the result of a comparison. (Some templates, like \
+                 `Num2Bits`, may be used only to constrain their inputs. Such templates can be \
+                 added to the `allow-list` in the configuration file.)",
+                r#"
+                component lt = LessThan(8);
+                lt.in[0] <== a;
+                lt.in[1] <== b;
+                "#,
+                r#"
+                component lt = LessThan(8);
+                lt.in[0] <== a;
+                lt.in[1] <== b;
+                lt.out === 1;
+                "#,
+            ),
+            UnusedSuppression => Explanation::new(
+                "An inline suppression comment does not suppress any results. The suppression \
+                 is either misspelled or no longer needed, and should be removed to ensure that \
+                 it does not hide new issues introduced in the future.",
+                r#"
+                // circomspect-disable-next-line CS0005 reason: constrained below
+                out <== in * in;
+                "#,
+                r#"
+                out <== in * in;
+                "#,
+            ),
+            Custom { .. } => return None,
+        };
+        Some(explanation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explanations() {
+        for code in ReportCode::builtin_codes() {
+            let explanation = code.explanation().expect("missing explanation");
+            assert!(!explanation.description().is_empty(), "empty description for {}", code.id());
+            assert!(!explanation.example().is_empty(), "empty example for {}", code.id());
+            assert!(!explanation.fixed_example().is_empty(), "empty fix for {}", code.id());
+        }
+        let custom = ReportCode::Custom { id: "TEST001", name: "test" };
+        assert!(custom.explanation().is_none());
+    }
+
+    #[test]
+    fn test_dedent() {
+        let example = "\n    template T() {\n\n        signal a;  \n    }\n    ";
+        assert_eq!(dedent(example), "template T() {\n\n    signal a;\n}");
+
+        let explanation = ReportCode::UnusedSuppression.explanation().unwrap();
+        assert!(explanation.to_text().contains("\n\nFixed example:\n\n    out <== in * in;"));
+        assert!(explanation.to_markdown().ends_with("```circom\nout <== in * in;\n```"));
+    }
+}
@@ -0,0 +1,259 @@
+use codespan_reporting::files::Files;
+
+use super::file_definition::{Comment, FileID, FileLibrary, FileLocation};
+use super::report::Report;
+use super::report_code::ReportCode;
+
+const DISABLE_NEXT_LINE: &str = "circomspect-disable-next-line";
+const DISABLE_LINE: &str = "circomspect-disable-line";
+const DISABLE: &str = "circomspect-disable";
+const REASON: &str = "reason:";
+
+/// The scope of an inline suppression comment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SuppressionKind {
+    /// `// circomspect-disable-next-line ID ...` suppresses results on the
+    /// line following the comment.
+    NextLine,
+    /// `// circomspect-disable-line ID ...` suppresses results on the same
+    /// line as the comment.
+    Line,
+    /// `// circomspect-disable ID ...` suppresses results in the enclosing
+    /// function or template, or (if the comment is not contained in a
+    /// definition) in the next function or template in the file.
+    Definition,
+}
+
+/// An inline suppression comment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suppression {
+    kind: SuppressionKind,
+    file_id: FileID,
+    /// The location of the suppression comment.
+    comment_location: FileLocation,
+    /// The source code covered by the suppression.
+    covered_location: FileLocation,
+    /// Report IDs or names suppressed. If empty, all reports are suppressed.
+    codes: Vec<String>,
+    /// The reason given for the suppression (if any).
+    reason: Option<String>,
+}
+
+impl Suppression {
+    /// Parses the directive in the given comment. Returns `None` if the
+    /// comment does not contain a suppression directive, or if the scope of
+    /// the directive could not be determined.
+    ///
+    /// The `definitions` argument contains the locations of all functions and
+    /// templates defined in the file.
+    pub fn from_comment(
+        comment: &Comment,
+        file_id: FileID,
+        file_library: &FileLibrary,
+        definitions: &[FileLocation],
+    ) -> Option<Suppression> {
+        let text = comment.text().trim();
+        // Note that `DISABLE` is a prefix of the other two directives.
+        let (kind, arguments) = if let Some(arguments) = text.strip_prefix(DISABLE_NEXT_LINE) {
+            (SuppressionKind::NextLine, arguments)
+        } else if let Some(arguments) = text.strip_prefix(DISABLE_LINE) {
+            (SuppressionKind::Line, arguments)
+        } else if let Some(arguments) = text.strip_prefix(DISABLE) {
+            (SuppressionKind::Definition, arguments)
+        } else {
+            return None;
+        };
+        if !arguments.is_empty() && !arguments.starts_with(char::is_whitespace) {
+            // This is some other word starting with the directive.
+            return None;
+        }
+        let (codes, reason) = match arguments.split_once(REASON) {
+            Some((codes, reason)) => (codes, Some(reason.trim().to_string())),
+            None => (arguments, None),
+        };
+        let codes = codes
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|code| !code.is_empty())
+            .map(ToString::to_string)
+            .collect();
+
+        let files = file_library.to_storage();
+        let comment_location = comment.location().clone();
+        let covered_location = match kind {
+            SuppressionKind::NextLine => {
+                let line = files.line_index(file_id, comment_location.end).ok()?;
+                files.line_range(file_id, line + 1).ok()?
+            }
+            SuppressionKind::Line => {
+                let line = files.line_index(file_id, comment_location.start).ok()?;
+                files.line_range(file_id, line).ok()?
+            }
+            SuppressionKind::Definition => definitions
+                .iter()
+                .find(|definition| {
+                    definition.start <= comment_location.start
+                        && comment_location.end <= definition.end
+                })
+                .or_else(|| {
+                    definitions
+                        .iter()
+                        .filter(|definition| definition.start >= comment_location.end)
+                        .min_by_key(|definition| definition.start)
+                })?
+                .clone(),
+        };
+        Some(Suppression { kind, file_id, comment_location, covered_location, codes, reason })
+    }
+
+    pub fn kind(&self) -> &SuppressionKind {
+        &self.kind
+    }
+
+    pub fn file_id(&self) -> FileID {
+        self.file_id
+    }
+
+    pub fn comment_location(&self) -> &FileLocation {
+        &self.comment_location
+    }
+
+    pub fn covered_location(&self) -> &FileLocation {
+        &self.covered_location
+    }
+
+    pub fn codes(&self) -> &[String] {
+        &self.codes
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /// Returns true if the suppression covers a primary location of the
+    /// given report.
+    pub fn suppresses(&self, report: &Report) -> bool {
+        let matches_code = self.codes.is_empty()
+            || self.codes.iter().any(|code| *code == report.id() || *code == report.name());
+        matches_code
+            && report.primary().iter().any(|label| {
+                label.file_id == self.file_id
+                    && self.covered_location.start <= label.range.start
+                    && label.range.start < self.covered_location.end
+            })
+    }
+
+    /// Returns a report indicating that the suppression was never used.
+    pub fn unused_report(&self) -> Report {
+        let mut report = Report::warning(
+            "This suppression comment does not suppress any results.".to_string(),
+            ReportCode::UnusedSuppression,
+        );
+        report.add_primary(
+            self.comment_location.clone(),
+            self.file_id,
+            "This suppression is unused.".to_string(),
+        );
+        report.add_note("Consider removing the comment if it is no longer needed.".to_string());
+        report
+    }
+}
+
+/// Returns all suppressions defined by the comments in the given file.
+pub fn find_suppressions(
+    file_id: FileID,
+    file_library: &FileLibrary,
+    definitions: &[FileLocation],
+) -> Vec<Suppression> {
+    file_library
+        .comments(file_id)
+        .iter()
+        .filter_map(|comment| {
+            Suppression::from_comment(comment, file_id, file_library, definitions)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suppressions(src: &str, definitions: &[FileLocation]) -> (FileLibrary, Vec<Suppression>) {
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file("test.circom".to_string(), src.to_string(), true);
+        // Find line comments in the source.
+        let mut comments = Vec::new();
+        let mut offset = 0;
+        for line in src.split_inclusive('\n') {
+            if let Some(start) = line.find("//") {
+                let end = line.trim_end_matches('\n').len();
+                comments.push(Comment::new(
+                    line[start + 2..end].to_string(),
+                    offset + start..offset + end,
+                ));
+            }
+            offset += line.len();
+        }
+        file_library.add_comments(file_id, comments);
+        let suppressions = find_suppressions(file_id, &file_library, definitions);
+        (file_library, suppressions)
+    }
+
+    #[test]
+    fn test_parse_suppressions() {
+        let src = "// circomspect-disable-next-line CS0005, CS0006 reason: This is fine.\n\
+                   x <-- y;\n\
+                   z <-- w; // circomspect-disable-line\n\
+                   // circomspect-disabled CS0005\n\
+                   // Some other comment.\n";
+        let (_, suppressions) = suppressions(src, &[]);
+        assert_eq!(suppressions.len(), 2);
+
+        assert_eq!(suppressions[0].kind(), &SuppressionKind::NextLine);
+        assert_eq!(suppressions[0].codes(), &["CS0005", "CS0006"]);
+        assert_eq!(suppressions[0].reason(), Some("This is fine."));
+        assert_eq!(&src[suppressions[0].covered_location().clone()], "x <-- y;\n");
+
+        assert_eq!(suppressions[1].kind(), &SuppressionKind::Line);
+        assert!(suppressions[1].codes().is_empty());
+        assert_eq!(suppressions[1].reason(), None);
+        assert!(src[suppressions[1].covered_location().clone()].starts_with("z <-- w;"));
+    }
+
+    #[test]
+    fn test_definition_suppressions() {
+        let src = "// circomspect-disable CS0010\n\
+                   template A() {}\n\
+                   template B() {\n\
+                   // circomspect-disable CS0005\n\
+                   }\n";
+        let a = src.find("template A").unwrap();
+        let b = src.find("template B").unwrap();
+        let definitions = [a..a + 15, b..src.len() - 1];
+        let (_, suppressions) = suppressions(src, &definitions);
+        assert_eq!(suppressions.len(), 2);
+        assert_eq!(suppressions[0].covered_location(), &definitions[0]);
+        assert_eq!(suppressions[1].covered_location(), &definitions[1]);
+    }
+
+    #[test]
+    fn test_suppresses() {
+        let src = "// circomspect-disable-next-line signal-assignment-statement\n\
+                   x <-- y;\n\
+                   z <-- w;\n";
+        let (_, suppressions) = suppressions(src, &[]);
+        let x = src.find("x <--").unwrap();
+        let z = src.find("z <--").unwrap();
+
+        let mut report = Report::warning("".to_string(), ReportCode::SignalAssignmentStatement);
+        report.add_primary(x..x + 7, 0, "".to_string());
+        assert!(suppressions[0].suppresses(&report));
+
+        let mut report = Report::warning("".to_string(), ReportCode::SignalAssignmentStatement);
+        report.add_primary(z..z + 7, 0, "".to_string());
+        assert!(!suppressions[0].suppresses(&report));
+
+        let mut report = Report::warning("".to_string(), ReportCode::FieldElementArithmetic);
+        report.add_primary(x..x + 7, 0, "".to_string());
+        assert!(!suppressions[0].suppresses(&report));
+    }
+}
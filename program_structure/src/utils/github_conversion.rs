@@ -0,0 +1,138 @@
+use codespan_reporting::files::Files;
+use std::env;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::report::{MessageCategory, Report};
+use crate::file_definition::{FileID, FileLibrary};
+
+/// Converts the given report to GitHub Actions workflow commands (one for each
+/// primary location of the report). Each command creates an annotation on the
+/// corresponding lines when the workflow runs on a pull request.
+pub fn to_annotations(report: &Report, files: &FileLibrary) -> GitHubResult<Vec<String>> {
+    let storage = files.to_storage();
+    let root = workspace_root();
+    report
+        .primary()
+        .iter()
+        .map(|label| {
+            let file =
+                storage.get(label.file_id).map_err(|_| GitHubError::UnknownFile(label.file_id))?;
+            let start = storage
+                .location(label.file_id, label.range.start)
+                .map_err(|_| GitHubError::UnknownLocation(label.file_id, label.range.clone()))?;
+            let end = storage
+                .location(label.file_id, label.range.end)
+                .map_err(|_| GitHubError::UnknownLocation(label.file_id, label.range.clone()))?;
+            let file_name = relative_path(file.name(), root.as_deref());
+            Ok(format!(
+                "::{} file={},line={},col={},endLine={},endColumn={},title={}::{}",
+                to_command(report.category()),
+                escape_property(&file_name),
+                start.line_number,
+                start.column_number,
+                end.line_number,
+                end.column_number,
+                escape_property(&report.id()),
+                escape_data(&message(report, &label.message))
+            ))
+        })
+        .collect()
+}
+
+/// Returns the annotation message. This is the report message followed by the
+/// label message and any notes.
+fn message(report: &Report, label_message: &str) -> String {
+    let mut message = report.message().clone();
+    if !label_message.is_empty() {
+        message.push('\n');
+        message.push_str(label_message);
+    }
+    for note in report.notes() {
+        message.push('\n');
+        message.push_str(note);
+    }
+    message
+}
+
+/// Returns the root of the repository. GitHub Actions expects file paths to
+/// be given relative to this directory.
+fn workspace_root() -> Option<PathBuf> {
+    env::var_os("GITHUB_WORKSPACE").map(PathBuf::from).or_else(|| env::current_dir().ok())
+}
+
+fn relative_path(file_name: &str, root: Option<&Path>) -> String {
+    match root.and_then(|root| Path::new(file_name).strip_prefix(root).ok()) {
+        Some(path) => path.display().to_string(),
+        None => file_name.to_string(),
+    }
+}
+
+fn to_command(category: &MessageCategory) -> &'static str {
+    use MessageCategory::*;
+    match category {
+        Error => "error",
+        Warning => "warning",
+        Info => "notice",
+    }
+}
+
+/// Escapes the message of a workflow command.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escapes a property value of a workflow command.
+fn escape_property(property: &str) -> String {
+    escape_data(property).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[derive(Error, Debug)]
+pub enum GitHubError {
+    #[error("unknown location `{}:{}` in file with ID `{0}`", .1.start, .1.end)]
+    UnknownLocation(FileID, Range<usize>),
+    #[error("unknown file ID `{0}`")]
+    UnknownFile(FileID),
+}
+
+type GitHubResult<T> = Result<T, GitHubError>;
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_to_annotations() {
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file(
+            "test.circom".to_string(),
+            "template T() {\n    out <-- in;\n}\n".to_string(),
+            true,
+        );
+        let mut report = Report::info(
+            "Using the signal assignment operator `<--` is 100% risky.".to_string(),
+            ReportCode::SignalAssignmentStatement,
+        );
+        report.add_primary(19..30, file_id, "The signal `out` is assigned here.".to_string());
+        let annotations = to_annotations(&report, &file_library).unwrap();
+        assert_eq!(
+            annotations,
+            vec![
+                "::notice file=test.circom,line=2,col=5,endLine=2,endColumn=16,title=CS0005::\
+                 Using the signal assignment operator `<--` is 100%25 risky.%0A\
+                 The signal `out` is assigned here."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let root = Path::new("/home/user/project");
+        assert_eq!(relative_path("/home/user/project/src/a.circom", Some(root)), "src/a.circom");
+        assert_eq!(relative_path("/tmp/b.circom", Some(root)), "/tmp/b.circom");
+        assert_eq!(escape_property("a,b:c"), "a%2Cb%3Ac");
+    }
+}
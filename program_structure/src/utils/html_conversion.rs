@@ -0,0 +1,394 @@
+use codespan_reporting::files::Files;
+use log::{trace, warn};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::ops::Range;
+use thiserror::Error;
+
+use crate::report::{Report, ReportLabel};
+use crate::file_definition::{FileID, FileLibrary};
+use crate::xml_conversion::escape;
+
+const TITLE: &str = "Circomspect report";
+const NO_DEFINITION: &str = "(outside of any template or function)";
+const KEYWORDS: [&str; 24] = [
+    "assert",
+    "bus",
+    "circom",
+    "component",
+    "custom",
+    "custom_templates",
+    "do",
+    "else",
+    "for",
+    "function",
+    "if",
+    "include",
+    "input",
+    "log",
+    "main",
+    "output",
+    "parallel",
+    "pragma",
+    "public",
+    "return",
+    "signal",
+    "template",
+    "var",
+    "while",
+];
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em auto; max-width: 70em; color: #24292f; }
+a { color: #0969da; }
+h2 { border-bottom: 1px solid #d0d7de; padding-bottom: 0.3em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d0d7de; padding: 0.3em 0.8em; text-align: left; }
+.finding { border: 1px solid #d0d7de; border-radius: 6px; margin: 1em 0; padding: 0 1em 1em 1em; }
+.badge { border-radius: 4px; color: white; font-size: 0.8em; font-weight: bold; padding: 0.1em 0.5em; text-transform: uppercase; }
+.badge.error { background: #cf222e; }
+.badge.warning { background: #bf8700; }
+.badge.info { background: #0969da; }
+.location { color: #57606a; font-family: monospace; margin: 1em 0 0.2em 0; }
+pre { background: #f6f8fa; border-radius: 6px; margin: 0; overflow-x: auto; padding: 0.5em; }
+.line-number { color: #8c959f; display: inline-block; margin-right: 1em; text-align: right; user-select: none; width: 3em; }
+.keyword { color: #cf222e; }
+.comment { color: #6e7781; font-style: italic; }
+.string { color: #0a3069; }
+.number { color: #0550ae; }
+.primary { background: #ffd8b5; }
+.secondary { background: #ddf4ff; }
+.label-message { font-family: monospace; margin: 0.2em 0 0 4em; }
+.label-message.primary { background: none; color: #bc4c00; }
+.label-message.secondary { background: none; color: #0969da; }
+"#;
+
+/// The syntax highlighting class of a character in the source code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TokenClass {
+    Plain,
+    Keyword,
+    Comment,
+    String,
+    Number,
+}
+
+impl TokenClass {
+    fn css_class(&self) -> Option<&'static str> {
+        use TokenClass::*;
+        match self {
+            Plain => None,
+            Keyword => Some("keyword"),
+            Comment => Some("comment"),
+            String => Some("string"),
+            Number => Some("number"),
+        }
+    }
+}
+
+/// Converts the given reports to a self-contained HTML document. Findings are
+/// grouped by template or function, and indexed by report code. Reports with
+/// locations which cannot be resolved are skipped. Returns the document
+/// together with the number of findings included in the document.
+pub fn to_html(reports: &[Report], files: &FileLibrary) -> (String, usize) {
+    trace!("converting report collection to HTML");
+    let mut highlighter = Highlighter::new();
+
+    // Render each finding separately to ensure that a single report does not
+    // prevent the remaining reports from being included.
+    let mut findings = Vec::new();
+    for report in reports {
+        let index = findings.len();
+        let mut finding = String::new();
+        match write_finding(&mut finding, index, report, files, &mut highlighter) {
+            Ok(()) => findings.push((report, finding)),
+            Err(err) => warn!("failed to convert report `{}` to HTML: {err}", report.id()),
+        }
+    }
+
+    // Group findings by definition and by report code.
+    let mut by_definition: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let mut by_code: BTreeMap<String, Vec<(usize, &Report)>> = BTreeMap::new();
+    for (index, (report, finding)) in findings.iter().enumerate() {
+        let definition = report.definition_name().unwrap_or(NO_DEFINITION);
+        by_definition.entry(definition).or_default().push(finding);
+        by_code.entry(report.id()).or_default().push((index, report));
+    }
+
+    let mut html = String::new();
+    let _ = writeln!(html, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>");
+    let _ = writeln!(html, "<meta charset=\"utf-8\">\n<title>{TITLE}</title>");
+    let _ = writeln!(html, "<style>{STYLE}</style>\n</head>\n<body>");
+    let _ = writeln!(html, "<h1>{TITLE}</h1>");
+    let _ = writeln!(
+        html,
+        "<p>{} {} in {} {}.</p>",
+        findings.len(),
+        if findings.len() == 1 { "finding" } else { "findings" },
+        by_definition.len(),
+        if by_definition.len() == 1 { "definition" } else { "definitions" },
+    );
+
+    // Summary of findings by report code.
+    let _ = writeln!(html, "<h2>Findings by code</h2>");
+    if !by_code.is_empty() {
+        let _ = writeln!(html, "<table>\n<tr><th>ID</th><th>Name</th><th>Findings</th></tr>");
+        for (id, findings) in &by_code {
+            let (_, report) = findings[0];
+            let links = findings
+                .iter()
+                .map(|(index, _)| format!("<a href=\"#finding-{index}\">#{index}</a>"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{links}</td></tr>",
+                escape(id),
+                documentation_link(report)
+            );
+        }
+        let _ = writeln!(html, "</table>");
+    }
+
+    // All findings grouped by definition.
+    let _ = writeln!(html, "<h2>Findings by template or function</h2>");
+    for (definition, findings) in &by_definition {
+        let _ = writeln!(html, "<h3>{}</h3>", escape(definition));
+        for finding in findings {
+            html.push_str(finding);
+        }
+    }
+    let _ = writeln!(html, "</body>\n</html>");
+    (html, findings.len())
+}
+
+fn write_finding(
+    html: &mut String,
+    index: usize,
+    report: &Report,
+    files: &FileLibrary,
+    highlighter: &mut Highlighter,
+) -> HtmlResult<()> {
+    let category = report.category().to_string();
+    let _ = writeln!(html, "<div class=\"finding\" id=\"finding-{index}\">");
+    let _ = writeln!(
+        html,
+        "<h4><span class=\"badge {category}\">{category}</span> #{index} {} [{}]</h4>",
+        escape(report.message()),
+        documentation_link(report)
+    );
+    for label in report.primary() {
+        write_label(html, label, "primary", files, highlighter)?;
+    }
+    for label in report.secondary() {
+        write_label(html, label, "secondary", files, highlighter)?;
+    }
+    if !report.notes().is_empty() {
+        let _ = writeln!(html, "<ul>");
+        for note in report.notes() {
+            let _ = writeln!(html, "<li>{}</li>", escape(note));
+        }
+        let _ = writeln!(html, "</ul>");
+    }
+    let _ = writeln!(html, "</div>");
+    Ok(())
+}
+
+/// Writes the source lines covered by the label, with the label range marked.
+fn write_label(
+    html: &mut String,
+    label: &ReportLabel,
+    style: &str,
+    files: &FileLibrary,
+    highlighter: &mut Highlighter,
+) -> HtmlResult<()> {
+    let storage = files.to_storage();
+    let unknown_location = || HtmlError::UnknownLocation(label.file_id, label.range.clone());
+    let file = storage.get(label.file_id).map_err(|_| HtmlError::UnknownFile(label.file_id))?;
+    // Locations past the end of the file are not rejected by the file storage.
+    if label.range.start > label.range.end || label.range.end > file.source().len() {
+        return Err(unknown_location());
+    }
+    let start =
+        storage.location(label.file_id, label.range.start).map_err(|_| unknown_location())?;
+    let first_line =
+        storage.line_index(label.file_id, label.range.start).map_err(|_| unknown_location())?;
+    let last_line =
+        storage.line_index(label.file_id, label.range.end).map_err(|_| unknown_location())?;
+
+    let _ = writeln!(
+        html,
+        "<div class=\"location\">{}:{}:{}</div>",
+        escape(file.name()),
+        start.line_number,
+        start.column_number
+    );
+    let source = file.source();
+    let classes = highlighter.classes(label.file_id, source);
+    let _ = write!(html, "<pre>");
+    for line in first_line..=last_line {
+        let range = storage.line_range(label.file_id, line).map_err(|_| unknown_location())?;
+        let line_end = source[range.clone()].trim_end_matches(['\n', '\r']).len() + range.start;
+        let _ = write!(html, "<span class=\"line-number\">{}</span>", line + 1);
+        write_highlighted(html, source, classes, range.start..line_end, &label.range, style);
+        html.push('\n');
+    }
+    let _ = writeln!(html, "</pre>");
+    if !label.message.is_empty() {
+        let _ =
+            writeln!(html, "<div class=\"label-message {style}\">{}</div>", escape(&label.message));
+    }
+    Ok(())
+}
+
+/// Writes the given source range as HTML. Characters are grouped into spans
+/// with the same syntax highlighting class, and characters in the marked
+/// range are wrapped in a `<mark>` element with the given style.
+fn write_highlighted(
+    html: &mut String,
+    source: &str,
+    classes: &[TokenClass],
+    range: Range<usize>,
+    marked: &Range<usize>,
+    style: &str,
+) {
+    let mut start = range.start;
+    while start < range.end {
+        let is_marked = |offset: usize| marked.contains(&offset);
+        let class = classes[start];
+        let mut end = start + 1;
+        while end < range.end && classes[end] == class && is_marked(end) == is_marked(start) {
+            end += 1;
+        }
+        // Ensure that we split the source on character boundaries.
+        while !source.is_char_boundary(end) {
+            end += 1;
+        }
+        let text = escape(&source[start..end]);
+        let text = match class.css_class() {
+            Some(css_class) => format!("<span class=\"{css_class}\">{text}</span>"),
+            None => text,
+        };
+        if is_marked(start) {
+            let _ = write!(html, "<mark class=\"{style}\">{text}</mark>");
+        } else {
+            html.push_str(&text);
+        }
+        start = end;
+    }
+}
+
+fn documentation_link(report: &Report) -> String {
+    match report.code().url() {
+        Some(url) => format!("<a href=\"{}\">{}</a>", escape(&url), escape(&report.name())),
+        None => escape(&report.name()),
+    }
+}
+
+/// Caches the syntax highlighting classes of each file.
+struct Highlighter {
+    classes: HashMap<FileID, Vec<TokenClass>>,
+}
+
+impl Highlighter {
+    fn new() -> Highlighter {
+        Highlighter { classes: HashMap::new() }
+    }
+
+    fn classes(&mut self, file_id: FileID, source: &str) -> &[TokenClass] {
+        self.classes.entry(file_id).or_insert_with(|| classify(source))
+    }
+}
+
+/// Returns the syntax highlighting class of each byte in the given source.
+fn classify(source: &str) -> Vec<TokenClass> {
+    let bytes = source.as_bytes();
+    let mut classes = vec![TokenClass::Plain; bytes.len()];
+    let mut index = 0;
+    while index < bytes.len() {
+        let rest = &source[index..];
+        let (class, length) = if rest.starts_with("//") {
+            (TokenClass::Comment, rest.find('\n').unwrap_or(rest.len()))
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            (TokenClass::Comment, comment.find("*/").map(|end| end + 4).unwrap_or(rest.len()))
+        } else if let Some(string) = rest.strip_prefix('"') {
+            (TokenClass::String, string.find('"').map(|end| end + 2).unwrap_or(rest.len()))
+        } else if bytes[index].is_ascii_alphanumeric() || bytes[index] == b'_' {
+            let length = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            let word = &rest[..length];
+            if bytes[index].is_ascii_digit() {
+                (TokenClass::Number, length)
+            } else if KEYWORDS.contains(&word) {
+                (TokenClass::Keyword, length)
+            } else {
+                (TokenClass::Plain, length)
+            }
+        } else {
+            (TokenClass::Plain, rest.chars().next().map(char::len_utf8).unwrap_or(1))
+        };
+        classes[index..index + length].fill(class);
+        index += length;
+    }
+    classes
+}
+
+#[derive(Error, Debug)]
+pub enum HtmlError {
+    #[error("unknown location `{}:{}` in file with ID `{0}`", .1.start, .1.end)]
+    UnknownLocation(FileID, Range<usize>),
+    #[error("unknown file ID `{0}`")]
+    UnknownFile(FileID),
+}
+
+type HtmlResult<T> = Result<T, HtmlError>;
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let source = "signal x; // <-- 1\nvar y = 12;";
+        let classes = classify(source);
+        let class_of = |pattern: &str| classes[source.find(pattern).unwrap()];
+        assert_eq!(class_of("signal"), TokenClass::Keyword);
+        assert_eq!(class_of("x;"), TokenClass::Plain);
+        assert_eq!(class_of("// <--"), TokenClass::Comment);
+        assert_eq!(class_of("1\n"), TokenClass::Comment);
+        assert_eq!(class_of("var"), TokenClass::Keyword);
+        assert_eq!(class_of("12"), TokenClass::Number);
+    }
+
+    #[test]
+    fn test_to_html() {
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file(
+            "test.circom".to_string(),
+            "template T() {\n    out <-- in;\n}\n".to_string(),
+            true,
+        );
+        let mut report = Report::warning(
+            "Using the signal assignment operator `<--` does not constrain the assigned signal."
+                .to_string(),
+            ReportCode::SignalAssignmentStatement,
+        );
+        report.add_primary(19..30, file_id, "The signal `out` is assigned here.".to_string());
+        report.add_secondary(0..12, file_id, Some("The template is defined here.".to_string()));
+        report.add_note("Consider using `<==` instead.".to_string());
+        report.set_definition_name("T");
+
+        let (html, included) = to_html(&[report], &file_library);
+        assert_eq!(included, 1);
+        assert!(html.contains("<h3>T</h3>"));
+        assert!(html.contains("<div class=\"location\">test.circom:2:5</div>"));
+        assert!(html.contains("<mark class=\"primary\">out &lt;-- in;</mark>"));
+        assert!(html
+            .contains("<mark class=\"secondary\"><span class=\"keyword\">template</span></mark>"));
+        assert!(html.contains("<li>Consider using `&lt;==` instead.</li>"));
+        assert!(html.contains("analysis_passes.md#signal-assignment"));
+        assert!(!html.contains("http://") && !html.contains("<script"));
+    }
+}
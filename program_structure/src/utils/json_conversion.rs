@@ -0,0 +1,152 @@
+use codespan_reporting::files::Files;
+use log::trace;
+use serde_json::{json, Value};
+use std::ops::Range;
+use thiserror::Error;
+
+use crate::report::{Report, ReportCollection, ReportLabel, Suggestion};
+use crate::file_definition::{FileID, FileLibrary};
+
+/// A trait for objects that can be converted into JSON.
+pub trait ToJson {
+    /// Converts the object to the corresponding JSON value.
+    fn to_json(&self, files: &FileLibrary) -> JsonResult<Value>;
+}
+
+impl ToJson for ReportCollection {
+    fn to_json(&self, files: &FileLibrary) -> JsonResult<Value> {
+        trace!("converting report collection to JSON");
+        let reports =
+            self.iter().map(|report| report.to_json(files)).collect::<JsonResult<Vec<_>>>()?;
+        Ok(Value::Array(reports))
+    }
+}
+
+impl ToJson for Report {
+    fn to_json(&self, files: &FileLibrary) -> JsonResult<Value> {
+        let primary = self
+            .primary()
+            .iter()
+            .map(|label| label.to_json(files))
+            .collect::<JsonResult<Vec<_>>>()?;
+        let secondary = self
+            .secondary()
+            .iter()
+            .map(|label| label.to_json(files))
+            .collect::<JsonResult<Vec<_>>>()?;
+        let suggestions = self
+            .suggestions()
+            .iter()
+            .map(|suggestion| suggestion.to_json(files))
+            .collect::<JsonResult<Vec<_>>>()?;
+        Ok(json!({
+            "id": self.id(),
+            "name": self.name(),
+            "category": self.category().to_string(),
+            "message": self.message(),
+            "definition": self.definition_name(),
+            "primary": primary,
+            "secondary": secondary,
+            "notes": self.notes(),
+            "suggestions": suggestions,
+            "url": self.code().url(),
+        }))
+    }
+}
+
+impl ToJson for ReportLabel {
+    fn to_json(&self, files: &FileLibrary) -> JsonResult<Value> {
+        let mut json = location_to_json(self.file_id, &self.range, files)?;
+        json["message"] = json!(self.message);
+        Ok(json)
+    }
+}
+
+impl ToJson for Suggestion {
+    fn to_json(&self, files: &FileLibrary) -> JsonResult<Value> {
+        let mut json = location_to_json(self.file_id(), self.location(), files)?;
+        json["replacement"] = json!(self.replacement());
+        json["message"] = json!(self.message());
+        Ok(json)
+    }
+}
+
+fn location_to_json(
+    file_id: FileID,
+    range: &Range<usize>,
+    files: &FileLibrary,
+) -> JsonResult<Value> {
+    let storage = files.to_storage();
+    let file = storage.get(file_id).map_err(|_| JsonError::UnknownFile(file_id))?;
+    let start = storage
+        .location(file_id, range.start)
+        .map_err(|_| JsonError::UnknownLocation(file_id, range.clone()))?;
+    let end = storage
+        .location(file_id, range.end)
+        .map_err(|_| JsonError::UnknownLocation(file_id, range.clone()))?;
+    Ok(json!({
+        "file": file.name(),
+        "start": { "line": start.line_number, "column": start.column_number },
+        "end": { "line": end.line_number, "column": end.column_number },
+    }))
+}
+
+#[derive(Error, Debug)]
+pub enum JsonError {
+    #[error("unknown location `{}:{}` in file with ID `{0}`", .1.start, .1.end)]
+    UnknownLocation(FileID, Range<usize>),
+    #[error("unknown file ID `{0}`")]
+    UnknownFile(FileID),
+}
+
+type JsonResult<T> = Result<T, JsonError>;
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_report_to_json() {
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file(
+            "test.circom".to_string(),
+            "template T() {\n    out <-- in;\n}\n".to_string(),
+            true,
+        );
+        let mut report = Report::warning(
+            "Using the signal assignment operator `<--` does not constrain the assigned signal."
+                .to_string(),
+            ReportCode::SignalAssignmentStatement,
+        );
+        report.add_primary(
+            19..30,
+            file_id,
+            "The assigned signal `out` is not constrained here.".to_string(),
+        );
+        report.add_secondary(0..12, file_id, None);
+        report.add_note("A note.".to_string());
+        report.add_suggestion(23..26, file_id, "<==".to_string(), "A suggestion.".to_string());
+        report.set_definition_name("T");
+
+        let json = report.to_json(&file_library).unwrap();
+        assert_eq!(json["id"], "CS0005");
+        assert_eq!(json["name"], "signal-assignment-statement");
+        assert_eq!(json["category"], "warning");
+        assert_eq!(json["definition"], "T");
+        assert_eq!(json["primary"][0]["file"], "test.circom");
+        assert_eq!(json["primary"][0]["start"], json!({ "line": 2, "column": 5 }));
+        assert_eq!(json["primary"][0]["end"], json!({ "line": 2, "column": 16 }));
+        assert_eq!(json["secondary"][0]["message"], "");
+        assert_eq!(json["notes"], json!(["A note."]));
+        assert_eq!(json["suggestions"][0]["start"], json!({ "line": 2, "column": 9 }));
+        assert_eq!(json["suggestions"][0]["replacement"], "<==");
+        assert_eq!(json["suggestions"][0]["message"], "A suggestion.");
+        assert!(json["url"].as_str().unwrap().ends_with("#signal-assignment"));
+
+        let mut report = Report::error("Unknown file.".to_string(), ReportCode::ParseFail);
+        report.add_primary(0..1, file_id + 1, "Invalid file ID.".to_string());
+        assert!(report.to_json(&file_library).is_err());
+    }
+}
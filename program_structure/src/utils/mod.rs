@@ -2,4 +2,9 @@ pub mod constants;
 pub mod environment;
 pub mod nonempty_vec;
 pub mod writers;
+pub mod github_conversion;
+pub mod html_conversion;
+pub mod json_conversion;
 pub mod sarif_conversion;
+pub mod short_conversion;
+pub mod xml_conversion;
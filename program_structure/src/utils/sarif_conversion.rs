@@ -1,13 +1,14 @@
 use codespan_reporting::files::Files;
 use log::{debug, trace};
 use serde_sarif::sarif;
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::fmt;
 use std::ops::Range;
 use std::path::PathBuf;
 use thiserror::Error;
 
-use crate::report::{Report, ReportCollection, ReportLabel};
+use crate::report::{Report, ReportCollection, ReportLabel, Suggestion};
+use crate::report_code::ReportCode;
 use crate::file_definition::{FileID, FileLibrary};
 
 // This is the Sarif file format version, not the tool version.
@@ -35,14 +36,11 @@ impl ToSarif for ReportCollection {
         trace!("building reporting descriptors");
         let rules = self
             .iter()
-            .map(|report| (report.name(), report.id()))
-            .collect::<HashSet<_>>()
-            .iter()
-            .map(|(name, id)| {
-                sarif::ReportingDescriptorBuilder::default().name(name).id(id).build()
-            })
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(SarifError::from)?;
+            .map(|report| ((report.name(), report.id()), report.code()))
+            .collect::<HashMap<_, _>>()
+            .values()
+            .map(|code| reporting_descriptor(code))
+            .collect::<SarifResult<Vec<_>>>()?;
         // Build tool.
         //
         // TODO: Should include primary package version.
@@ -93,20 +91,52 @@ impl ToSarif for Report {
             .id(&rule_id)
             .build()
             .map_err(SarifError::from)?;
+        // Build fixes.
+        trace!("building fixes");
+        let fixes = self
+            .suggestions()
+            .iter()
+            .map(|suggestion| suggestion.to_sarif(files))
+            .collect::<SarifResult<Vec<_>>>()?;
         // Build result.
         trace!("building result");
-        sarif::ResultBuilder::default()
+        let mut builder = sarif::ResultBuilder::default();
+        builder
             .level(level)
             .message(message)
             .rule_id(rule_id)
             .rule(rule)
             .locations(locations)
-            .related_locations(related_locations)
-            .build()
-            .map_err(SarifError::from)
+            .related_locations(related_locations);
+        if !fixes.is_empty() {
+            builder.fixes(fixes);
+        }
+        builder.build().map_err(SarifError::from)
     }
 }
 
+/// Converts the report code to a Sarif reporting descriptor (rule). The rule
+/// description and help text are given by the explanation of the code (if
+/// available).
+fn reporting_descriptor(code: &ReportCode) -> SarifResult<sarif::ReportingDescriptor> {
+    let mut builder = sarif::ReportingDescriptorBuilder::default();
+    builder.name(code.name()).id(code.id());
+    if let Some(explanation) = code.explanation() {
+        let full_description = sarif::MultiformatMessageStringBuilder::default()
+            .text(explanation.description())
+            .build()?;
+        let help = sarif::MultiformatMessageStringBuilder::default()
+            .text(explanation.to_text())
+            .markdown(explanation.to_markdown())
+            .build()?;
+        builder.full_description(full_description).help(help);
+    }
+    if let Some(url) = code.url() {
+        builder.help_uri(url);
+    }
+    builder.build().map_err(SarifError::from)
+}
+
 impl ToSarif for ReportLabel {
     type Sarif = sarif::Location;
     type Error = SarifError;
@@ -118,21 +148,7 @@ impl ToSarif for ReportLabel {
         let artifact_location = sarif::ArtifactLocationBuilder::default().uri(file_uri).build()?;
         // Build region.
         trace!("building region");
-        assert!(self.range.start <= self.range.end);
-        let start = files
-            .to_storage()
-            .location(self.file_id, self.range.start)
-            .map_err(|_| SarifError::UnknownLocation(self.file_id, self.range.clone()))?;
-        let end = files
-            .to_storage()
-            .location(self.file_id, self.range.end)
-            .map_err(|_| SarifError::UnknownLocation(self.file_id, self.range.clone()))?;
-        let region = sarif::RegionBuilder::default()
-            .start_line(start.line_number as i64)
-            .start_column(start.column_number as i64)
-            .end_line(end.line_number as i64)
-            .end_column(end.column_number as i64)
-            .build()?;
+        let region = region(self.file_id, &self.range, files)?;
         // Build physical location.
         trace!("building physical location");
         let physical_location = sarif::PhysicalLocationBuilder::default()
@@ -153,6 +169,62 @@ impl ToSarif for ReportLabel {
     }
 }
 
+impl ToSarif for Suggestion {
+    type Sarif = sarif::Fix;
+    type Error = SarifError;
+
+    fn to_sarif(&self, files: &FileLibrary) -> SarifResult<sarif::Fix> {
+        // Build replacement.
+        trace!("building replacement");
+        let inserted_content =
+            sarif::ArtifactContentBuilder::default().text(self.replacement()).build()?;
+        let replacement = sarif::ReplacementBuilder::default()
+            .deleted_region(region(self.file_id(), self.location(), files)?)
+            .inserted_content(inserted_content)
+            .build()?;
+        // Build artifact change.
+        trace!("building artifact change");
+        let file_uri = self.file_id().to_uri(files)?;
+        let artifact_location = sarif::ArtifactLocationBuilder::default().uri(file_uri).build()?;
+        let artifact_change = sarif::ArtifactChangeBuilder::default()
+            .artifact_location(artifact_location)
+            .replacements(vec![replacement])
+            .build()?;
+        // Build fix.
+        trace!("building fix");
+        let description = sarif::MessageBuilder::default().text(self.message()).build()?;
+        sarif::FixBuilder::default()
+            .description(description)
+            .artifact_changes(vec![artifact_change])
+            .build()
+            .map_err(SarifError::from)
+    }
+}
+
+/// Converts the given file location to a Sarif region.
+fn region(
+    file_id: FileID,
+    range: &Range<usize>,
+    files: &FileLibrary,
+) -> SarifResult<sarif::Region> {
+    assert!(range.start <= range.end);
+    let start = files
+        .to_storage()
+        .location(file_id, range.start)
+        .map_err(|_| SarifError::UnknownLocation(file_id, range.clone()))?;
+    let end = files
+        .to_storage()
+        .location(file_id, range.end)
+        .map_err(|_| SarifError::UnknownLocation(file_id, range.clone()))?;
+    sarif::RegionBuilder::default()
+        .start_line(start.line_number as i64)
+        .start_column(start.column_number as i64)
+        .end_line(end.line_number as i64)
+        .end_column(end.column_number as i64)
+        .build()
+        .map_err(SarifError::from)
+}
+
 trait ToUri {
     type Error;
     fn to_uri(&self, files: &FileLibrary) -> Result<String, Self::Error>;
@@ -183,12 +255,16 @@ pub enum SarifError {
     InvalidToolComponent(#[from] sarif::ToolComponentBuilderError),
     InvalidLocation(#[from] sarif::LocationBuilderError),
     InvalidMessage(#[from] sarif::MessageBuilderError),
+    InvalidMultiformatMessage(#[from] sarif::MultiformatMessageStringBuilderError),
     InvalidRegion(#[from] sarif::RegionBuilderError),
     InvalidResult(#[from] sarif::ResultBuilderError),
     InvalidRun(#[from] sarif::RunBuilderError),
     InvalidSarif(#[from] sarif::SarifBuilderError),
     InvalidTool(#[from] sarif::ToolBuilderError),
     InvalidFix(#[from] sarif::FixBuilderError),
+    InvalidArtifactChange(#[from] sarif::ArtifactChangeBuilderError),
+    InvalidArtifactContent(#[from] sarif::ArtifactContentBuilderError),
+    InvalidReplacement(#[from] sarif::ReplacementBuilderError),
     UnknownLocation(FileID, Range<usize>),
     UnknownFile(FileID),
 }
@@ -0,0 +1,147 @@
+use codespan_reporting::files::Files;
+use std::ops::Range;
+use thiserror::Error;
+
+use crate::report::{Report, ReportLabel};
+use crate::file_definition::{FileID, FileLibrary};
+
+/// Converts the given report to compact one-line messages on the form
+/// `path:line:column: severity[ID]: message`, one for each primary location
+/// of the report. If `include_secondary` is true, each message is followed by
+/// indented `note:` lines for the secondary locations of the report.
+pub fn to_short_format(
+    report: &Report,
+    files: &FileLibrary,
+    include_secondary: bool,
+) -> ShortResult<Vec<String>> {
+    let mut lines = Vec::new();
+    for label in report.primary() {
+        lines.push(format!(
+            "{}: {}[{}]: {}",
+            location(label, files)?,
+            report.category(),
+            report.id(),
+            single_line(report.message())
+        ));
+        if !include_secondary {
+            continue;
+        }
+        for label in report.secondary() {
+            let mut line = format!("    note: {}", location(label, files)?);
+            if !label.message.is_empty() {
+                line.push_str(": ");
+                line.push_str(&single_line(&label.message));
+            }
+            lines.push(line);
+        }
+    }
+    Ok(lines)
+}
+
+/// Returns the start location of the label on the form `path:line:column`.
+fn location(label: &ReportLabel, files: &FileLibrary) -> ShortResult<String> {
+    let storage = files.to_storage();
+    let file = storage.get(label.file_id).map_err(|_| ShortError::UnknownFile(label.file_id))?;
+    let start = storage
+        .location(label.file_id, label.range.start)
+        .map_err(|_| ShortError::UnknownLocation(label.file_id, label.range.clone()))?;
+    Ok(format!("{}:{}:{}", file.name(), start.line_number, start.column_number))
+}
+
+/// Replaces newlines to ensure that each message occupies a single line.
+fn single_line(message: &str) -> String {
+    message.lines().map(str::trim).collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Error, Debug)]
+pub enum ShortError {
+    #[error("unknown location `{}:{}` in file with ID `{0}`", .1.start, .1.end)]
+    UnknownLocation(FileID, Range<usize>),
+    #[error("unknown file ID `{0}`")]
+    UnknownFile(FileID),
+}
+
+type ShortResult<T> = Result<T, ShortError>;
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_to_short_format() {
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file(
+            "test.circom".to_string(),
+            "template T() {\n    out <-- in;\n}\n".to_string(),
+            true,
+        );
+        let mut report = Report::warning(
+            "Using the signal assignment operator `<--` does not constrain the assigned signal."
+                .to_string(),
+            ReportCode::SignalAssignmentStatement,
+        );
+        report.add_primary(19..30, file_id, "The signal `out` is assigned here.".to_string());
+        report.add_secondary(0..12, file_id, Some("The template is defined here.".to_string()));
+
+        let message = "test.circom:2:5: warning[CS0005]: Using the signal assignment operator \
+                       `<--` does not constrain the assigned signal.";
+        assert_eq!(to_short_format(&report, &file_library, false).unwrap(), vec![message]);
+        assert_eq!(
+            to_short_format(&report, &file_library, true).unwrap(),
+            vec![message, "    note: test.circom:1:1: The template is defined here."]
+        );
+    }
+
+    #[test]
+    fn test_multiple_labels_and_lines() {
+        let mut file_library = FileLibrary::new();
+        let source = "template T() {\n    a <-- in;\n    b <-- in;\n}\n";
+        let file_id = file_library.add_file("test.circom".to_string(), source.to_string(), true);
+        let mut report = Report::warning(
+            "A message spanning\n    multiple lines.\n".to_string(),
+            ReportCode::SignalAssignmentStatement,
+        );
+        let a = source.find("a <--").unwrap();
+        let b = source.find("b <--").unwrap();
+        report.add_primary(a..a + 9, file_id, "The first signal.".to_string());
+        report.add_primary(b..b + 9, file_id, "The second signal.".to_string());
+        report.add_secondary(0..12, file_id, Some("A label spanning\ntwo lines.".to_string()));
+        report.add_secondary(source.len() - 2..source.len() - 1, file_id, None);
+
+        // Each primary location gives a single line, with newlines in the
+        // message replaced by spaces.
+        let first = "test.circom:2:5: warning[CS0005]: A message spanning multiple lines.";
+        let second = "test.circom:3:5: warning[CS0005]: A message spanning multiple lines.";
+        assert_eq!(to_short_format(&report, &file_library, false).unwrap(), vec![first, second]);
+
+        // Secondary locations are repeated for each primary location. Labels
+        // without a message only include the location.
+        let notes =
+            ["    note: test.circom:1:1: A label spanning two lines.", "    note: test.circom:4:1"];
+        assert_eq!(
+            to_short_format(&report, &file_library, true).unwrap(),
+            vec![first, notes[0], notes[1], second, notes[0], notes[1]]
+        );
+    }
+
+    #[test]
+    fn test_unknown_location() {
+        let mut file_library = FileLibrary::new();
+        let file_id =
+            file_library.add_file("test.circom".to_string(), "a <-- b;\n".to_string(), true);
+        let mut report =
+            Report::warning("A warning.".to_string(), ReportCode::SignalAssignmentStatement);
+        report.add_primary(0..1, file_id + 1, "Unknown file.".to_string());
+        assert!(matches!(
+            to_short_format(&report, &file_library, false),
+            Err(ShortError::UnknownFile(_))
+        ));
+
+        // Reports without primary locations are not written.
+        let report =
+            Report::warning("A warning.".to_string(), ReportCode::SignalAssignmentStatement);
+        assert!(to_short_format(&report, &file_library, true).unwrap().is_empty());
+    }
+}
@@ -1,30 +1,203 @@
 use anyhow;
 use anyhow::Context;
 use log::{info, warn};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::fs::File;
 use std::io::Write;
+use std::ops::Range;
 use std::path::{PathBuf, Path};
+use std::rc::Rc;
 use codespan_reporting::term;
 use termcolor::{StandardStream, ColorChoice, WriteColor, ColorSpec, Color};
 
+use crate::github_conversion;
+use crate::html_conversion;
+use crate::json_conversion::ToJson;
 use crate::sarif_conversion::ToSarif;
+use crate::short_conversion;
+use crate::xml_conversion::{self, TestSuite};
 use crate::{
     program_library::report::{Report, ReportCollection},
-    file_definition::FileLibrary,
+    program_library::suppression::Suppression,
+    program_library::baseline::{Baseline, BaselineEntry, Fingerprint},
+    program_library::diff::Diff,
+    file_definition::{FileID, FileLibrary, FileLocation},
 };
 
 pub trait ReportFilter {
-    /// Returns true if the report should be included.
-    fn filter(&self, report: &Report) -> bool;
+    /// Returns true if the report should be included. The file library is
+    /// used by filters that need to resolve report locations.
+    fn filter(&self, report: &Report, file_library: &FileLibrary) -> bool;
 }
 
 impl<F: Fn(&Report) -> bool> ReportFilter for F {
-    fn filter(&self, report: &Report) -> bool {
+    fn filter(&self, report: &Report, _: &FileLibrary) -> bool {
         self(report)
     }
 }
 
+/// A report filter which drops reports suppressed by inline suppression
+/// comments. Clones of the filter share the set of used suppressions, so the
+/// same filter can be added to multiple writers.
+#[derive(Clone)]
+pub struct SuppressionFilter {
+    suppressions: Rc<Vec<Suppression>>,
+    used: Rc<RefCell<HashSet<usize>>>,
+}
+
+impl SuppressionFilter {
+    pub fn new(file_library: &FileLibrary) -> SuppressionFilter {
+        SuppressionFilter {
+            suppressions: Rc::new(file_library.suppressions().to_vec()),
+            used: Rc::new(RefCell::new(HashSet::new())),
+        }
+    }
+
+    /// Returns reports for all suppressions in user provided input files which
+    /// have not suppressed any reports.
+    pub fn unused_suppressions(&self, file_library: &FileLibrary) -> ReportCollection {
+        let used = self.used.borrow();
+        self.suppressions
+            .iter()
+            .enumerate()
+            .filter(|(index, suppression)| {
+                !used.contains(index) && file_library.is_user_input(suppression.file_id())
+            })
+            .map(|(_, suppression)| suppression.unused_report())
+            .collect()
+    }
+}
+
+impl ReportFilter for SuppressionFilter {
+    fn filter(&self, report: &Report, _: &FileLibrary) -> bool {
+        let mut suppressed = false;
+        for (index, suppression) in self.suppressions.iter().enumerate() {
+            if suppression.suppresses(report) {
+                self.used.borrow_mut().insert(index);
+                suppressed = true;
+            }
+        }
+        !suppressed
+    }
+}
+
+/// A report filter which drops reports contained in a baseline. Each baseline
+/// entry suppresses at most `count` reports with the corresponding
+/// fingerprint. Clones of the filter share the remaining counts.
+#[derive(Clone)]
+pub struct BaselineFilter {
+    entries: Rc<Vec<BaselineEntry>>,
+    remaining: Rc<RefCell<HashMap<Fingerprint, usize>>>,
+}
+
+impl BaselineFilter {
+    pub fn new(baseline: &Baseline) -> BaselineFilter {
+        let remaining = baseline
+            .entries()
+            .iter()
+            .map(|entry| (entry.fingerprint.clone(), entry.count))
+            .collect();
+        BaselineFilter {
+            entries: Rc::new(baseline.entries().to_vec()),
+            remaining: Rc::new(RefCell::new(remaining)),
+        }
+    }
+
+    /// Returns the baseline entries which did not match any reports.
+    pub fn disappeared_entries(&self) -> Vec<BaselineEntry> {
+        let remaining = self.remaining.borrow();
+        self.entries
+            .iter()
+            .filter_map(|entry| match remaining.get(&entry.fingerprint) {
+                Some(&count) if count > 0 => Some(BaselineEntry { count, ..entry.clone() }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl ReportFilter for BaselineFilter {
+    fn filter(&self, report: &Report, file_library: &FileLibrary) -> bool {
+        let fingerprint = Fingerprint::new(report, file_library);
+        match self.remaining.borrow_mut().get_mut(&fingerprint) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        }
+    }
+}
+
+/// A report filter which only keeps reports with a primary label overlapping
+/// lines added or modified by a diff. If changed definitions are given, all
+/// reports from these templates and functions are kept as well.
+#[derive(Clone)]
+pub struct DiffFilter {
+    diff: Rc<Diff>,
+    definitions: Rc<HashSet<String>>,
+    changed_lines: Rc<RefCell<HashMap<FileID, Vec<Range<usize>>>>>,
+}
+
+impl DiffFilter {
+    pub fn new(diff: &Diff) -> DiffFilter {
+        DiffFilter {
+            diff: Rc::new(diff.clone()),
+            definitions: Rc::new(HashSet::new()),
+            changed_lines: Rc::new(RefCell::new(HashMap::new())),
+        }
+    }
+
+    /// Keep all reports from the given templates and functions.
+    pub fn with_definitions(mut self, definitions: impl IntoIterator<Item = String>) -> DiffFilter {
+        self.definitions = Rc::new(definitions.into_iter().collect());
+        self
+    }
+
+    /// Returns true if the given location overlaps lines added or modified by
+    /// the diff.
+    pub fn is_changed(
+        &self,
+        file_id: FileID,
+        location: &FileLocation,
+        file_library: &FileLibrary,
+    ) -> bool {
+        let first_line = file_library.get_line(location.start, file_id);
+        let last_line = file_library.get_line(location.end.max(location.start + 1) - 1, file_id);
+        let (Some(first_line), Some(last_line)) = (first_line, last_line) else {
+            return false;
+        };
+        let mut changed_lines = self.changed_lines.borrow_mut();
+        let ranges = changed_lines.entry(file_id).or_insert_with(|| {
+            let Ok(file) = file_library.to_storage().get(file_id) else {
+                return Vec::new();
+            };
+            // Paths in the diff are relative to the repository root, so we
+            // match against the canonical path of the file.
+            let path = PathBuf::from(file.name());
+            let path = path.canonicalize().unwrap_or(path);
+            self.diff.changed_lines(&path).to_vec()
+        });
+        ranges.iter().any(|range| range.start <= last_line && first_line < range.end)
+    }
+}
+
+impl ReportFilter for DiffFilter {
+    fn filter(&self, report: &Report, file_library: &FileLibrary) -> bool {
+        if let Some(name) = report.definition_name() {
+            if self.definitions.contains(name) {
+                return true;
+            }
+        }
+        report
+            .primary()
+            .iter()
+            .any(|label| self.is_changed(label.file_id, &label.range, file_library))
+    }
+}
+
 pub trait ReportWriter {
     /// Filter and write the given reports. Returns the number of reports written.
     fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize;
@@ -47,6 +220,32 @@ pub trait LogWriter {
     }
 }
 
+/// Writes log messages on the form `circomspect: MESSAGE` to the given stream.
+fn write_log_messages<D: Display>(stream: &StandardStream, messages: &[D]) {
+    let mut spec = ColorSpec::new();
+    spec.set_fg(Some(Color::Green));
+
+    let write_impl = |message: &D| {
+        let mut writer = stream.lock();
+        writer.set_color(&spec)?;
+        write!(&mut writer, "circomspect")?;
+        writer.reset()?;
+        writeln!(&mut writer, ": {message}")
+    };
+    for message in messages {
+        write_impl(message).expect("failed to write log messages")
+    }
+}
+
+/// Returns a color choice based on whether the stream is a TTY or not.
+fn color_choice(stream: atty::Stream) -> ColorChoice {
+    if atty::is(stream) {
+        ColorChoice::Always
+    } else {
+        ColorChoice::Never
+    }
+}
+
 pub struct StdoutWriter {
     verbose: bool,
     written: usize,
@@ -56,11 +255,7 @@ pub struct StdoutWriter {
 
 impl StdoutWriter {
     pub fn new(verbose: bool) -> StdoutWriter {
-        let writer = if atty::is(atty::Stream::Stdout) {
-            StandardStream::stdout(ColorChoice::Always)
-        } else {
-            StandardStream::stdout(ColorChoice::Never)
-        };
+        let writer = StandardStream::stdout(color_choice(atty::Stream::Stdout));
         StdoutWriter { verbose, written: 0, writer, filters: Vec::new() }
     }
 
@@ -69,10 +264,10 @@ impl StdoutWriter {
         self
     }
 
-    fn filter(&self, reports: &[Report]) -> ReportCollection {
+    fn filter(&self, reports: &[Report], file_library: &FileLibrary) -> ReportCollection {
         reports
             .iter()
-            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .filter(|report| self.filters.iter().all(|f| f.filter(report, file_library)))
             .cloned()
             .collect()
     }
@@ -80,25 +275,13 @@ impl StdoutWriter {
 
 impl LogWriter for StdoutWriter {
     fn write_messages<D: Display>(&mut self, messages: &[D]) {
-        let mut spec = ColorSpec::new();
-        spec.set_fg(Some(Color::Green));
-
-        let write_impl = |message: &D| {
-            let mut writer = self.writer.lock();
-            writer.set_color(&spec)?;
-            write!(&mut writer, "circomspect")?;
-            writer.reset()?;
-            writeln!(&mut writer, ": {message}")
-        };
-        for message in messages {
-            write_impl(message).expect("failed to write log messages")
-        }
+        write_log_messages(&self.writer, messages)
     }
 }
 
 impl ReportWriter for StdoutWriter {
     fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize {
-        let reports = self.filter(reports);
+        let reports = self.filter(reports, file_library);
 
         let mut config = term::Config::default();
         let mut diagnostics = Vec::new();
@@ -124,41 +307,104 @@ impl ReportWriter for StdoutWriter {
     }
 }
 
-/// A `StdoutWriter` that caches all reports.
-pub struct CachedStdoutWriter {
-    writer: StdoutWriter,
+/// A writer that caches all reports, and writes the reports passing the given
+/// filters using the wrapped writer.
+pub struct CachedWriter<W> {
+    writer: W,
     reports: ReportCollection,
+    written: ReportCollection,
+    filters: Vec<Box<dyn ReportFilter>>,
 }
 
-impl CachedStdoutWriter {
-    pub fn new(verbose: bool) -> CachedStdoutWriter {
-        CachedStdoutWriter { writer: StdoutWriter::new(verbose), reports: ReportCollection::new() }
+/// A `StdoutWriter` that caches all reports.
+pub type CachedStdoutWriter = CachedWriter<StdoutWriter>;
+
+impl<W> CachedWriter<W> {
+    pub fn new(writer: W) -> CachedWriter<W> {
+        CachedWriter {
+            writer,
+            reports: ReportCollection::new(),
+            written: ReportCollection::new(),
+            filters: Vec::new(),
+        }
     }
 
+    /// Returns all reports, including reports that did not pass the filters.
     pub fn reports(&self) -> &ReportCollection {
         &self.reports
     }
 
-    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> CachedStdoutWriter {
-        self.writer.filters.push(Box::new(filter));
+    /// Returns all reports passing the filters.
+    pub fn written_reports(&self) -> &ReportCollection {
+        &self.written
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> CachedWriter<W> {
+        self.filters.push(Box::new(filter));
         self
     }
+
+    fn filter(&self, reports: &[Report], file_library: &FileLibrary) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report, file_library)))
+            .cloned()
+            .collect()
+    }
 }
 
-impl LogWriter for CachedStdoutWriter {
+impl<W: LogWriter> LogWriter for CachedWriter<W> {
     fn write_messages<D: Display>(&mut self, messages: &[D]) {
         self.writer.write_messages(messages)
     }
 }
 
-impl ReportWriter for CachedStdoutWriter {
+impl<W: ReportWriter> ReportWriter for CachedWriter<W> {
     fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize {
         self.reports.extend(reports.iter().cloned());
-        self.writer.write_reports(reports, file_library)
+        let reports = self.filter(reports, file_library);
+        self.written.extend(reports.iter().cloned());
+        self.writer.write_reports(&reports, file_library);
+        reports.len()
     }
 
     fn reports_written(&self) -> usize {
-        self.writer.reports_written()
+        self.written.len()
+    }
+}
+
+/// A writer that writes log messages to `stderr` and discards all reports.
+/// This is used when reports are written once analysis is complete, to ensure
+/// that `stdout` only contains the generated output.
+pub struct StderrLogWriter {
+    writer: StandardStream,
+}
+
+impl StderrLogWriter {
+    pub fn new() -> StderrLogWriter {
+        StderrLogWriter { writer: StandardStream::stderr(color_choice(atty::Stream::Stderr)) }
+    }
+}
+
+impl Default for StderrLogWriter {
+    fn default() -> Self {
+        StderrLogWriter::new()
+    }
+}
+
+impl LogWriter for StderrLogWriter {
+    fn write_messages<D: Display>(&mut self, messages: &[D]) {
+        write_log_messages(&self.writer, messages)
+    }
+}
+
+impl ReportWriter for StderrLogWriter {
+    fn write_reports(&mut self, _: &[Report], _: &FileLibrary) -> usize {
+        0
+    }
+
+    fn reports_written(&self) -> usize {
+        0
     }
 }
 
@@ -179,10 +425,10 @@ impl SarifWriter {
         self
     }
 
-    fn filter(&self, reports: &[Report]) -> ReportCollection {
+    fn filter(&self, reports: &[Report], file_library: &FileLibrary) -> ReportCollection {
         reports
             .iter()
-            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .filter(|report| self.filters.iter().all(|f| f.filter(report, file_library)))
             .cloned()
             .collect()
     }
@@ -204,7 +450,7 @@ impl SarifWriter {
 
 impl ReportWriter for SarifWriter {
     fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize {
-        let reports = self.filter(reports);
+        let reports = self.filter(reports, file_library);
         match self.serialize_reports(&reports, file_library) {
             Ok(()) => {
                 info!("reports written to `{}`", self.sarif_file.display());
@@ -222,3 +468,612 @@ impl ReportWriter for SarifWriter {
         self.written
     }
 }
+
+/// A writer which records all (filtered) reports in a baseline file.
+#[derive(Default)]
+pub struct BaselineWriter {
+    baseline_file: PathBuf,
+    written: usize,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl BaselineWriter {
+    pub fn new(baseline_file: &Path) -> BaselineWriter {
+        BaselineWriter { baseline_file: baseline_file.to_owned(), ..Default::default() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> BaselineWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &[Report], file_library: &FileLibrary) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report, file_library)))
+            .cloned()
+            .collect()
+    }
+}
+
+impl BaselineWriter {
+    /// Filters the given reports and writes them to the baseline file.
+    /// Returns the written baseline.
+    pub fn write_baseline(
+        &mut self,
+        reports: &[Report],
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<Baseline> {
+        let reports = self.filter(reports, file_library);
+        let baseline = Baseline::new(&reports, file_library);
+        baseline.write(&self.baseline_file)?;
+        info!("baseline written to `{}`", self.baseline_file.display());
+        self.written += reports.len();
+        Ok(baseline)
+    }
+}
+
+impl ReportWriter for BaselineWriter {
+    fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize {
+        self.write_baseline(reports, file_library).map(|baseline| baseline.len()).unwrap_or_else(
+            |_| {
+                warn!("failed to write baseline to `{}`", self.baseline_file.display());
+                0
+            },
+        )
+    }
+
+    fn reports_written(&self) -> usize {
+        self.written
+    }
+}
+
+/// A writer that writes all reports as a single JSON array.
+pub struct JsonWriter<O: Write> {
+    output: O,
+    written: usize,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl<O: Write> JsonWriter<O> {
+    pub fn new(output: O) -> JsonWriter<O> {
+        JsonWriter { output, written: 0, filters: Vec::new() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> JsonWriter<O> {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &[Report], file_library: &FileLibrary) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report, file_library)))
+            .cloned()
+            .collect()
+    }
+
+    /// Writes the given reports as a JSON array. Reports which cannot be
+    /// converted (e.g. because a label refers to an unknown location) are
+    /// skipped, to ensure that a single report does not prevent the remaining
+    /// reports from being written. Returns the number of reports written.
+    fn serialize_reports(
+        &mut self,
+        reports: &ReportCollection,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<usize> {
+        let json = reports
+            .iter()
+            .filter_map(|report| match report.to_json(file_library) {
+                Ok(json) => Some(json),
+                Err(err) => {
+                    warn!("failed to convert report `{}` to JSON format: {err}", report.id());
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_writer_pretty(&mut self.output, &json)?;
+        writeln!(self.output)?;
+        Ok(json.len())
+    }
+}
+
+impl<O: Write> ReportWriter for JsonWriter<O> {
+    fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize {
+        let reports = self.filter(reports, file_library);
+        match self.serialize_reports(&reports, file_library) {
+            Ok(written) => {
+                self.written += written;
+                written
+            }
+            Err(_) => {
+                warn!("failed to write reports as JSON");
+                0
+            }
+        }
+    }
+
+    fn reports_written(&self) -> usize {
+        self.written
+    }
+}
+
+/// A writer that writes each report as a JSON object on a single line, as
+/// soon as the report is generated. Log messages are written to `stderr`.
+pub struct JsonLinesWriter<O: Write> {
+    output: O,
+    written: usize,
+    filters: Vec<Box<dyn ReportFilter>>,
+    log_writer: StderrLogWriter,
+}
+
+impl<O: Write> JsonLinesWriter<O> {
+    pub fn new(output: O) -> JsonLinesWriter<O> {
+        JsonLinesWriter {
+            output,
+            written: 0,
+            filters: Vec::new(),
+            log_writer: StderrLogWriter::new(),
+        }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> JsonLinesWriter<O> {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &[Report], file_library: &FileLibrary) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report, file_library)))
+            .cloned()
+            .collect()
+    }
+
+    fn serialize_report(
+        &mut self,
+        report: &Report,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<()> {
+        let json =
+            report.to_json(file_library).context("failed to convert report to JSON format")?;
+        serde_json::to_writer(&mut self.output, &json)?;
+        writeln!(self.output)?;
+        self.output.flush()?;
+        Ok(())
+    }
+}
+
+impl<O: Write> LogWriter for JsonLinesWriter<O> {
+    fn write_messages<D: Display>(&mut self, messages: &[D]) {
+        self.log_writer.write_messages(messages)
+    }
+}
+
+impl<O: Write> ReportWriter for JsonLinesWriter<O> {
+    fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize {
+        let reports = self.filter(reports, file_library);
+        let mut written = 0;
+        for report in &reports {
+            match self.serialize_report(report, file_library) {
+                Ok(()) => written += 1,
+                Err(_) => warn!("failed to write report as JSON"),
+            }
+        }
+        self.written += written;
+        written
+    }
+
+    fn reports_written(&self) -> usize {
+        self.written
+    }
+}
+
+/// A writer that writes all reports as a JUnit XML document. Each analyzed
+/// function or template corresponds to a test case, which fails if there are
+/// reports for the corresponding definition.
+pub struct JUnitWriter<O: Write> {
+    output: O,
+    written: usize,
+    test_suites: Vec<TestSuite>,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl<O: Write> JUnitWriter<O> {
+    pub fn new(output: O) -> JUnitWriter<O> {
+        JUnitWriter { output, written: 0, test_suites: Vec::new(), filters: Vec::new() }
+    }
+
+    pub fn add_test_suite(mut self, test_suite: TestSuite) -> JUnitWriter<O> {
+        self.test_suites.push(test_suite);
+        self
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> JUnitWriter<O> {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &[Report], file_library: &FileLibrary) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report, file_library)))
+            .cloned()
+            .collect()
+    }
+
+    /// Writes the given reports as a JUnit XML document. Reports which cannot
+    /// be converted are skipped. Returns the number of reports written.
+    fn serialize_reports(
+        &mut self,
+        reports: &ReportCollection,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<usize> {
+        let (xml, written) = xml_conversion::to_junit(reports, &self.test_suites, file_library);
+        self.output.write_all(xml.as_bytes())?;
+        Ok(written)
+    }
+}
+
+impl<O: Write> ReportWriter for JUnitWriter<O> {
+    fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize {
+        let reports = self.filter(reports, file_library);
+        match self.serialize_reports(&reports, file_library) {
+            Ok(written) => {
+                self.written += written;
+                written
+            }
+            Err(_) => {
+                warn!("failed to write reports as JUnit XML");
+                0
+            }
+        }
+    }
+
+    fn reports_written(&self) -> usize {
+        self.written
+    }
+}
+
+/// A writer that writes all reports as a Checkstyle XML document.
+pub struct CheckstyleWriter<O: Write> {
+    output: O,
+    written: usize,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl<O: Write> CheckstyleWriter<O> {
+    pub fn new(output: O) -> CheckstyleWriter<O> {
+        CheckstyleWriter { output, written: 0, filters: Vec::new() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> CheckstyleWriter<O> {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &[Report], file_library: &FileLibrary) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report, file_library)))
+            .cloned()
+            .collect()
+    }
+
+    /// Writes the given reports as a Checkstyle XML document. Reports which cannot
+    /// be converted are skipped. Returns the number of reports written.
+    fn serialize_reports(
+        &mut self,
+        reports: &ReportCollection,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<usize> {
+        let (xml, written) = xml_conversion::to_checkstyle(reports, file_library);
+        self.output.write_all(xml.as_bytes())?;
+        Ok(written)
+    }
+}
+
+impl<O: Write> ReportWriter for CheckstyleWriter<O> {
+    fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize {
+        let reports = self.filter(reports, file_library);
+        match self.serialize_reports(&reports, file_library) {
+            Ok(written) => {
+                self.written += written;
+                written
+            }
+            Err(_) => {
+                warn!("failed to write reports as Checkstyle XML");
+                0
+            }
+        }
+    }
+
+    fn reports_written(&self) -> usize {
+        self.written
+    }
+}
+
+/// A writer that writes each report as a GitHub Actions workflow command, as
+/// soon as the report is generated. Log messages are written to `stderr`.
+pub struct GitHubWriter<O: Write> {
+    output: O,
+    written: usize,
+    filters: Vec<Box<dyn ReportFilter>>,
+    log_writer: StderrLogWriter,
+}
+
+impl<O: Write> GitHubWriter<O> {
+    pub fn new(output: O) -> GitHubWriter<O> {
+        GitHubWriter { output, written: 0, filters: Vec::new(), log_writer: StderrLogWriter::new() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> GitHubWriter<O> {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &[Report], file_library: &FileLibrary) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report, file_library)))
+            .cloned()
+            .collect()
+    }
+
+    fn serialize_report(
+        &mut self,
+        report: &Report,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<()> {
+        let annotations = github_conversion::to_annotations(report, file_library)
+            .context("failed to convert report to GitHub workflow commands")?;
+        for annotation in annotations {
+            writeln!(self.output, "{annotation}")?;
+        }
+        self.output.flush()?;
+        Ok(())
+    }
+}
+
+impl<O: Write> LogWriter for GitHubWriter<O> {
+    fn write_messages<D: Display>(&mut self, messages: &[D]) {
+        self.log_writer.write_messages(messages)
+    }
+}
+
+impl<O: Write> ReportWriter for GitHubWriter<O> {
+    fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize {
+        let reports = self.filter(reports, file_library);
+        let mut written = 0;
+        for report in &reports {
+            match self.serialize_report(report, file_library) {
+                Ok(()) => written += 1,
+                Err(_) => warn!("failed to write report as a GitHub workflow command"),
+            }
+        }
+        self.written += written;
+        written
+    }
+
+    fn reports_written(&self) -> usize {
+        self.written
+    }
+}
+
+/// A writer that writes all reports as a self-contained HTML document.
+pub struct HtmlWriter<O: Write> {
+    output: O,
+    written: usize,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl<O: Write> HtmlWriter<O> {
+    pub fn new(output: O) -> HtmlWriter<O> {
+        HtmlWriter { output, written: 0, filters: Vec::new() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> HtmlWriter<O> {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &[Report], file_library: &FileLibrary) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report, file_library)))
+            .cloned()
+            .collect()
+    }
+
+    /// Writes the given reports as an HTML document. Reports which cannot be
+    /// converted are skipped. Returns the number of reports written.
+    fn serialize_reports(
+        &mut self,
+        reports: &ReportCollection,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<usize> {
+        let (html, written) = html_conversion::to_html(reports, file_library);
+        self.output.write_all(html.as_bytes())?;
+        Ok(written)
+    }
+}
+
+impl<O: Write> ReportWriter for HtmlWriter<O> {
+    fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize {
+        let reports = self.filter(reports, file_library);
+        match self.serialize_reports(&reports, file_library) {
+            Ok(written) => {
+                self.written += written;
+                written
+            }
+            Err(_) => {
+                warn!("failed to write reports as HTML");
+                0
+            }
+        }
+    }
+
+    fn reports_written(&self) -> usize {
+        self.written
+    }
+}
+
+/// A writer that writes each report on the compact form
+/// `path:line:column: severity[ID]: message`, suitable for editors and tools
+/// like `grep`. Log messages are written to `stderr`.
+pub struct ShortWriter<O: Write> {
+    output: O,
+    /// If true, secondary locations are written as indented notes.
+    verbose: bool,
+    written: usize,
+    filters: Vec<Box<dyn ReportFilter>>,
+    log_writer: StderrLogWriter,
+}
+
+impl<O: Write> ShortWriter<O> {
+    pub fn new(output: O, verbose: bool) -> ShortWriter<O> {
+        ShortWriter {
+            output,
+            verbose,
+            written: 0,
+            filters: Vec::new(),
+            log_writer: StderrLogWriter::new(),
+        }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> ShortWriter<O> {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &[Report], file_library: &FileLibrary) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report, file_library)))
+            .cloned()
+            .collect()
+    }
+
+    fn serialize_report(
+        &mut self,
+        report: &Report,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<()> {
+        let lines = short_conversion::to_short_format(report, file_library, self.verbose)
+            .context("failed to convert report to short format")?;
+        for line in lines {
+            writeln!(self.output, "{line}")?;
+        }
+        self.output.flush()?;
+        Ok(())
+    }
+}
+
+impl<O: Write> LogWriter for ShortWriter<O> {
+    fn write_messages<D: Display>(&mut self, messages: &[D]) {
+        self.log_writer.write_messages(messages)
+    }
+}
+
+impl<O: Write> ReportWriter for ShortWriter<O> {
+    fn write_reports(&mut self, reports: &[Report], file_library: &FileLibrary) -> usize {
+        let reports = self.filter(reports, file_library);
+        let mut written = 0;
+        for report in &reports {
+            match self.serialize_report(report, file_library) {
+                Ok(()) => written += 1,
+                Err(_) => warn!("failed to write report in short format"),
+            }
+        }
+        self.written += written;
+        written
+    }
+
+    fn reports_written(&self) -> usize {
+        self.written
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_json_writer_skips_invalid_reports() {
+        let mut file_library = FileLibrary::new();
+        let file_id =
+            file_library.add_file("test.circom".to_string(), "out <-- in;\n".to_string(), true);
+        let report = |file_id: FileID| {
+            let mut report =
+                Report::warning("A warning.".to_string(), ReportCode::SignalAssignmentStatement);
+            report.add_primary(0..3, file_id, "A label.".to_string());
+            report
+        };
+        let reports = vec![report(file_id), report(file_id + 1), report(file_id)];
+
+        let mut output = Vec::new();
+        let mut writer = JsonWriter::new(&mut output);
+        assert_eq!(writer.write_reports(&reports, &file_library), 2);
+        assert_eq!(writer.reports_written(), 2);
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_html_writer_skips_invalid_reports() {
+        let mut file_library = FileLibrary::new();
+        let file_id =
+            file_library.add_file("test.circom".to_string(), "out <-- in;\n".to_string(), true);
+        let report = |file_id: FileID, range: Range<usize>| {
+            let mut report =
+                Report::warning("A warning.".to_string(), ReportCode::SignalAssignmentStatement);
+            report.add_primary(range, file_id, "A label.".to_string());
+            report
+        };
+        let reports = vec![
+            report(file_id, 0..3),
+            report(file_id + 1, 0..3),
+            report(file_id, 8..100),
+            report(file_id, 4..7),
+        ];
+
+        let mut output = Vec::new();
+        let mut writer = HtmlWriter::new(&mut output);
+        assert_eq!(writer.write_reports(&reports, &file_library), 2);
+        assert_eq!(writer.reports_written(), 2);
+        let html = String::from_utf8(output).unwrap();
+        assert!(html.contains("<p>2 findings in 1 definition.</p>"));
+        assert!(html.contains("id=\"finding-0\"") && html.contains("id=\"finding-1\""));
+        assert!(!html.contains("id=\"finding-2\""));
+    }
+
+    #[test]
+    fn test_short_writer() {
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file(
+            "test.circom".to_string(),
+            "template T() {\n    out <-- in;\n}\n".to_string(),
+            true,
+        );
+        let mut report =
+            Report::warning("A warning.".to_string(), ReportCode::SignalAssignmentStatement);
+        report.add_primary(19..30, file_id, "A label.".to_string());
+        report.add_secondary(0..12, file_id, None);
+        let mut invalid_report = report.clone();
+        invalid_report.add_primary(0..1, file_id + 1, "Unknown file.".to_string());
+        let reports = vec![report, invalid_report];
+
+        for (verbose, expected) in [
+            (false, "test.circom:2:5: warning[CS0005]: A warning.\n"),
+            (true, "test.circom:2:5: warning[CS0005]: A warning.\n    note: test.circom:1:1\n"),
+        ] {
+            let mut output = Vec::new();
+            let mut writer = ShortWriter::new(&mut output, verbose);
+            // Reports which cannot be converted are skipped.
+            assert_eq!(writer.write_reports(&reports, &file_library), 1);
+            assert_eq!(String::from_utf8(output).unwrap(), expected);
+        }
+    }
+}
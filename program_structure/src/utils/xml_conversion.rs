@@ -0,0 +1,352 @@
+use codespan_reporting::files::{Files, Location};
+use log::{trace, warn};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::ops::Range;
+use thiserror::Error;
+
+use crate::report::{MessageCategory, Report, ReportLabel};
+use crate::file_definition::{FileID, FileLibrary};
+
+const XML_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+const CHECKSTYLE_VERSION: &str = "4.3";
+const TEST_SUITES_NAME: &str = "circomspect";
+
+/// A group of analyzed definitions (e.g. all templates) reported as a single
+/// JUnit test suite.
+#[derive(Clone, Debug, Default)]
+pub struct TestSuite {
+    name: String,
+    test_cases: Vec<String>,
+}
+
+impl TestSuite {
+    pub fn new(name: &str, test_cases: &[String]) -> TestSuite {
+        TestSuite { name: name.to_string(), test_cases: test_cases.to_vec() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn test_cases(&self) -> &[String] {
+        &self.test_cases
+    }
+}
+
+/// Converts the given reports to a Checkstyle XML document. Each primary
+/// location of a report is emitted as a separate `<error>` element. Reports
+/// with locations which cannot be resolved are skipped. Returns the document
+/// together with the number of reports included in the document.
+pub fn to_checkstyle(reports: &[Report], files: &FileLibrary) -> (String, usize) {
+    trace!("converting report collection to Checkstyle format");
+    // Group errors by file name to ensure that each file is listed once.
+    let mut errors: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut included = 0;
+    for report in reports {
+        let labels = match report
+            .primary()
+            .iter()
+            .map(|label| resolve_label(label, files))
+            .collect::<XmlResult<Vec<_>>>()
+        {
+            Ok(labels) => labels,
+            Err(err) => {
+                warn!("failed to convert report `{}` to Checkstyle format: {err}", report.id());
+                continue;
+            }
+        };
+        for (file_name, start) in labels {
+            errors.entry(file_name).or_default().push(format!(
+                r#"<error line="{}" column="{}" severity="{}" message="{}" source="{}"/>"#,
+                start.line_number,
+                start.column_number,
+                to_checkstyle_severity(report.category()),
+                escape(report.message()),
+                escape(&report.id())
+            ));
+        }
+        included += 1;
+    }
+    let mut xml = format!("{XML_HEADER}\n<checkstyle version=\"{CHECKSTYLE_VERSION}\">\n");
+    for (file_name, errors) in errors {
+        let _ = writeln!(xml, "  <file name=\"{}\">", escape(&file_name));
+        for error in errors {
+            let _ = writeln!(xml, "    {error}");
+        }
+        xml.push_str("  </file>\n");
+    }
+    xml.push_str("</checkstyle>\n");
+    (xml, included)
+}
+
+/// Converts the given reports to a JUnit XML document. Each analyzed
+/// definition is emitted as a test case which fails if there are reports for
+/// the definition. Reports which are not associated with a definition are
+/// emitted as failing test cases named after the corresponding file. Reports
+/// with locations which cannot be resolved are skipped. Returns the document
+/// together with the number of reports included in the document.
+pub fn to_junit(
+    reports: &[Report],
+    test_suites: &[TestSuite],
+    files: &FileLibrary,
+) -> (String, usize) {
+    trace!("converting report collection to JUnit format");
+    // Group reports by definition name (or file name, if the report is not
+    // associated with a definition).
+    let mut by_definition: BTreeMap<&str, Vec<Failure>> = BTreeMap::new();
+    let mut by_file: BTreeMap<String, Vec<Failure>> = BTreeMap::new();
+    let mut included = 0;
+    for report in reports {
+        let failure = match Failure::new(report, files) {
+            Ok(failure) => failure,
+            Err(err) => {
+                warn!("failed to convert report `{}` to JUnit format: {err}", report.id());
+                continue;
+            }
+        };
+        match report.definition_name() {
+            Some(name) => by_definition.entry(name).or_default().push(failure),
+            None => by_file.entry(failure.file_name.clone()).or_default().push(failure),
+        }
+        included += 1;
+    }
+    let mut suites = test_suites
+        .iter()
+        .map(|suite| {
+            let test_cases = suite
+                .test_cases()
+                .iter()
+                .map(|name| {
+                    let reports = by_definition.remove(name.as_str()).unwrap_or_default();
+                    (name.clone(), reports)
+                })
+                .collect::<Vec<_>>();
+            (suite.name().to_string(), test_cases)
+        })
+        .collect::<Vec<_>>();
+    // Reports for definitions that were not listed are added to a separate
+    // test suite to ensure that no reports are dropped.
+    let other_cases = by_definition
+        .into_iter()
+        .map(|(name, reports)| (name.to_string(), reports))
+        .chain(by_file)
+        .collect::<Vec<_>>();
+    if !other_cases.is_empty() {
+        suites.push(("other".to_string(), other_cases));
+    }
+
+    let total_tests: usize = suites.iter().map(|(_, cases)| cases.len()).sum();
+    let total_failures: usize = suites
+        .iter()
+        .map(|(_, cases)| cases.iter().filter(|(_, reports)| !reports.is_empty()).count())
+        .sum();
+    let mut xml = format!("{XML_HEADER}\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"{TEST_SUITES_NAME}\" tests=\"{total_tests}\" failures=\"{total_failures}\">"
+    );
+    for (suite_name, test_cases) in &suites {
+        let failures = test_cases.iter().filter(|(_, reports)| !reports.is_empty()).count();
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\">",
+            escape(suite_name),
+            test_cases.len()
+        );
+        for (name, reports) in test_cases {
+            let _ = write!(
+                xml,
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                escape(suite_name),
+                escape(name)
+            );
+            if reports.is_empty() {
+                xml.push_str("/>\n");
+                continue;
+            }
+            xml.push_str(">\n");
+            for failure in reports {
+                let _ = writeln!(
+                    xml,
+                    "      <failure type=\"{}\" message=\"{}\">{}</failure>",
+                    escape(&failure.report.id()),
+                    escape(failure.report.message()),
+                    escape(&failure.details)
+                );
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    (xml, included)
+}
+
+/// A report emitted as a JUnit test case failure.
+struct Failure<'a> {
+    report: &'a Report,
+    /// The name of the file containing the first primary location.
+    file_name: String,
+    /// A textual description of the report locations and notes.
+    details: String,
+}
+
+impl<'a> Failure<'a> {
+    fn new(report: &'a Report, files: &FileLibrary) -> XmlResult<Failure<'a>> {
+        let mut file_name = None;
+        let mut details = format!("{}: {} [{}]", report.category(), report.message(), report.id());
+        for label in report.primary().iter().chain(report.secondary().iter()) {
+            let (label_file_name, start) = resolve_label(label, files)?;
+            let _ = write!(
+                details,
+                "\n  at {label_file_name}:{}:{}",
+                start.line_number, start.column_number
+            );
+            if !label.message.is_empty() {
+                let _ = write!(details, ": {}", label.message);
+            }
+            file_name.get_or_insert(label_file_name);
+        }
+        for note in report.notes() {
+            let _ = write!(details, "\n  note: {note}");
+        }
+        Ok(Failure { report, file_name: file_name.unwrap_or_default(), details })
+    }
+}
+
+/// Returns the file name and start location of the given label.
+fn resolve_label(label: &ReportLabel, files: &FileLibrary) -> XmlResult<(String, Location)> {
+    let storage = files.to_storage();
+    let file = storage.get(label.file_id).map_err(|_| XmlError::UnknownFile(label.file_id))?;
+    // Locations past the end of the file are not rejected by the file storage.
+    if label.range.start > label.range.end || label.range.end > file.source().len() {
+        return Err(XmlError::UnknownLocation(label.file_id, label.range.clone()));
+    }
+    let start = storage
+        .location(label.file_id, label.range.start)
+        .map_err(|_| XmlError::UnknownLocation(label.file_id, label.range.clone()))?;
+    Ok((file.name().to_string(), start))
+}
+
+fn to_checkstyle_severity(category: &MessageCategory) -> &'static str {
+    use MessageCategory::*;
+    match category {
+        Error => "error",
+        Warning => "warning",
+        Info => "info",
+    }
+}
+
+/// Escapes the XML special characters in the given text.
+pub(crate) fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[derive(Error, Debug)]
+pub enum XmlError {
+    #[error("unknown location `{}:{}` in file with ID `{0}`", .1.start, .1.end)]
+    UnknownLocation(FileID, Range<usize>),
+    #[error("unknown file ID `{0}`")]
+    UnknownFile(FileID),
+}
+
+type XmlResult<T> = Result<T, XmlError>;
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    fn setup() -> (FileLibrary, Vec<Report>) {
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file(
+            "test.circom".to_string(),
+            "template T() {\n    out <-- in;\n}\n".to_string(),
+            true,
+        );
+        let mut report = Report::warning(
+            "Using the signal assignment operator `<--` is <dangerous>.".to_string(),
+            ReportCode::SignalAssignmentStatement,
+        );
+        report.add_primary(19..30, file_id, "The signal `out` is assigned here.".to_string());
+        report.set_definition_name("T");
+        (file_library, vec![report])
+    }
+
+    #[test]
+    fn test_to_checkstyle() {
+        let (file_library, reports) = setup();
+        let (xml, included) = to_checkstyle(&reports, &file_library);
+        assert_eq!(included, 1);
+        assert!(xml.contains("<file name=\"test.circom\">"));
+        assert!(xml.contains(
+            "<error line=\"2\" column=\"5\" severity=\"warning\" message=\"Using the signal \
+             assignment operator `&lt;--` is &lt;dangerous&gt;.\" source=\"CS0005\"/>"
+        ));
+    }
+
+    #[test]
+    fn test_to_junit() {
+        let (file_library, reports) = setup();
+        let test_suites = [
+            TestSuite::new("templates", &["S".to_string(), "T".to_string()]),
+            TestSuite::new("functions", &[]),
+        ];
+        let (xml, included) = to_junit(&reports, &test_suites, &file_library);
+        assert_eq!(included, 1);
+        assert!(xml.contains("<testsuites name=\"circomspect\" tests=\"2\" failures=\"1\">"));
+        assert!(xml.contains("<testsuite name=\"templates\" tests=\"2\" failures=\"1\">"));
+        assert!(xml.contains("<testcase classname=\"templates\" name=\"S\"/>"));
+        assert!(xml.contains("<testcase classname=\"templates\" name=\"T\">"));
+        assert!(xml.contains("<failure type=\"CS0005\""));
+        assert!(xml.contains("at test.circom:2:5: The signal `out` is assigned here."));
+        assert!(xml.contains("<testsuite name=\"functions\" tests=\"0\" failures=\"0\">"));
+    }
+
+    #[test]
+    fn test_skip_invalid_reports() {
+        let (file_library, mut reports) = setup();
+        // A report with an unknown file ID, and a report with a range past
+        // the end of the file.
+        let mut report = reports[0].clone();
+        report.add_primary(0..1, 1, "An unknown file.".to_string());
+        reports.insert(0, report);
+        let mut report =
+            Report::warning("Out of range.".to_string(), ReportCode::UnusedVariableValue);
+        report.add_primary(100..101, 0, "Past the end of the file.".to_string());
+        reports.push(report);
+
+        let (xml, included) = to_checkstyle(&reports, &file_library);
+        assert_eq!(included, 1);
+        assert_eq!(xml.matches("<error ").count(), 1);
+        assert!(xml.contains("source=\"CS0005\""));
+
+        let test_suites = [TestSuite::new("templates", &["T".to_string()])];
+        let (xml, included) = to_junit(&reports, &test_suites, &file_library);
+        assert_eq!(included, 1);
+        assert!(xml.contains("<testsuites name=\"circomspect\" tests=\"1\" failures=\"1\">"));
+        assert_eq!(xml.matches("<failure ").count(), 1);
+        assert!(!xml.contains("Out of range."));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("a < b && c > \"d\" 'e'"),
+            "a &lt; b &amp;&amp; c &gt; &quot;d&quot; &apos;e&apos;"
+        );
+    }
+}
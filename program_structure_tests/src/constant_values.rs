@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use parser::parse_definition;
+use program_structure::cfg::IntoCfg;
+use program_structure::constants::Curve;
+use program_structure::report::ReportCollection;
+use program_structure::ir::Statement;
+
+#[test]
+fn test_values_from_inline_arrays() {
+    let src = r#"
+        function f(n) {
+            var t[2][2] = [[1, 2], [3, 4]];
+            var x = t[1][0];
+            var y = t[0];
+            var z = t[n][0];
+            return x;
+        }
+    "#;
+    validate_values(
+        src,
+        &[
+            ("t.0", Some("[[1, 2], [3, 4]]")),
+            ("x.0", Some("3")),
+            ("y.0", Some("[1, 2]")),
+            ("z.0", None),
+        ],
+    );
+}
+
+#[test]
+fn test_values_from_updates() {
+    let src = r#"
+        function f(n) {
+            var t[3];
+            t[0] = 1;
+            t[2] = t[0] + 1;
+            var x = t[2];
+            var u[3];
+            u[n] = 1;
+            var y = u[0];
+            return x;
+        }
+    "#;
+    validate_values(
+        src,
+        &[
+            ("t.1", Some("[1, 0, 0]")),
+            ("t.2", Some("[1, 0, 2]")),
+            ("x.0", Some("2")),
+            ("u.1", None),
+            ("y.0", None),
+        ],
+    );
+}
+
+#[test]
+fn test_values_from_phi_expressions() {
+    let src = r#"
+        function f(n) {
+            var t[2] = [1, 2];
+            if (n == 0) {
+                t[0] = 1;
+            }
+            var x = t[1];
+            if (n == 1) {
+                t[1] = 3;
+            }
+            var y = t[1];
+            return x + y;
+        }
+    "#;
+    validate_values(src, &[("x.0", Some("2")), ("y.0", None)]);
+}
+
+fn validate_values(src: &str, expected: &[(&str, Option<&str>)]) {
+    let mut reports = ReportCollection::new();
+    let cfg = parse_definition(src)
+        .unwrap()
+        .into_cfg(&Curve::default(), &mut reports)
+        .unwrap()
+        .into_ssa()
+        .unwrap();
+    assert!(reports.is_empty());
+
+    // The value of each assigned variable is tracked by the statement metadata.
+    let values = cfg
+        .iter()
+        .flat_map(|basic_block| basic_block.iter())
+        .filter_map(|stmt| match stmt {
+            Statement::Substitution { meta, var, .. } => Some((
+                format!("{var:?}"),
+                meta.value_knowledge().get_reduces_to().map(ToString::to_string),
+            )),
+            _ => None,
+        })
+        .collect::<HashMap<_, _>>();
+    for (name, value) in expected {
+        assert_eq!(
+            values.get(*name),
+            Some(&value.map(ToString::to_string)),
+            "unexpected value for `{name}`"
+        );
+    }
+}
@@ -1,5 +1,11 @@
+#[cfg(test)]
+mod constant_values;
+
 #[cfg(test)]
 mod control_flow_graph;
 
 #[cfg(test)]
 mod static_single_assignment;
+
+#[cfg(test)]
+mod value_ranges;
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use parser::parse_definition;
+use program_structure::cfg::IntoCfg;
+use program_structure::constants::Curve;
+use program_structure::report::ReportCollection;
+use program_structure::ir::range_meta::ValueRange;
+use program_structure::ir::Statement;
+
+#[test]
+fn test_ranges_from_loop() {
+    let src = r#"
+        function f(n) {
+            var s = 0;
+            for (var i = 0; i < 10; i++) {
+                s += i;
+            }
+            var j = 0;
+            while (j <= n) {
+                j += 2;
+            }
+            return s;
+        }
+    "#;
+    validate_ranges(
+        src,
+        &[
+            ("i.0", Some((0, 0))),
+            ("i.1", Some((0, 10))),
+            ("i.2", Some((1, 11))),
+            ("s.2", None),
+            ("j.1", None),
+        ],
+    );
+}
+
+#[test]
+fn test_ranges_from_constraints() {
+    let src = r#"
+        template T() {
+            signal input x;
+            signal input y;
+            signal input z;
+            signal input {binary} w;
+            signal output {maxbit} out;
+            signal a;
+            signal b;
+            signal c;
+
+            component n2b = Num2Bits(8);
+            n2b.in <== x;
+            component lt = LessThan(8);
+            lt.in[0] <== x;
+            lt.in[1] <== y;
+            y * (y - 1) === 0;
+            z * z === z;
+
+            a <== x * 2 + y;
+            b <-- x * 2;
+            c <== lt.out + w;
+            out.maxbit = 4;
+            out <== x;
+        }
+    "#;
+    validate_ranges(
+        src,
+        &[("a", Some((0, 511))), ("b", None), ("c", Some((0, 2))), ("out", Some((0, 15)))],
+    );
+}
+
+fn validate_ranges(src: &str, expected: &[(&str, Option<(i64, i64)>)]) {
+    let mut reports = ReportCollection::new();
+    let cfg = parse_definition(src)
+        .unwrap()
+        .into_cfg(&Curve::default(), &mut reports)
+        .unwrap()
+        .into_ssa()
+        .unwrap();
+    assert!(reports.is_empty());
+
+    // The range of each assigned variable is tracked by the statement metadata.
+    let ranges = cfg
+        .iter()
+        .flat_map(|basic_block| basic_block.iter())
+        .filter_map(|stmt| match stmt {
+            Statement::Substitution { meta, var, .. } => {
+                Some((format!("{var:?}"), meta.range_knowledge().range().cloned()))
+            }
+            _ => None,
+        })
+        .collect::<HashMap<_, _>>();
+    for (name, range) in expected {
+        let range = match range {
+            Some((lower, upper)) => ValueRange::new((*lower).into(), (*upper).into()),
+            None => ValueRange::unbounded(),
+        };
+        assert_eq!(ranges.get(*name), Some(&Some(range)), "unexpected range for `{name}`");
+    }
+}